@@ -0,0 +1,39 @@
+CREATE TABLE IF NOT EXISTS tickets (
+	user_id BIGINT PRIMARY KEY,
+	dm_channel_id BIGINT NOT NULL,
+	thread_id BIGINT NOT NULL,
+	is_open BOOLEAN NOT NULL DEFAULT FALSE,
+	blocked BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS ticket_dm_channels ON tickets (dm_channel_id);
+CREATE UNIQUE INDEX IF NOT EXISTS ticket_threads ON tickets (thread_id);
+
+CREATE TABLE IF NOT EXISTS messages (
+	id BIGSERIAL PRIMARY KEY,
+	user_id BIGINT NOT NULL REFERENCES tickets (user_id) ON DELETE CASCADE,
+	dm_msg_id BIGINT NOT NULL,
+	thread_msg_id BIGINT NOT NULL,
+	thread_update_msg_id BIGINT DEFAULT NULL,
+	author_id BIGINT DEFAULT NULL,
+	content TEXT DEFAULT NULL,
+	created_at BIGINT DEFAULT NULL,
+	edited_at BIGINT DEFAULT NULL,
+	deleted_at BIGINT DEFAULT NULL
+);
+
+CREATE INDEX IF NOT EXISTS message_users ON messages (user_id);
+CREATE INDEX IF NOT EXISTS message_dm_msgs ON messages (dm_msg_id);
+CREATE INDEX IF NOT EXISTS message_thread_msgs ON messages (thread_msg_id);
+
+-- previous versions of a message's content, the current version lives in `messages`
+CREATE TABLE IF NOT EXISTS message_revisions (
+	id BIGSERIAL PRIMARY KEY,
+	user_id BIGINT NOT NULL REFERENCES tickets (user_id) ON DELETE CASCADE,
+	dm_msg_id BIGINT NOT NULL,
+	thread_msg_id BIGINT NOT NULL,
+	content TEXT NOT NULL,
+	created_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS message_revision_msgs ON message_revisions (user_id, dm_msg_id);
@@ -0,0 +1,2 @@
+-- the server the ticket was opened from, NULL for tickets from before there could be more than one
+ALTER TABLE tickets ADD COLUMN guild_id BIGINT DEFAULT NULL;
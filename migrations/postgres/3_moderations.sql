@@ -0,0 +1,13 @@
+-- warnings, timeouts, kicks, and bans, whether they were taken through the bot or not
+CREATE TABLE IF NOT EXISTS moderations (
+	id BIGSERIAL PRIMARY KEY,
+	user_id BIGINT NOT NULL,
+	guild_id BIGINT NOT NULL,
+	staff_id BIGINT NOT NULL,
+	kind TEXT NOT NULL,
+	reason TEXT DEFAULT NULL,
+	expires_at BIGINT DEFAULT NULL,
+	created_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS moderation_users ON moderations (user_id);
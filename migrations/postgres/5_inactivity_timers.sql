@@ -0,0 +1,7 @@
+-- when each open ticket last had a message, for closing inactive tickets
+CREATE TABLE IF NOT EXISTS inactivity_timers (
+	user_id BIGINT PRIMARY KEY,
+	last_message_at BIGINT NOT NULL,
+	staff_spoke_last BOOLEAN NOT NULL,
+	warned_at BIGINT DEFAULT NULL
+);
@@ -0,0 +1,12 @@
+-- how users rated their tickets after they were closed
+CREATE TABLE IF NOT EXISTS survey_responses (
+	thread_id BIGINT NOT NULL,
+	closed_at BIGINT NOT NULL,
+	user_id BIGINT NOT NULL,
+	guild_id BIGINT NOT NULL,
+	staff_id BIGINT DEFAULT NULL,
+	rating INTEGER NOT NULL CHECK(rating BETWEEN 1 AND 5),
+	comment TEXT DEFAULT NULL,
+	created_at BIGINT NOT NULL,
+	PRIMARY KEY (thread_id, closed_at)
+);
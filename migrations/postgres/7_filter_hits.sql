@@ -0,0 +1,13 @@
+-- DMs that matched content filters, kept even if they weren't relayed
+CREATE TABLE IF NOT EXISTS filter_hits (
+	id BIGSERIAL PRIMARY KEY,
+	user_id BIGINT NOT NULL,
+	dm_msg_id BIGINT NOT NULL,
+	filter TEXT NOT NULL,
+	action TEXT NOT NULL,
+	matched TEXT NOT NULL,
+	content TEXT NOT NULL,
+	created_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS filter_hit_users ON filter_hits (user_id);
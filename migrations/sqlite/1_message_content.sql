@@ -0,0 +1,21 @@
+-- content is NULL for messages relayed before this migration
+ALTER TABLE messages ADD COLUMN author_id INTEGER DEFAULT NULL;
+ALTER TABLE messages ADD COLUMN content TEXT DEFAULT NULL;
+ALTER TABLE messages ADD COLUMN created_at INTEGER DEFAULT NULL;
+ALTER TABLE messages ADD COLUMN edited_at INTEGER DEFAULT NULL;
+ALTER TABLE messages ADD COLUMN deleted_at INTEGER DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS message_dm_msgs ON messages (dm_msg_id);
+CREATE INDEX IF NOT EXISTS message_thread_msgs ON messages (thread_msg_id);
+
+-- previous versions of a message's content, the current version lives in `messages`
+CREATE TABLE IF NOT EXISTS message_revisions (
+	user_id INTEGER NOT NULL,
+	dm_msg_id INTEGER NOT NULL,
+	thread_msg_id INTEGER NOT NULL,
+	content TEXT NOT NULL,
+	created_at INTEGER NOT NULL,
+	FOREIGN KEY (user_id) REFERENCES tickets (user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS message_revision_msgs ON message_revisions (user_id, dm_msg_id);
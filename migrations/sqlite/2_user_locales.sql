@@ -0,0 +1,5 @@
+-- the locale each user's discord client last reported, for translating dms
+CREATE TABLE IF NOT EXISTS user_locales (
+	user_id INTEGER PRIMARY KEY,
+	locale TEXT NOT NULL
+);
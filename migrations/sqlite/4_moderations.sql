@@ -0,0 +1,12 @@
+-- warnings, timeouts, kicks, and bans, whether they were taken through the bot or not
+CREATE TABLE IF NOT EXISTS moderations (
+	user_id INTEGER NOT NULL,
+	guild_id INTEGER NOT NULL,
+	staff_id INTEGER NOT NULL,
+	kind TEXT NOT NULL,
+	reason TEXT DEFAULT NULL,
+	expires_at INTEGER DEFAULT NULL,
+	created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS moderation_users ON moderations (user_id);
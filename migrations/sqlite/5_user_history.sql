@@ -0,0 +1,19 @@
+-- tickets being opened, closed, blocked, and unblocked, kept even after the ticket is gone
+CREATE TABLE IF NOT EXISTS ticket_events (
+	user_id INTEGER NOT NULL,
+	kind TEXT NOT NULL,
+	staff_id INTEGER DEFAULT NULL,
+	reason TEXT DEFAULT NULL,
+	created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS ticket_event_users ON ticket_events (user_id);
+
+CREATE TABLE IF NOT EXISTS staff_notes (
+	user_id INTEGER NOT NULL,
+	staff_id INTEGER NOT NULL,
+	content TEXT NOT NULL,
+	created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS staff_note_users ON staff_notes (user_id);
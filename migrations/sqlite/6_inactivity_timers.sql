@@ -0,0 +1,7 @@
+-- when each open ticket last had a message, for closing inactive tickets
+CREATE TABLE IF NOT EXISTS inactivity_timers (
+	user_id INTEGER PRIMARY KEY,
+	last_message_at INTEGER NOT NULL,
+	staff_spoke_last BOOLEAN NOT NULL CHECK(staff_spoke_last IN (0, 1)),
+	warned_at INTEGER DEFAULT NULL
+);
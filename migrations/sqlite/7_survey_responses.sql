@@ -0,0 +1,12 @@
+-- how users rated their tickets after they were closed
+CREATE TABLE IF NOT EXISTS survey_responses (
+	thread_id INTEGER NOT NULL,
+	closed_at INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	guild_id INTEGER NOT NULL,
+	staff_id INTEGER DEFAULT NULL,
+	rating INTEGER NOT NULL CHECK(rating BETWEEN 1 AND 5),
+	comment TEXT DEFAULT NULL,
+	created_at INTEGER NOT NULL,
+	PRIMARY KEY (thread_id, closed_at)
+);
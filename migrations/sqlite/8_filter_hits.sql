@@ -0,0 +1,12 @@
+-- DMs that matched content filters, kept even if they weren't relayed
+CREATE TABLE IF NOT EXISTS filter_hits (
+	user_id INTEGER NOT NULL,
+	dm_msg_id INTEGER NOT NULL,
+	filter TEXT NOT NULL,
+	action TEXT NOT NULL,
+	matched TEXT NOT NULL,
+	content TEXT NOT NULL,
+	created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS filter_hit_users ON filter_hits (user_id);
@@ -0,0 +1,105 @@
+use super::Bot;
+use crate::{
+	config::{AttachmentDecision, Vars},
+	database::Ticket,
+	scanner::{self, ScanResult, Scanner, MAX_SCAN_SIZE, SCAN_TIMEOUT},
+	util::formatting,
+};
+use hyper::body::HttpBody as _;
+use twilight::model::channel::{Attachment, Message};
+
+impl Bot {
+	/// Links to a DM's attachments in the thread, saying what the attachment policy decided.
+	///
+	/// Attachments that the policy denies or the scanner doesn't trust aren't linked, so staff
+	/// can't open them by accident.
+	pub async fn relay_attachments(&self, ticket: &Ticket, dm_msg: &Message) -> anyhow::Result<()> {
+		let live_config = self.config.live();
+		let policy = &live_config.attachments;
+		let scanner = policy.scanner.as_ref().map(scanner::connect);
+
+		let mut lines = Vec::with_capacity(dm_msg.attachments.len());
+		for attachment in &dm_msg.attachments {
+			let name = formatting::escape_markdown(&attachment.filename);
+			let link = match policy.check(attachment) {
+				AttachmentDecision::Allowed => attachment.url.clone(),
+				AttachmentDecision::Spoilered => format!("||{}||", attachment.url),
+				AttachmentDecision::Denied(reason) => {
+					lines.push(format!("🚫 {name} wasn't relayed: {reason}"));
+					continue;
+				},
+			};
+
+			let Some(scanner) = &scanner else {
+				lines.push(link);
+				continue;
+			};
+			match self.scan_attachment(&**scanner, attachment).await {
+				Ok(ScanResult::Clean) => lines.push(format!("{link} (✅ scanned)")),
+				Ok(ScanResult::Infected(found)) => {
+					warn!(
+						"quarantined {} from {}: {found}",
+						attachment.filename, dm_msg.author.id
+					);
+					lines.push(format!(
+						"☣️ {name} was quarantined: the scanner found {}",
+						formatting::escape_markdown(&found)
+					));
+				},
+				Err(err) => {
+					error!(?err, "unable to scan {}", attachment.filename);
+					lines.push(format!("☣️ {name} was quarantined: it couldn't be scanned"));
+				},
+			}
+		}
+
+		let server = self.config.ticket_server(ticket.guild_id);
+		let locale = self.guild_locale(server.forum_guild_id);
+		let attachments_msg = live_config
+			.messages(locale.as_deref())
+			.attachments
+			.render(&Vars {
+				attachments: Some(lines.join("\n")),
+				..self.ticket_vars(ticket)
+			});
+		self.http
+			.create_message(ticket.thread_id)
+			.content(&attachments_msg.content)?
+			.embeds(&attachments_msg.embeds)?
+			.await?;
+
+		Ok(())
+	}
+
+	/// Downloads an attachment and scans it, giving up if it takes too long.
+	async fn scan_attachment(
+		&self,
+		scanner: &dyn Scanner,
+		attachment: &Attachment,
+	) -> anyhow::Result<ScanResult> {
+		if attachment.size > MAX_SCAN_SIZE as u64 {
+			bail!("attachment is too big to scan ({} bytes)", attachment.size);
+		}
+
+		tokio::time::timeout(SCAN_TIMEOUT, async {
+			let response = self.web.get(attachment.url.parse()?).await?;
+			if !response.status().is_success() {
+				bail!("unable to download attachment: {}", response.status());
+			}
+
+			// discord's size could be wrong, so don't trust it while downloading either
+			let mut body = response.into_body();
+			let mut data = Vec::with_capacity(attachment.size as usize);
+			while let Some(chunk) = body.data().await {
+				let chunk = chunk?;
+				if data.len() + chunk.len() > MAX_SCAN_SIZE {
+					bail!("attachment is too big to scan");
+				}
+				data.extend_from_slice(&chunk);
+			}
+			scanner.scan(&data).await
+		})
+		.await
+		.map_err(|_| anyhow!("scanning took longer than {SCAN_TIMEOUT:?}"))?
+	}
+}
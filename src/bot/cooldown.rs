@@ -0,0 +1,275 @@
+use super::Bot;
+use crate::{
+	config::{CooldownAction, Vars},
+	database::{Ticket, TicketEventKind},
+};
+use anyhow::Context;
+use std::time::{SystemTime, UNIX_EPOCH};
+use twilight::{
+	model::{
+		application::interaction::{
+			message_component::MessageComponentInteractionData, Interaction,
+		},
+		channel::message::{
+			component::{ActionRow, Button, ButtonStyle},
+			Component, Message,
+		},
+		http::interaction::{InteractionResponse, InteractionResponseType},
+	},
+	util::builder::InteractionResponseDataBuilder,
+	validate::component::COMPONENT_BUTTON_LABEL_LENGTH,
+};
+
+pub const REOPEN_ID: &str = "reopen";
+
+/// DMs sent during the cooldown after a ticket was closed.
+pub struct HeldDms {
+	/// When the cooldown ends. The DMs are dropped after this if the user didn't reopen the
+	/// ticket, since they're told to send another message instead.
+	ends_at: i64,
+	messages: Vec<Message>,
+}
+
+impl HeldDms {
+	fn is_expired(&self, now: i64) -> bool {
+		now >= self.ends_at
+	}
+
+	/// The DMs to relay when the ticket is reopened at `now`.
+	fn into_unexpired(self, now: i64) -> Vec<Message> {
+		if self.is_expired(now) {
+			Vec::new()
+		} else {
+			self.messages
+		}
+	}
+}
+
+impl Bot {
+	/// When the cooldown after a closed ticket ends, if it hasn't already.
+	pub async fn close_cooldown_end(&self, ticket: &Ticket) -> anyhow::Result<Option<i64>> {
+		let minutes = self.config.live().close_cooldown.minutes;
+		if ticket.is_open || minutes == 0 {
+			return Ok(None);
+		}
+
+		let closed_at = self
+			.db
+			.ticket_events(ticket.user_id)
+			.await?
+			.into_iter()
+			.find(|event| event.kind == TicketEventKind::Close)
+			.map(|event| event.created_at);
+		let Some(closed_at) = closed_at else {
+			return Ok(None);
+		};
+
+		let ends_at = closed_at + i64::from(minutes) * 60;
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+		Ok((now < ends_at).then_some(ends_at))
+	}
+
+	/// Handles a DM sent during the cooldown after a ticket was closed, without reopening it.
+	pub async fn handle_cooldown_dm(
+		&self,
+		ticket: &Ticket,
+		dm_msg: Message,
+		ends_at: i64,
+	) -> anyhow::Result<()> {
+		match self.config.live().close_cooldown.action {
+			CooldownAction::Append => {
+				self.relay_dm(ticket, dm_msg).await?;
+
+				// sending a message unarchives the thread
+				if let Err(err) = self
+					.http
+					.update_thread(ticket.thread_id)
+					.archived(true)
+					.await
+				{
+					error!(?err);
+				}
+			},
+
+			CooldownAction::Prompt => {
+				// the user is only asked once, no matter how many messages they send
+				let is_first = {
+					let mut held = self.held_dms.entry(ticket.user_id).or_insert(HeldDms {
+						ends_at,
+						messages: Vec::new(),
+					});
+					held.messages.push(dm_msg);
+					held.messages.len() == 1
+				};
+				if !is_first {
+					return Ok(());
+				}
+
+				let server = self.config.ticket_server(ticket.guild_id);
+				let locale = self.user_locale(ticket.user_id, server.guild_id).await?;
+				let live_config = self.config.live();
+				let messages = live_config.messages(locale.as_deref());
+				let vars = Vars {
+					expires: Some(ends_at),
+					..self.ticket_vars(ticket)
+				};
+				let prompt_msg = messages.reopen_prompt.render(&vars);
+				let button = Component::ActionRow(ActionRow {
+					components: vec![Component::Button(Button {
+						custom_id: Some(REOPEN_ID.to_owned()),
+						disabled: false,
+						emoji: None,
+						label: Some(
+							messages
+								.reopen_button
+								.render_text(&vars, COMPONENT_BUTTON_LABEL_LENGTH),
+						),
+						style: ButtonStyle::Primary,
+						url: None,
+					})],
+				});
+
+				self.http
+					.create_message(ticket.dm_channel_id)
+					.content(&prompt_msg.content)?
+					.embeds(&prompt_msg.embeds)?
+					.components(&[button])?
+					.await?;
+			},
+		}
+
+		Ok(())
+	}
+
+	/// Reopens a user's ticket when they press the button on the reopen prompt.
+	pub async fn reopen_from_dm(
+		&self,
+		interaction: Interaction,
+		_: MessageComponentInteractionData,
+	) -> anyhow::Result<()> {
+		let user_id = interaction.author_id().context("missing author")?;
+		let Some(mut ticket) = self.db.ticket_by_user(user_id).await? else {
+			return Ok(());
+		};
+
+		// the button is left behind if the ticket was reopened some other way, and blocked users
+		// aren't told that they're blocked
+		if ticket.is_open || ticket.blocked {
+			if ticket.blocked {
+				self.held_dms.remove(&user_id);
+			}
+			self.interact()
+				.create_response(
+					interaction.id,
+					&interaction.token,
+					&InteractionResponse {
+						kind: InteractionResponseType::UpdateMessage,
+						data: Some(InteractionResponseDataBuilder::new().components([]).build()),
+					},
+				)
+				.await?;
+			return Ok(());
+		}
+
+		let server = self.config.ticket_server(ticket.guild_id);
+		let reopened_msg = self
+			.config
+			.live()
+			.messages(interaction.locale.as_deref())
+			.reopened
+			.render(&self.user_vars(user_id, server.guild_id));
+		self.interact()
+			.create_response(
+				interaction.id,
+				&interaction.token,
+				&InteractionResponse {
+					kind: InteractionResponseType::UpdateMessage,
+					data: Some(
+						InteractionResponseDataBuilder::new()
+							.content(reopened_msg.content)
+							.embeds(reopened_msg.embeds)
+							.components([])
+							.build(),
+					),
+				},
+			)
+			.await?;
+
+		self.open_ticket(&mut ticket, true, None).await?;
+		self.relay_held_dms(&ticket).await
+	}
+
+	/// Relays the DMs that a user sent during the cooldown, now that their ticket is open.
+	pub async fn relay_held_dms(&self, ticket: &Ticket) -> anyhow::Result<()> {
+		let Some((_, held)) = self.held_dms.remove(&ticket.user_id) else {
+			return Ok(());
+		};
+		// the cleanup might not have gotten to them yet
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+		for dm_msg in held.into_unexpired(now) {
+			self.relay_dm(ticket, dm_msg).await?;
+		}
+		Ok(())
+	}
+
+	/// Drops held DMs whose cooldown has ended without the user reopening their ticket.
+	pub fn forget_expired_held_dms(&self) -> anyhow::Result<()> {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+		self.held_dms.retain(|_, held| !held.is_expired(now));
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn dm(id: u64) -> Message {
+		serde_json::from_value(serde_json::json!({
+			"id": id.to_string(),
+			"channel_id": "2",
+			"author": {
+				"id": "3",
+				"username": "user",
+				"discriminator": "0",
+				"avatar": null,
+			},
+			"content": "hi",
+			"timestamp": "2024-01-01T00:00:00.000000+00:00",
+			"edited_timestamp": null,
+			"tts": false,
+			"mention_everyone": false,
+			"mentions": [],
+			"mention_roles": [],
+			"attachments": [],
+			"embeds": [],
+			"pinned": false,
+			"type": 0,
+		}))
+		.unwrap()
+	}
+
+	#[test]
+	fn held_dms_are_relayed_during_the_cooldown() {
+		let held = HeldDms {
+			ends_at: 100,
+			messages: vec![dm(1), dm(2)],
+		};
+		let ids: Vec<_> = held
+			.into_unexpired(99)
+			.iter()
+			.map(|dm_msg| dm_msg.id.get())
+			.collect();
+		assert_eq!(ids, [1, 2]);
+	}
+
+	#[test]
+	fn held_dms_are_dropped_after_the_cooldown() {
+		let held = HeldDms {
+			ends_at: 100,
+			messages: vec![dm(1)],
+		};
+		assert!(held.is_expired(100));
+		assert!(held.into_unexpired(100).is_empty());
+	}
+}
@@ -0,0 +1,116 @@
+use super::Bot;
+use crate::{
+	config::{FilterAction, FilterMatch},
+	database::{FilterHit, Ticket},
+	util::{formatting, RED},
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+use twilight::{
+	id::{ChannelId, MessageId, UserId},
+	model::channel::message::embed::EmbedField,
+	util::builder::embed::{EmbedBuilder, EmbedFieldBuilder},
+	validate::embed::FIELD_VALUE_LENGTH,
+};
+
+impl Bot {
+	/// Checks a DM against the content filters, saving a hit for each one it matches.
+	pub async fn check_filters(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+		content: &str,
+	) -> anyhow::Result<Vec<FilterMatch>> {
+		let matches = self.config.live().filters.check(content);
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+		for found in &matches {
+			self.db
+				.insert_filter_hit(&FilterHit {
+					user_id,
+					dm_msg_id,
+					filter: found.filter.clone(),
+					action: found.action,
+					matched: found.matched.clone(),
+					content: content.to_owned(),
+					created_at: now,
+				})
+				.await?;
+		}
+		Ok(matches)
+	}
+
+	/// Lets staff and the user know that a DM wasn't relayed because it matched a filter.
+	pub async fn report_filtered(
+		&self,
+		ticket: &Ticket,
+		dm_channel_id: ChannelId,
+		content: &str,
+		matches: &[FilterMatch],
+		is_edit: bool,
+	) -> anyhow::Result<()> {
+		let what = if is_edit { "An edit" } else { "A message" };
+		let embed = EmbedBuilder::new()
+			.color(RED)
+			.description(format!(
+				"🚫 {what} from the user wasn't delivered because it matched a filter."
+			))
+			.field(filter_field(matches))
+			.field(EmbedFieldBuilder::new("Content", spoiler(content, FIELD_VALUE_LENGTH)).build())
+			.build();
+		self.http
+			.create_message(ticket.thread_id)
+			.embeds(&[embed])?
+			.await?;
+
+		let server = self.config.ticket_server(ticket.guild_id);
+		let locale = self.user_locale(ticket.user_id, server.guild_id).await?;
+		let blocked_msg = self
+			.config
+			.live()
+			.messages(locale.as_deref())
+			.filter_blocked
+			.render(&self.ticket_vars(ticket));
+		if let Err(err) = self
+			.http
+			.create_message(dm_channel_id)
+			.content(&blocked_msg.content)?
+			.embeds(&blocked_msg.embeds)?
+			.await
+		{
+			debug!(?err, "unable to tell user their message was filtered");
+		}
+
+		Ok(())
+	}
+}
+
+/// The strictest action out of every filter that matched.
+pub fn filter_action(matches: &[FilterMatch]) -> Option<FilterAction> {
+	matches.iter().map(|found| found.action).max()
+}
+
+/// Shows staff which filters a message matched, and what matched them.
+pub fn filter_field(matches: &[FilterMatch]) -> EmbedField {
+	let mut text = String::new();
+	for (i, found) in matches.iter().enumerate() {
+		let line = format!(
+			"**{}** ({}): {}\n",
+			formatting::escape_markdown(&found.filter),
+			found.action.name(),
+			spoiler(&formatting::escape_markdown(&found.matched), 100),
+		);
+		// cutting a line off could leave a spoiler open, so leave out whole lines instead
+		if text.chars().count() + line.chars().count() > FIELD_VALUE_LENGTH - 30 {
+			text.push_str(&format!("...and {} more", matches.len() - i));
+			break;
+		}
+		text.push_str(&line);
+	}
+	EmbedFieldBuilder::new("⚠️ Matched filters", text).build()
+}
+
+/// Hides text behind spoiler tags, shortening it to at most `max_chars` characters.
+pub fn spoiler(text: &str, max_chars: usize) -> String {
+	// spoiler tags inside the text would end the spoiler early
+	let escaped = text.replace("||", "|\u{200B}|");
+	format!("||{}||", formatting::truncate(&escaped, max_chars - 4))
+}
@@ -0,0 +1,145 @@
+use super::Bot;
+use crate::config::Vars;
+use anyhow::Context;
+use twilight::{
+	id::GuildId,
+	model::{
+		application::interaction::{
+			message_component::MessageComponentInteractionData, Interaction,
+		},
+		channel::message::{
+			component::{ActionRow, SelectMenu, SelectMenuOption},
+			Component, Message,
+		},
+		http::interaction::{InteractionResponse, InteractionResponseType},
+	},
+	util::builder::InteractionResponseDataBuilder,
+};
+
+pub const GUILD_PICKER_ID: &str = "guild_picker";
+
+impl Bot {
+	/// Holds onto a DM until the user picks which server it's about.
+	///
+	/// The user is only asked once, no matter how many messages they send before picking.
+	pub async fn ask_for_server(
+		&self,
+		dm_msg: Message,
+		guild_ids: Vec<GuildId>,
+	) -> anyhow::Result<()> {
+		let user_id = dm_msg.author.id;
+		let dm_channel_id = dm_msg.channel_id;
+		let is_first = {
+			let mut pending = self.pending_dms.entry(user_id).or_default();
+			pending.push(dm_msg);
+			pending.len() == 1
+		};
+		if !is_first {
+			return Ok(());
+		}
+
+		// select menus can't have more than 25 options
+		let options = guild_ids
+			.into_iter()
+			.take(25)
+			.map(|guild_id| SelectMenuOption {
+				default: false,
+				description: None,
+				emoji: None,
+				label: match self.discord_cache.guild(guild_id) {
+					Some(guild) => guild.name().to_owned(),
+					None => guild_id.to_string(),
+				},
+				value: guild_id.to_string(),
+			})
+			.collect();
+		let picker = Component::ActionRow(ActionRow {
+			components: vec![Component::SelectMenu(SelectMenu {
+				custom_id: GUILD_PICKER_ID.to_owned(),
+				disabled: false,
+				max_values: Some(1),
+				min_values: Some(1),
+				options,
+				placeholder: None,
+			})],
+		});
+
+		let default_guild_id = self.config.default_server().guild_id;
+		let locale = self.user_locale(user_id, default_guild_id).await?;
+		let picker_msg = self
+			.config
+			.live()
+			.messages(locale.as_deref())
+			.guild_picker
+			.render(&Vars {
+				server_id: None,
+				server_name: None,
+				..self.user_vars(user_id, default_guild_id)
+			});
+
+		self.http
+			.create_message(dm_channel_id)
+			.content(&picker_msg.content)?
+			.embeds(&picker_msg.embeds)?
+			.components(&[picker])?
+			.await?;
+
+		Ok(())
+	}
+
+	/// Opens a ticket in the server a user picked, then relays what they sent while picking.
+	pub async fn pick_server(
+		&self,
+		interaction: Interaction,
+		data: MessageComponentInteractionData,
+	) -> anyhow::Result<()> {
+		let user_id = interaction.author_id().context("missing author")?;
+		let guild_id: GuildId = data
+			.values
+			.first()
+			.and_then(|value| value.parse().ok())
+			.context("missing server")?;
+		let server = self.config.server(guild_id).context("unknown server")?;
+
+		let picked_msg = self
+			.config
+			.live()
+			.messages(interaction.locale.as_deref())
+			.guild_picked
+			.render(&self.user_vars(user_id, server.guild_id));
+		self.interact()
+			.create_response(
+				interaction.id,
+				&interaction.token,
+				&InteractionResponse {
+					kind: InteractionResponseType::UpdateMessage,
+					data: Some(
+						InteractionResponseDataBuilder::new()
+							.content(picked_msg.content)
+							.embeds(picked_msg.embeds)
+							.components([])
+							.build(),
+					),
+				},
+			)
+			.await?;
+
+		// they might have picked already, or the bot restarted since they were asked
+		let Some((_, pending)) = self.pending_dms.remove(&user_id) else {
+			return Ok(());
+		};
+
+		let mut ticket = match self.db.ticket_by_user(user_id).await? {
+			Some(ticket) if ticket.blocked => return Ok(()),
+			Some(ticket) if ticket.is_open => ticket,
+			ticket => self.ticket_in_server(ticket, user_id, guild_id).await?,
+		};
+		self.open_ticket(&mut ticket, true, None).await?;
+
+		for dm_msg in pending {
+			self.relay_dm(&ticket, dm_msg).await?;
+		}
+
+		Ok(())
+	}
+}
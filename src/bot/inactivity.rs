@@ -0,0 +1,182 @@
+use super::Bot;
+use crate::{
+	config::{AutoCloseConfig, Vars},
+	database::{InactivityTimer, Ticket},
+};
+use std::{
+	sync::{atomic::Ordering, Arc},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use twilight::validate::embed::FIELD_VALUE_LENGTH;
+
+/// How often tickets are checked for inactivity.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+const HOUR: i64 = 60 * 60;
+
+impl Bot {
+	/// Checks for inactive tickets every so often, until the bot stops.
+	pub fn start_inactivity_checks(self: &Arc<Self>) {
+		let bot = Arc::clone(self);
+		tokio::spawn(async move {
+			let mut interval = tokio::time::interval(CHECK_INTERVAL);
+			loop {
+				interval.tick().await;
+				if bot.stopping.load(Ordering::Acquire) {
+					break;
+				}
+
+				// run as a task so that shutting down waits for it
+				let bot_handle = Arc::clone(&bot);
+				let check = bot.tasks.spawn(async move {
+					if let Err(err) = bot_handle.check_inactivity().await {
+						error!(?err, "error checking for inactive tickets");
+					}
+				});
+				let _ = check.await;
+			}
+		});
+	}
+
+	/// Warns users about, and closes, tickets that have gone too long without a message.
+	async fn check_inactivity(&self) -> anyhow::Result<()> {
+		let auto_close = self.config.live().auto_close.clone();
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+		for timer in self.db.inactivity_timers().await? {
+			// one broken ticket shouldn't keep the others open
+			if let Err(err) = self.check_timer(&auto_close, &timer, now).await {
+				error!(?err, "error checking ticket for inactivity");
+			}
+		}
+
+		Ok(())
+	}
+
+	async fn check_timer(
+		&self,
+		auto_close: &AutoCloseConfig,
+		timer: &InactivityTimer,
+		now: i64,
+	) -> anyhow::Result<()> {
+		// timers are left behind by tickets that were deleted
+		let mut ticket = match self.db.ticket_by_user(timer.user_id).await? {
+			Some(ticket) if ticket.is_open && !ticket.blocked => ticket,
+			_ => return self.db.delete_inactivity_timer(timer.user_id).await,
+		};
+
+		let rule = if timer.staff_spoke_last {
+			auto_close.waiting_on_user
+		} else {
+			auto_close.waiting_on_staff
+		};
+		let Some(rule) = rule else {
+			return Ok(());
+		};
+		let close_after = i64::from(rule.close_after_hours) * HOUR;
+
+		match (rule.warn_after_hours, timer.warned_at) {
+			(Some(warn_after_hours), None) => {
+				if now >= timer.last_message_at + i64::from(warn_after_hours) * HOUR {
+					self.warn_inactive(&ticket, now + close_after).await?;
+				}
+			},
+			(Some(warn_after_hours), Some(warned_at)) => {
+				if now >= warned_at + close_after {
+					let hours = warn_after_hours + rule.close_after_hours;
+					self.close_inactive(&mut ticket, timer.staff_spoke_last, hours)
+						.await?;
+				}
+			},
+			(None, _) => {
+				if now >= timer.last_message_at + close_after {
+					self.close_inactive(
+						&mut ticket,
+						timer.staff_spoke_last,
+						rule.close_after_hours,
+					)
+					.await?;
+				}
+			},
+		}
+
+		Ok(())
+	}
+
+	/// Tells the user that their ticket will be closed at `closes_at`, and lets staff know.
+	async fn warn_inactive(&self, ticket: &Ticket, closes_at: i64) -> anyhow::Result<()> {
+		let live_config = self.config.live();
+		let server = self.config.ticket_server(ticket.guild_id);
+		let vars = Vars {
+			expires: Some(closes_at),
+			..self.ticket_vars(ticket)
+		};
+
+		let locale = self.user_locale(ticket.user_id, server.guild_id).await?;
+		let warning_msg = live_config
+			.messages(locale.as_deref())
+			.inactivity_warning
+			.render(&vars);
+		if let Err(err) = self
+			.http
+			.create_message(ticket.dm_channel_id)
+			.content(&warning_msg.content)?
+			.embeds(&warning_msg.embeds)?
+			.await
+		{
+			debug!(?err, "unable to send inactivity warning");
+		}
+
+		let locale = self.guild_locale(server.forum_guild_id);
+		let warned_msg = live_config
+			.messages(locale.as_deref())
+			.inactivity_warned
+			.render(&vars);
+		self.http
+			.create_message(ticket.thread_id)
+			.content(&warned_msg.content)?
+			.embeds(&warned_msg.embeds)?
+			.await?;
+
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+		self.db.set_inactivity_warned(ticket.user_id, now).await
+	}
+
+	/// Closes a ticket that nobody replied to for `hours`, explaining why in the thread.
+	async fn close_inactive(
+		&self,
+		ticket: &mut Ticket,
+		staff_spoke_last: bool,
+		hours: u32,
+	) -> anyhow::Result<()> {
+		let server = self.config.ticket_server(ticket.guild_id);
+		let locale = self.guild_locale(server.forum_guild_id);
+		let live_config = self.config.live();
+		let messages = live_config.messages(locale.as_deref());
+		let reason_template = if staff_spoke_last {
+			&messages.inactivity_user_reason
+		} else {
+			&messages.inactivity_staff_reason
+		};
+		let reason = reason_template.render_text(
+			&Vars {
+				hours: Some(hours),
+				..self.ticket_vars(ticket)
+			},
+			// reasons are shown in embed fields
+			FIELD_VALUE_LENGTH,
+		);
+		let closed_msg = messages.inactivity_closed.render(&Vars {
+			reason: Some(reason.clone()),
+			..self.ticket_vars(ticket)
+		});
+		self.http
+			.create_message(ticket.thread_id)
+			.content(&closed_msg.content)?
+			.embeds(&closed_msg.embeds)?
+			.await?;
+
+		self.close_ticket(ticket, true, None, Some(reason), false)
+			.await
+	}
+}
@@ -1,24 +1,76 @@
-use crate::{config::Config, database::Database, util::Tasks};
+use crate::{
+	cli::Args,
+	config::{Config, ModAction},
+	database::Database,
+	util::Tasks,
+};
+use dashmap::{DashMap, DashSet};
+use hyper::client::HttpConnector;
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
 use std::{
-	sync::atomic::{AtomicBool, Ordering},
-	time::Duration,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	time::{Duration, Instant},
 };
+use tokio::sync::Notify;
 use twilight::{
 	cache::{InMemoryCache, ResourceType},
 	gateway::{CloseFrame, Intents, MessageSender, Shard, ShardId},
 	http::{client::InteractionClient, Client},
-	id::{ApplicationId, UserId},
+	id::{ApplicationId, ChannelId, StickerId, StickerPackId, UserId},
+	model::channel::Message,
 };
 
+mod attachments;
+
+mod cooldown;
+use cooldown::HeldDms;
+pub use cooldown::REOPEN_ID;
+
+mod filters;
+pub use filters::{filter_action, filter_field, spoiler};
+
+mod guild_picker;
+pub use guild_picker::GUILD_PICKER_ID;
+
+mod inactivity;
 mod modmail;
 
+mod profile;
+pub use profile::{profile_buttons, ProfilePage, PROFILE_ID_PREFIX};
+
+mod rate_limit;
+use rate_limit::DmBucket;
+
+mod reactions;
+mod relay;
+
+mod stickers;
+use stickers::StickerOrigin;
+
+mod survey;
+pub use survey::{SURVEY_COMMENT_ID_PREFIX, SURVEY_ID_PREFIX};
+
+mod typing;
+mod webhooks;
+
+/// How often state that's only kept in memory is checked for things to forget.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
 const INTENTS: Intents = {
 	use Intents as I;
 	I::empty()
 		.union(I::DIRECT_MESSAGES)
+		.union(I::DIRECT_MESSAGE_REACTIONS)
+		.union(I::DIRECT_MESSAGE_TYPING)
 		.union(I::GUILDS)
+		.union(I::GUILD_EMOJIS_AND_STICKERS)
 		.union(I::GUILD_MEMBERS)
 		.union(I::GUILD_MODERATION)
+		.union(I::GUILD_MESSAGE_REACTIONS)
+		.union(I::GUILD_MESSAGE_TYPING)
 };
 
 const CACHE_TYPES: ResourceType = {
@@ -28,6 +80,7 @@ const CACHE_TYPES: ResourceType = {
 		.union(R::GUILD)
 		.union(R::MEMBER)
 		.union(R::ROLE)
+		.union(R::STICKER)
 		.union(R::USER)
 };
 
@@ -37,15 +90,42 @@ pub struct Bot {
 	pub user_id: UserId,
 	pub discord_cache: InMemoryCache,
 	pub http: Client,
+	/// For requests that aren't to discord's API, like downloading attachments.
+	pub web: hyper::Client<HttpsConnector<HttpConnector>>,
 	pub db: Database,
 	pub stopping: AtomicBool,
 	pub tasks: Tasks,
 	pub discord_websocket: MessageSender,
+	/// DMs from users who haven't picked which server they're about yet.
+	pub pending_dms: DashMap<UserId, Vec<Message>, ahash::RandomState>,
+	/// DMs sent during the cooldown after a ticket was closed, until the user reopens it or the
+	/// cooldown ends.
+	pub held_dms: DashMap<UserId, HeldDms, ahash::RandomState>,
+	/// How many more DMs each user can send before they're rate limited.
+	pub dm_buckets: DashMap<UserId, DmBucket, ahash::RandomState>,
+	/// Moderation actions taken through the bot's commands, by the user they were taken on.
+	///
+	/// The commands log these with the staff member who used them, so their audit log entries
+	/// aren't relayed.
+	pub logged_mod_actions: DashSet<(UserId, ModAction), ahash::RandomState>,
+	/// When each ticket's current minute of reaction relaying started, and how many reactions
+	/// have been relayed in it.
+	pub relayed_reactions: DashMap<UserId, (Instant, u32), ahash::RandomState>,
+	/// When the bot last started typing in each channel, since it keeps typing for a while.
+	pub typing_since: DashMap<ChannelId, Instant, ahash::RandomState>,
+	/// Wakes up webhook delivery when there's something new to send.
+	pub webhooks_queued: Notify,
+	/// Where stickers from outside the bot's servers are from, since discord has to be asked.
+	pub sticker_origins: DashMap<StickerId, StickerOrigin, ahash::RandomState>,
+	/// The names of discord's own sticker packs.
+	pub sticker_packs: DashMap<StickerPackId, String, ahash::RandomState>,
+	/// When the sticker packs were last downloaded.
+	pub sticker_packs_fetched_at: tokio::sync::Mutex<Option<Instant>>,
 }
 
 impl Bot {
-	pub async fn init() -> anyhow::Result<(Self, Shard)> {
-		let config = Config::load()?;
+	pub async fn init(args: &Args) -> anyhow::Result<(Self, Shard)> {
+		let config = Config::load(args)?;
 
 		let http = Client::builder()
 			.token(config.token.clone())
@@ -61,12 +141,21 @@ impl Bot {
 			.message_cache_size(0)
 			.build();
 
+		let web = hyper::Client::builder().build(
+			HttpsConnectorBuilder::new()
+				.with_native_roots()
+				.https_or_http()
+				.enable_http1()
+				.build(),
+		);
+
 		let shard = Shard::new(ShardId::ONE, config.token.clone(), INTENTS);
-		let db = Database::connect().await?;
+		let db = Database::connect(&config.database).await?;
 
 		let bot = Bot {
 			config,
 			http,
+			web,
 			app_id,
 			user_id,
 			discord_cache,
@@ -74,6 +163,16 @@ impl Bot {
 			stopping: AtomicBool::new(false),
 			tasks: Tasks::new(),
 			discord_websocket: shard.sender(),
+			pending_dms: DashMap::default(),
+			held_dms: DashMap::default(),
+			dm_buckets: DashMap::default(),
+			logged_mod_actions: DashSet::default(),
+			relayed_reactions: DashMap::default(),
+			typing_since: DashMap::default(),
+			webhooks_queued: Notify::new(),
+			sticker_origins: DashMap::default(),
+			sticker_packs: DashMap::default(),
+			sticker_packs_fetched_at: Default::default(),
 		};
 
 		// log session information
@@ -93,19 +192,57 @@ impl Bot {
 		);
 
 		// sync commands with discord
-		let cmd_info = crate::commands::info();
-		bot.interact()
-			.set_guild_commands(bot.config.forum_guild_id, &cmd_info)
-			.await?;
-		if bot.config.guild_id != bot.config.forum_guild_id {
+		let cmd_info = crate::commands::info(&bot.config.live().locales);
+		let mut guild_ids = Vec::new();
+		for server in &bot.config.servers {
+			for guild_id in [server.forum_guild_id, server.guild_id] {
+				if !guild_ids.contains(&guild_id) {
+					guild_ids.push(guild_id);
+				}
+			}
+		}
+		for guild_id in guild_ids {
 			bot.interact()
-				.set_guild_commands(bot.config.guild_id, &cmd_info)
+				.set_guild_commands(guild_id, &cmd_info)
 				.await?;
 		}
 
 		Ok((bot, shard))
 	}
 
+	/// Reloads the config, returning the changed fields that need a restart to apply.
+	pub fn reload_config(&self) -> anyhow::Result<Vec<&'static str>> {
+		let needs_restart = self.config.reload()?;
+		info!("config reloaded");
+		if !needs_restart.is_empty() {
+			warn!(
+				"these config changes need a restart to apply: {}",
+				needs_restart.join(", ")
+			);
+		}
+		Ok(needs_restart)
+	}
+
+	/// Forgets in-memory state that's no longer needed every so often, until the bot stops.
+	pub fn start_cleanups(self: &Arc<Self>) {
+		let bot = Arc::clone(self);
+		tokio::spawn(async move {
+			let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+			loop {
+				interval.tick().await;
+				if bot.stopping.load(Ordering::Acquire) {
+					break;
+				}
+				if let Err(err) = bot.forget_expired_held_dms() {
+					error!(?err, "error forgetting held DMs");
+				}
+				bot.forget_full_dm_buckets();
+				bot.forget_old_reaction_windows();
+				bot.forget_old_typing();
+			}
+		});
+	}
+
 	pub fn stop(&self) {
 		warn!("stopping");
 		self.stopping.store(true, Ordering::Release);
@@ -115,10 +252,10 @@ impl Bot {
 	}
 
 	pub async fn finish_shutdown(&self) {
-		self.db.connection.close().await;
+		self.db.close().await;
 	}
 
-	pub fn interact(&self) -> InteractionClient {
+	pub fn interact(&self) -> InteractionClient<'_> {
 		self.http.interaction(self.app_id)
 	}
 }
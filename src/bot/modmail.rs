@@ -1,12 +1,16 @@
-use super::Bot;
+use super::{profile_buttons, Bot, ProfilePage};
 use crate::{
-	database::Ticket,
-	util::{formatting, SqliteId},
+	config::{Server, Vars},
+	database::{Ticket, TicketEvent, TicketEventKind},
+	util::formatting,
 };
 use anyhow::Context;
-use std::fmt::Write as _;
+use std::{
+	fmt::Write as _,
+	time::{SystemTime, UNIX_EPOCH},
+};
 use twilight::{
-	id::{Id, UserId},
+	id::{ChannelId, GuildId, Id, UserId},
 	model::channel::{
 		message::{AllowedMentions, MentionType},
 		thread::AutoArchiveDuration,
@@ -14,9 +18,76 @@ use twilight::{
 };
 
 impl Bot {
-	pub async fn create_ticket(&self, user_id: UserId) -> anyhow::Result<Ticket> {
+	/// The locale to send a user messages in.
+	///
+	/// This is the locale their discord client last reported, or the server's if it isn't known.
+	pub async fn user_locale(
+		&self,
+		user_id: UserId,
+		guild_id: GuildId,
+	) -> anyhow::Result<Option<String>> {
+		if let Some(locale) = self.db.user_locale(user_id).await? {
+			return Ok(Some(locale));
+		}
+		Ok(self.guild_locale(guild_id))
+	}
+
+	/// The locale for messages in a server that aren't meant for a specific user.
+	pub fn guild_locale(&self, guild_id: GuildId) -> Option<String> {
+		self.config.live().default_locale.clone().or_else(|| {
+			self.discord_cache
+				.guild(guild_id)
+				.map(|guild| guild.preferred_locale().to_owned())
+		})
+	}
+
+	/// Placeholder values for messages about a user in a server.
+	pub fn user_vars(&self, user_id: UserId, guild_id: GuildId) -> Vars {
+		Vars {
+			user_id: Some(user_id),
+			user_name: self
+				.discord_cache
+				.user(user_id)
+				.map(|user| user.name.clone()),
+			server_id: Some(guild_id),
+			server_name: self
+				.discord_cache
+				.guild(guild_id)
+				.map(|guild| guild.name().to_owned()),
+			..Vars::default()
+		}
+	}
+
+	/// Placeholder values for messages about a user's ticket.
+	pub fn ticket_vars(&self, ticket: &Ticket) -> Vars {
+		let server = self.config.ticket_server(ticket.guild_id);
+		Vars {
+			ticket_id: Some(ticket.thread_id),
+			..self.user_vars(ticket.user_id, server.guild_id)
+		}
+	}
+
+	/// The configured servers that a user is in.
+	pub fn shared_servers(&self, user_id: UserId) -> Vec<&Server> {
+		self.config
+			.servers
+			.iter()
+			.filter(|server| {
+				self.discord_cache
+					.member(server.guild_id, user_id)
+					.is_some()
+			})
+			.collect()
+	}
+
+	pub async fn create_ticket(
+		&self,
+		user_id: UserId,
+		guild_id: GuildId,
+	) -> anyhow::Result<Ticket> {
 		let mut ticket = Ticket {
 			user_id,
+			guild_id: Some(guild_id),
 			thread_id: Id::new(1),
 			dm_channel_id: Id::new(1),
 			is_open: false,
@@ -32,6 +103,58 @@ impl Bot {
 			.await?
 			.id;
 
+		ticket.thread_id = self.create_thread(user_id, guild_id).await?;
+
+		self.db.insert_ticket(&ticket).await?;
+
+		Ok(ticket)
+	}
+
+	/// Gets a closed ticket ready to be opened in a server, creating or moving it if needed.
+	pub async fn ticket_in_server(
+		&self,
+		ticket: Option<Ticket>,
+		user_id: UserId,
+		guild_id: GuildId,
+	) -> anyhow::Result<Ticket> {
+		match ticket {
+			Some(mut ticket) => {
+				if self.config.ticket_server(ticket.guild_id).guild_id != guild_id {
+					self.move_ticket(&mut ticket, guild_id).await?;
+				}
+				Ok(ticket)
+			},
+			None => self.create_ticket(user_id, guild_id).await,
+		}
+	}
+
+	/// Moves a closed ticket to a new thread in another server's forum.
+	///
+	/// The old thread is left alone, so its history stays where it was.
+	pub async fn move_ticket(&self, ticket: &mut Ticket, guild_id: GuildId) -> anyhow::Result<()> {
+		if ticket.is_open {
+			bail!("can't move an open ticket");
+		}
+
+		let thread_id = self.create_thread(ticket.user_id, guild_id).await?;
+		self.db
+			.move_ticket(ticket.user_id, guild_id, thread_id)
+			.await?;
+
+		ticket.guild_id = Some(guild_id);
+		ticket.thread_id = thread_id;
+
+		Ok(())
+	}
+
+	/// Creates a ticket thread in a server's forum.
+	async fn create_thread(&self, user_id: UserId, guild_id: GuildId) -> anyhow::Result<ChannelId> {
+		let forum_channel_id = self
+			.config
+			.server(guild_id)
+			.context("unknown server")?
+			.forum_channel_id;
+
 		let username = self
 			.discord_cache
 			.user(user_id)
@@ -41,7 +164,7 @@ impl Bot {
 
 		let thread = self
 			.http
-			.create_forum_thread(self.config.forum_channel_id, &username)
+			.create_forum_thread(forum_channel_id, &username)
 			.auto_archive_duration(AutoArchiveDuration::Week)
 			.message()
 			.content("Creating thread...")?
@@ -57,83 +180,140 @@ impl Bot {
 			error!(?err);
 		}
 
-		ticket.thread_id = thread.channel.id;
-
-		sqlx::query(indoc! {"
-			INSERT INTO tickets (user_id, thread_id, dm_channel_id)
-			VALUES (?1, ?2, ?3)
-		"})
-		.bind(SqliteId(ticket.user_id))
-		.bind(SqliteId(ticket.thread_id))
-		.bind(SqliteId(ticket.dm_channel_id))
-		.execute(&self.db.connection)
-		.await?;
-
-		Ok(ticket)
+		Ok(thread.channel.id)
 	}
 
+	/// Opens a ticket, or reopens it if `staff_id` is who did it.
+	///
+	/// Roles aren't pinged when staff reopen a ticket, since they're already there.
 	pub async fn open_ticket(
 		&self,
 		ticket: &mut Ticket,
 		send_open_msg: bool,
+		staff_id: Option<UserId>,
 	) -> anyhow::Result<()> {
 		if ticket.is_open {
 			return Ok(());
 		}
 
-		if let (true, Some(open_msg)) = (send_open_msg, &self.config.open_message) {
+		let live_config = self.config.live();
+		let server = self.config.ticket_server(ticket.guild_id);
+
+		let locale = self.user_locale(ticket.user_id, server.guild_id).await?;
+		let messages = live_config.messages(locale.as_deref());
+		if let (true, Some(open_msg)) = (send_open_msg, &messages.open_message) {
+			let open_msg = open_msg.render(&self.ticket_vars(ticket));
 			if let Err(err) = self
 				.http
 				.create_message(ticket.dm_channel_id)
-				.content(open_msg)?
+				.content(&open_msg.content)?
+				.embeds(&open_msg.embeds)?
 				.await
 			{
 				error!(?err);
 			}
 		}
 
+		let ping_roles = match staff_id {
+			Some(_) => &[],
+			None => live_config.ping_roles(server.guild_id),
+		};
 		let mut starter_msg_text = String::new();
-		for &role_id in &self.config.ping_roles {
+		for &role_id in ping_roles {
 			write!(starter_msg_text, "<@&{role_id}> ")?;
 		}
-		if !self.config.ping_roles.is_empty() {
+		if !ping_roles.is_empty() {
 			starter_msg_text.push_str("\n\n");
 		}
 		write!(starter_msg_text, "<@{}>", ticket.user_id)?;
+		let mut starter_embeds = Vec::new();
+		if let Some(staff_id) = staff_id {
+			let thread_locale = self.guild_locale(server.forum_guild_id);
+			let reopened_msg = live_config
+				.messages(thread_locale.as_deref())
+				.staff_reopened
+				.render(&Vars {
+					staff_id: Some(staff_id),
+					staff_name: self
+						.discord_cache
+						.user(staff_id)
+						.map(|user| user.name.clone()),
+					..self.ticket_vars(ticket)
+				});
+			if !reopened_msg.content.is_empty() {
+				starter_msg_text.push('\n');
+				starter_msg_text.push_str(&reopened_msg.content);
+			}
+			starter_embeds = reopened_msg.embeds;
+		}
 
 		let mut allow_role_pings = AllowedMentions::default();
 		allow_role_pings.parse.push(MentionType::Roles);
 
+		// the ticket is more important than the profile, so open it anyway
+		match formatting::user_info_embed(self, ticket.user_id, server.guild_id).await {
+			Ok(info_embed) => starter_embeds.push(info_embed),
+			Err(err) => warn!(?err, "couldn't show user info"),
+		}
+
 		self.http
 			.create_message(ticket.thread_id)
 			.content(&starter_msg_text)?
 			.allowed_mentions(Some(&allow_role_pings))
-			.embeds(&[formatting::user_info_embed(self, ticket.user_id).await?])?
+			.embeds(&starter_embeds)?
+			.components(&[profile_buttons(
+				ticket.user_id,
+				server.guild_id,
+				ProfilePage::Overview,
+			)])?
 			.await?;
 
 		ticket.is_open = true;
-		sqlx::query("UPDATE tickets SET is_open = TRUE WHERE user_id = ?")
-			.bind(SqliteId(ticket.user_id))
-			.execute(&self.db.connection)
-			.await?;
+		self.db.set_ticket_open(ticket.user_id, true).await?;
+		let event = TicketEvent {
+			user_id: ticket.user_id,
+			kind: TicketEventKind::Open,
+			staff_id,
+			reason: None,
+			created_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+		};
+		self.db.insert_ticket_event(&event).await?;
+		self.queue_ticket_event_webhooks(ticket, &event).await?;
 
 		Ok(())
 	}
 
+	/// Closes a ticket, recording who closed it and why.
+	///
+	/// The reason is only sent to the user if `share_reason` is set.
 	pub async fn close_ticket(
 		&self,
 		ticket: &mut Ticket,
 		send_close_msg: bool,
+		staff_id: Option<UserId>,
+		reason: Option<String>,
+		share_reason: bool,
 	) -> anyhow::Result<()> {
 		if !ticket.is_open {
 			return Ok(());
 		}
 
-		if let (true, Some(close_msg)) = (send_close_msg, &self.config.close_message) {
+		let closed_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+		let live_config = self.config.live();
+		let server = self.config.ticket_server(ticket.guild_id);
+		let locale = self.user_locale(ticket.user_id, server.guild_id).await?;
+		let messages = live_config.messages(locale.as_deref());
+		if let (true, Some(close_msg)) = (send_close_msg, &messages.close_message) {
+			let close_msg = close_msg.render(&Vars {
+				staff_id,
+				reason: reason.clone().filter(|_| share_reason),
+				..self.ticket_vars(ticket)
+			});
 			if let Err(err) = self
 				.http
 				.create_message(ticket.dm_channel_id)
-				.content(close_msg)?
+				.content(&close_msg.content)?
+				.embeds(&close_msg.embeds)?
 				.await
 			{
 				error!(?err);
@@ -141,10 +321,29 @@ impl Bot {
 		}
 
 		ticket.is_open = false;
-		sqlx::query("UPDATE tickets SET is_open = FALSE WHERE user_id = ?")
-			.bind(SqliteId(ticket.user_id))
-			.execute(&self.db.connection)
-			.await?;
+		self.db.set_ticket_open(ticket.user_id, false).await?;
+		self.db.delete_inactivity_timer(ticket.user_id).await?;
+		self.typing_since.remove(&ticket.thread_id);
+		self.typing_since.remove(&ticket.dm_channel_id);
+		let event = TicketEvent {
+			user_id: ticket.user_id,
+			kind: TicketEventKind::Close,
+			staff_id,
+			reason,
+			created_at: closed_at,
+		};
+		self.db.insert_ticket_event(&event).await?;
+		self.queue_ticket_event_webhooks(ticket, &event).await?;
+
+		// users who don't want messages from the bot don't want a survey either
+		if send_close_msg && live_config.survey {
+			if let Err(err) = self
+				.send_survey(ticket, server.guild_id, closed_at, locale.as_deref())
+				.await
+			{
+				debug!(?err, "unable to send survey");
+			}
+		}
 
 		if let Err(err) = self
 			.http
@@ -0,0 +1,279 @@
+use super::Bot;
+use crate::{
+	database::TicketEventKind,
+	util::{formatting, BLURPLE},
+};
+use anyhow::Context;
+use std::fmt::Write as _;
+use twilight::{
+	id::{GuildId, UserId},
+	model::{
+		application::interaction::{
+			message_component::MessageComponentInteractionData, Interaction,
+		},
+		channel::message::{
+			component::{ActionRow, Button, ButtonStyle},
+			Component, Embed,
+		},
+		http::interaction::{InteractionResponse, InteractionResponseType},
+	},
+	util::builder::{embed::EmbedBuilder, InteractionResponseDataBuilder},
+	validate::embed::DESCRIPTION_LENGTH,
+};
+
+/// Profile buttons have ids like `profile:<page>:<user id>:<guild id>`.
+pub const PROFILE_ID_PREFIX: &str = "profile:";
+
+/// Each page of a user's profile has the details behind one part of the overview.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ProfilePage {
+	Overview,
+	Tickets,
+	Blocks,
+	Moderation,
+	Notes,
+}
+
+impl ProfilePage {
+	const ALL: &[Self] = &[
+		Self::Overview,
+		Self::Tickets,
+		Self::Blocks,
+		Self::Moderation,
+		Self::Notes,
+	];
+
+	fn id(self) -> &'static str {
+		match self {
+			Self::Overview => "overview",
+			Self::Tickets => "tickets",
+			Self::Blocks => "blocks",
+			Self::Moderation => "moderation",
+			Self::Notes => "notes",
+		}
+	}
+
+	fn label(self) -> &'static str {
+		match self {
+			Self::Overview => "Overview",
+			Self::Tickets => "Tickets",
+			Self::Blocks => "Blocks",
+			Self::Moderation => "Moderation",
+			Self::Notes => "Notes",
+		}
+	}
+}
+
+/// Buttons for switching between the pages of a user's profile.
+pub fn profile_buttons(user_id: UserId, guild_id: GuildId, current: ProfilePage) -> Component {
+	let buttons = ProfilePage::ALL
+		.iter()
+		.map(|&page| {
+			Component::Button(Button {
+				custom_id: Some(format!(
+					"{PROFILE_ID_PREFIX}{}:{user_id}:{guild_id}",
+					page.id()
+				)),
+				disabled: page == current,
+				emoji: None,
+				label: Some(page.label().to_owned()),
+				style: if page == current {
+					ButtonStyle::Primary
+				} else {
+					ButtonStyle::Secondary
+				},
+				url: None,
+			})
+		})
+		.collect();
+	Component::ActionRow(ActionRow {
+		components: buttons,
+	})
+}
+
+impl Bot {
+	/// One page of a user's profile, as seen from a server.
+	///
+	/// Everything but the overview comes from the database.
+	pub async fn profile_page(
+		&self,
+		user_id: UserId,
+		guild_id: GuildId,
+		page: ProfilePage,
+	) -> anyhow::Result<Embed> {
+		let mut lines = Vec::new();
+		let mut header = None;
+		match page {
+			ProfilePage::Overview => {
+				return formatting::user_info_embed(self, user_id, guild_id).await;
+			},
+
+			ProfilePage::Tickets => {
+				let events = self.db.ticket_events(user_id).await?;
+				let opened = events
+					.iter()
+					.filter(|event| event.kind == TicketEventKind::Open)
+					.count();
+				let mut text = format!("{opened} ticket(s) opened");
+				match self.db.last_message_at(user_id).await? {
+					Some(timestamp) => write!(text, ", last contacted <t:{timestamp}:R>")?,
+					None => text.push_str(", never contacted"),
+				}
+				header = Some(text);
+
+				for event in events {
+					let emoji = match event.kind {
+						TicketEventKind::Open => "📬 Opened",
+						TicketEventKind::Close => "📪 Closed",
+						_ => continue,
+					};
+					lines.push(event_line(
+						event.created_at,
+						emoji,
+						event.staff_id,
+						event.reason.as_deref(),
+					));
+				}
+			},
+
+			ProfilePage::Blocks => {
+				for event in self.db.ticket_events(user_id).await? {
+					let emoji = match event.kind {
+						TicketEventKind::Block => "⛔ Blocked",
+						TicketEventKind::Unblock => "✅ Unblocked",
+						_ => continue,
+					};
+					lines.push(event_line(
+						event.created_at,
+						emoji,
+						event.staff_id,
+						event.reason.as_deref(),
+					));
+				}
+			},
+
+			ProfilePage::Moderation => {
+				for moderation in self.db.moderations(user_id).await? {
+					if moderation.guild_id != guild_id {
+						continue;
+					}
+					let mut action = format!(
+						"{} {}",
+						moderation.kind.emoji(),
+						capitalize(moderation.kind.past_tense())
+					);
+					if let Some(expires_at) = moderation.expires_at {
+						write!(action, " until <t:{expires_at}:f>")?;
+					}
+					lines.push(event_line(
+						moderation.created_at,
+						&action,
+						Some(moderation.staff_id),
+						moderation.reason.as_deref(),
+					));
+				}
+			},
+
+			ProfilePage::Notes => {
+				for note in self.db.staff_notes(user_id).await? {
+					lines.push(format!(
+						"<t:{}:f> <@{}>: {}",
+						note.created_at, note.staff_id, note.content
+					));
+				}
+			},
+		}
+
+		Ok(EmbedBuilder::new()
+			.color(BLURPLE)
+			.title(page.label())
+			.description(list_text(header, &lines))
+			.build())
+	}
+
+	/// Handles a profile button being pressed.
+	pub async fn switch_profile_page(
+		&self,
+		interaction: Interaction,
+		data: MessageComponentInteractionData,
+	) -> anyhow::Result<()> {
+		let parts = data
+			.custom_id
+			.strip_prefix(PROFILE_ID_PREFIX)
+			.map(|rest| rest.split(':').collect::<Vec<_>>());
+		let Some([page_id, user_id, guild_id]) = parts.as_deref() else {
+			bail!("invalid profile button {}", data.custom_id);
+		};
+		let page = *ProfilePage::ALL
+			.iter()
+			.find(|page| page.id() == *page_id)
+			.context("unknown profile page")?;
+		let user_id: UserId = user_id.parse()?;
+		let guild_id: GuildId = guild_id.parse()?;
+
+		let embed = self.profile_page(user_id, guild_id, page).await?;
+		self.interact()
+			.create_response(
+				interaction.id,
+				&interaction.token,
+				&InteractionResponse {
+					kind: InteractionResponseType::UpdateMessage,
+					data: Some(
+						InteractionResponseDataBuilder::new()
+							.embeds([embed])
+							.components([profile_buttons(user_id, guild_id, page)])
+							.build(),
+					),
+				},
+			)
+			.await?;
+
+		Ok(())
+	}
+}
+
+fn event_line(
+	timestamp: i64,
+	action: &str,
+	staff_id: Option<UserId>,
+	reason: Option<&str>,
+) -> String {
+	let mut line = format!("<t:{timestamp}:f> {action}");
+	if let Some(staff_id) = staff_id {
+		// can't fail
+		let _ = write!(line, " by <@{staff_id}>");
+	}
+	if let Some(reason) = reason {
+		let _ = write!(line, ": {reason}");
+	}
+	line
+}
+
+fn capitalize(text: &str) -> String {
+	let mut chars = text.chars();
+	match chars.next() {
+		Some(first) => first.to_uppercase().chain(chars).collect(),
+		None => String::new(),
+	}
+}
+
+/// Fits as many lines as possible in an embed description, newest first.
+fn list_text(header: Option<String>, lines: &[String]) -> String {
+	let mut text = header.map(|header| header + "\n\n").unwrap_or_default();
+	if lines.is_empty() {
+		text.push_str("Nothing here yet.");
+		return text;
+	}
+
+	// leave room for the "more" line
+	let max_chars = DESCRIPTION_LENGTH - 30;
+	for (i, line) in lines.iter().enumerate() {
+		if text.chars().count() + line.chars().count() + 1 > max_chars {
+			text.push_str(&format!("...and {} more", lines.len() - i));
+			break;
+		}
+		text.push_str(line);
+		text.push('\n');
+	}
+	text
+}
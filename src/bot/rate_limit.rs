@@ -0,0 +1,239 @@
+use super::Bot;
+use crate::{
+	config::{RateLimitConfig, Vars},
+	database::{TicketEvent, TicketEventKind},
+};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use twilight::{id::UserId, model::channel::Message, validate::embed::FIELD_VALUE_LENGTH};
+
+/// How many more DMs a user can send right now, which refills over time.
+pub struct DmBucket {
+	tokens: f64,
+	refilled_at: Instant,
+	/// Messages dropped since the last one that was relayed.
+	dropped: u32,
+}
+
+#[derive(Debug, PartialEq)]
+enum DmLimit {
+	Allowed,
+	Dropped {
+		is_first: bool,
+	},
+	/// Too many messages in a row were dropped.
+	Exceeded,
+}
+
+impl Bot {
+	/// Checks whether a DM should be relayed, returning `false` if the user is sending too many.
+	///
+	/// This warns the user the first time a message is dropped, and blocks them if too many are.
+	pub async fn check_dm_rate_limit(&self, dm_msg: &Message) -> anyhow::Result<bool> {
+		let config = self.config.live().rate_limit;
+		if config.per_minute == 0 {
+			return Ok(true);
+		}
+
+		match self.take_dm_token(dm_msg.author.id, config) {
+			DmLimit::Allowed => return Ok(true),
+			DmLimit::Dropped { is_first: false } => (),
+			DmLimit::Dropped { is_first: true } => {
+				let default_guild_id = self.config.default_server().guild_id;
+				let locale = self.user_locale(dm_msg.author.id, default_guild_id).await?;
+				let limited_msg = self
+					.config
+					.live()
+					.messages(locale.as_deref())
+					.rate_limited
+					.render(&self.user_vars(dm_msg.author.id, default_guild_id));
+				if let Err(err) = self
+					.http
+					.create_message(dm_msg.channel_id)
+					.content(&limited_msg.content)?
+					.embeds(&limited_msg.embeds)?
+					.await
+				{
+					debug!(?err, "unable to send rate limit warning");
+				}
+			},
+			DmLimit::Exceeded => self.block_spammer(dm_msg.author.id).await?,
+		}
+
+		debug!("not relaying DM, too many from {}", dm_msg.author.id);
+		Ok(false)
+	}
+
+	fn take_dm_token(&self, user_id: UserId, config: RateLimitConfig) -> DmLimit {
+		let now = Instant::now();
+		self.dm_buckets
+			.entry(user_id)
+			.or_insert_with(|| DmBucket::new(now, config))
+			.take(now, config)
+	}
+
+	/// Blocks a user for sending too many messages, and lets staff know.
+	async fn block_spammer(&self, user_id: UserId) -> anyhow::Result<()> {
+		// users without a ticket have nothing to block, and their messages are still dropped
+		let ticket = match self.db.ticket_by_user(user_id).await? {
+			Some(ticket) if !ticket.blocked => ticket,
+			_ => return Ok(()),
+		};
+
+		let server = self.config.ticket_server(ticket.guild_id);
+		let locale = self.guild_locale(server.forum_guild_id);
+		let live_config = self.config.live();
+		let messages = live_config.messages(locale.as_deref());
+		let reason = messages.rate_limit_reason.render_text(
+			&self.ticket_vars(&ticket),
+			// reasons are shown in embed fields
+			FIELD_VALUE_LENGTH,
+		);
+
+		self.db.set_ticket_blocked(user_id, true).await?;
+		let event = TicketEvent {
+			user_id,
+			kind: TicketEventKind::Block,
+			staff_id: None,
+			reason: Some(reason.clone()),
+			created_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+		};
+		self.db.insert_ticket_event(&event).await?;
+		self.queue_ticket_event_webhooks(&ticket, &event).await?;
+		warn!("blocked {user_id} for sending too many messages");
+
+		let blocked_msg = messages.rate_limit_blocked.render(&Vars {
+			reason: Some(reason),
+			..self.ticket_vars(&ticket)
+		});
+		self.http
+			.create_message(ticket.thread_id)
+			.content(&blocked_msg.content)?
+			.embeds(&blocked_msg.embeds)?
+			.await?;
+
+		Ok(())
+	}
+
+	/// Forgets the buckets of users who could send a full burst again, since a new one is the same.
+	pub fn forget_full_dm_buckets(&self) {
+		let config = self.config.live().rate_limit;
+		// the limit was turned off since they were used
+		if config.per_minute == 0 {
+			self.dm_buckets.clear();
+			return;
+		}
+
+		let now = Instant::now();
+		self.dm_buckets
+			.retain(|_, bucket| bucket.tokens_at(now, config) < f64::from(config.burst));
+	}
+}
+
+impl DmBucket {
+	fn new(now: Instant, config: RateLimitConfig) -> Self {
+		Self {
+			tokens: f64::from(config.burst),
+			refilled_at: now,
+			dropped: 0,
+		}
+	}
+
+	/// Uses up a token for a message sent at `now`, if there's one left.
+	fn take(&mut self, now: Instant, config: RateLimitConfig) -> DmLimit {
+		self.tokens = self.tokens_at(now, config);
+		self.refilled_at = now;
+
+		if self.tokens >= 1.0 {
+			self.tokens -= 1.0;
+			self.dropped = 0;
+			return DmLimit::Allowed;
+		}
+
+		self.dropped += 1;
+		if config.block_after == Some(self.dropped) {
+			DmLimit::Exceeded
+		} else {
+			DmLimit::Dropped {
+				is_first: self.dropped == 1,
+			}
+		}
+	}
+
+	/// How many tokens the bucket has at `now`, after refilling since it was last used.
+	fn tokens_at(&self, now: Instant, config: RateLimitConfig) -> f64 {
+		let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+		(self.tokens + elapsed * f64::from(config.per_minute) / 60.0).min(f64::from(config.burst))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	const FIRST_DROP: DmLimit = DmLimit::Dropped { is_first: true };
+	const DROP: DmLimit = DmLimit::Dropped { is_first: false };
+
+	fn config(block_after: Option<u32>) -> RateLimitConfig {
+		RateLimitConfig {
+			per_minute: 60,
+			burst: 3,
+			block_after,
+		}
+	}
+
+	#[test]
+	fn burst_runs_out() {
+		let config = config(None);
+		let now = Instant::now();
+		let mut bucket = DmBucket::new(now, config);
+
+		for _ in 0..3 {
+			assert_eq!(bucket.take(now, config), DmLimit::Allowed);
+		}
+		assert_eq!(bucket.take(now, config), FIRST_DROP);
+		assert_eq!(bucket.take(now, config), DROP);
+	}
+
+	#[test]
+	fn tokens_refill_over_time() {
+		let config = config(None);
+		let start = Instant::now();
+		let mut bucket = DmBucket::new(start, config);
+		for _ in 0..3 {
+			bucket.take(start, config);
+		}
+
+		// one token a second at 60 per minute
+		let later = start + Duration::from_millis(1500);
+		assert_eq!(bucket.take(later, config), DmLimit::Allowed);
+		assert_eq!(bucket.take(later, config), FIRST_DROP);
+
+		// never more than the burst, no matter how long it's been
+		let much_later = later + Duration::from_secs(60 * 60);
+		assert_eq!(bucket.tokens_at(much_later, config), 3.0);
+		for _ in 0..3 {
+			assert_eq!(bucket.take(much_later, config), DmLimit::Allowed);
+		}
+		assert_eq!(bucket.take(much_later, config), FIRST_DROP);
+	}
+
+	#[test]
+	fn too_many_drops_in_a_row_are_exceeded() {
+		let config = config(Some(3));
+		let now = Instant::now();
+		let mut bucket = DmBucket::new(now, config);
+		for _ in 0..3 {
+			bucket.take(now, config);
+		}
+
+		assert_eq!(bucket.take(now, config), FIRST_DROP);
+		assert_eq!(bucket.take(now, config), DROP);
+		assert_eq!(bucket.take(now, config), DmLimit::Exceeded);
+
+		// a relayed message starts the count over
+		let later = now + Duration::from_secs(1);
+		assert_eq!(bucket.take(later, config), DmLimit::Allowed);
+		assert_eq!(bucket.take(later, config), FIRST_DROP);
+	}
+}
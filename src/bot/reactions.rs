@@ -0,0 +1,129 @@
+use super::Bot;
+use std::time::{Duration, Instant};
+use twilight::{
+	http::request::channel::reaction::RequestReactionType,
+	id::UserId,
+	model::{channel::message::ReactionType, gateway::GatewayReaction},
+};
+
+/// Reactions the bot uses to show what happened to a message, which would be confusing if they
+/// showed up on the other side.
+const STATUS_REACTIONS: &[&str] = &["📨", "✏️"];
+
+/// How long `reactions.per_minute` counts reactions for.
+const REACTION_WINDOW: Duration = Duration::from_secs(60);
+
+impl Bot {
+	/// Mirrors a reaction being added or removed between a user's DMs and their ticket thread.
+	pub async fn relay_reaction(
+		&self,
+		reaction: &GatewayReaction,
+		added: bool,
+	) -> anyhow::Result<()> {
+		// the bot's own reactions include the ones it mirrored
+		if reaction.user_id == self.user_id {
+			return Ok(());
+		}
+
+		let (emoji, request_emoji) = match &reaction.emoji {
+			ReactionType::Unicode { name } if STATUS_REACTIONS.contains(&name.as_str()) => {
+				return Ok(());
+			},
+			ReactionType::Unicode { name } => (name.clone(), RequestReactionType::Unicode { name }),
+			ReactionType::Custom { id, name, .. } => (
+				id.to_string(),
+				RequestReactionType::Custom {
+					id: *id,
+					name: name.as_deref(),
+				},
+			),
+		};
+
+		let live_config = self.config.live();
+		if !live_config.reactions.allows(&emoji) {
+			return Ok(());
+		}
+
+		// find the same message on the other side
+		let (channel_id, message_id, ticket) = match reaction.guild_id {
+			None => {
+				let Some(ticket) = self.db.ticket_by_dm_channel(reaction.channel_id).await? else {
+					return Ok(());
+				};
+				let Some(msg) = self
+					.db
+					.message_by_dm_msg(ticket.user_id, reaction.message_id)
+					.await?
+				else {
+					return Ok(());
+				};
+				(ticket.thread_id, msg.latest_thread_msg_id(), ticket)
+			},
+			Some(_) => {
+				let Some(ticket) = self.db.ticket_by_thread(reaction.channel_id).await? else {
+					return Ok(());
+				};
+				let Some(msg) = self
+					.db
+					.message_by_thread_msg(ticket.user_id, reaction.message_id)
+					.await?
+				else {
+					return Ok(());
+				};
+				(ticket.dm_channel_id, msg.dm_msg_id, ticket)
+			},
+		};
+		if !ticket.is_open || ticket.blocked {
+			return Ok(());
+		}
+
+		// removing a reaction that was never mirrored does nothing, so only adding is limited
+		let result = if added {
+			if !self.take_reaction_slot(ticket.user_id, live_config.reactions.per_minute) {
+				debug!(
+					"not relaying reaction, too many for ticket {}",
+					ticket.thread_id
+				);
+				return Ok(());
+			}
+			self.http
+				.create_reaction(channel_id, message_id, &request_emoji)
+				.await
+		} else {
+			self.http
+				.delete_current_user_reaction(channel_id, message_id, &request_emoji)
+				.await
+		};
+
+		// custom emoji from servers the bot isn't in can't be used
+		if let Err(err) = result {
+			debug!(?err, "unable to relay reaction");
+		}
+
+		Ok(())
+	}
+
+	/// Counts a reaction being relayed for a ticket, returning `false` if there have been too many
+	/// in the last minute.
+	fn take_reaction_slot(&self, user_id: UserId, per_minute: u32) -> bool {
+		let now = Instant::now();
+		let mut entry = self.relayed_reactions.entry(user_id).or_insert((now, 0));
+		let (window_start, count) = &mut *entry;
+		if now.duration_since(*window_start) >= REACTION_WINDOW {
+			*window_start = now;
+			*count = 0;
+		}
+		if *count >= per_minute {
+			return false;
+		}
+		*count += 1;
+		true
+	}
+
+	/// Forgets reaction counts from windows that have ended, since they'd be started over anyway.
+	pub fn forget_old_reaction_windows(&self) {
+		let now = Instant::now();
+		self.relayed_reactions
+			.retain(|_, (window_start, _)| now.duration_since(*window_start) < REACTION_WINDOW);
+	}
+}
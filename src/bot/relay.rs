@@ -0,0 +1,216 @@
+use super::{filter_action, filter_field, spoiler, Bot};
+use crate::{
+	config::FilterAction,
+	database::{RelayedMessage, Ticket},
+	util::{formatting, BLANK_EMBED_COLOR},
+};
+use std::fmt::Write as _;
+use twilight::{
+	http::request::channel::reaction::RequestReactionType,
+	model::channel::message::{Message, MessageFlags, MessageReference, MessageType},
+	util::builder::embed::{EmbedBuilder, ImageSource},
+	validate::embed::DESCRIPTION_LENGTH,
+};
+
+impl Bot {
+	/// Sends a message from a user's DMs to their ticket thread.
+	pub async fn relay_dm(&self, ticket: &Ticket, dm_msg: Message) -> anyhow::Result<()> {
+		let filter_matches = self
+			.check_filters(dm_msg.author.id, dm_msg.id, &dm_msg.content)
+			.await?;
+		let filter_action = filter_action(&filter_matches);
+		if filter_action == Some(FilterAction::Block) {
+			return self
+				.report_filtered(
+					ticket,
+					dm_msg.channel_id,
+					&dm_msg.content,
+					&filter_matches,
+					false,
+				)
+				.await;
+		}
+
+		// build the embed we'll send to the thread
+		let mut embed = EmbedBuilder::new()
+			.color(BLANK_EMBED_COLOR)
+			.author(formatting::embed_author(
+				dm_msg.author.id,
+				&dm_msg.author.name,
+				dm_msg.author.avatar,
+			));
+
+		// stickers and markers are only shown in the thread, so they're not saved as content
+		let mut description = dm_msg.content.clone();
+		let mut add_line = |line: &str| {
+			if !description.is_empty() {
+				description.push('\n');
+			}
+			description.push_str(line);
+		};
+
+		// an embed only has one image, so any other stickers get their own
+		let mut has_image = false;
+		let mut sticker_embeds = Vec::new();
+		for sticker in &dm_msg.sticker_items {
+			add_line(&self.describe_sticker(sticker).await);
+			if let Some(url) = formatting::sticker_url(sticker) {
+				let image = ImageSource::url(url)?;
+				if !has_image {
+					has_image = true;
+					embed = embed.image(image);
+				} else {
+					sticker_embeds.push(
+						EmbedBuilder::new()
+							.color(BLANK_EMBED_COLOR)
+							.image(image)
+							.build(),
+					);
+				}
+			}
+		}
+
+		for marker in content_markers(&dm_msg) {
+			add_line(&marker);
+		}
+
+		if !description.is_empty() {
+			embed = embed.description(match filter_action {
+				Some(FilterAction::Spoiler) => spoiler(&description, DESCRIPTION_LENGTH),
+				_ => formatting::truncate(&description, DESCRIPTION_LENGTH),
+			});
+		}
+		if !filter_matches.is_empty() {
+			embed = embed.field(filter_field(&filter_matches));
+		}
+
+		// link previews and other embeds go after the message, as long as they fit
+		let mut embeds = vec![embed.build()];
+		embeds.append(&mut sticker_embeds);
+		// previews would show what the spoiler hides
+		if filter_action != Some(FilterAction::Spoiler) {
+			embeds.extend(formatting::embed_previews(&dm_msg.embeds, &embeds));
+		}
+
+		let mut thread_msg = self.http.create_message(ticket.thread_id);
+
+		// if the user replied to a message, reply to the corresponding one in the thread
+		if let (
+			MessageType::Reply,
+			Some(MessageReference {
+				message_id: Some(replied_dm_msg_id),
+				..
+			}),
+		) = (dm_msg.kind, &dm_msg.reference)
+		{
+			let replied_msg = self
+				.db
+				.message_by_dm_msg(dm_msg.author.id, *replied_dm_msg_id)
+				.await?;
+			if let Some(replied_msg) = replied_msg {
+				thread_msg = thread_msg.reply(replied_msg.thread_msg_id);
+			}
+		}
+
+		// send it to the thread
+		let thread_msg = thread_msg.embeds(&embeds)?.await?.model().await?;
+
+		// let the user know that it was sent
+		if let Err(err) = self
+			.http
+			.create_reaction(
+				dm_msg.channel_id,
+				dm_msg.id,
+				&RequestReactionType::Unicode { name: "📨" },
+			)
+			.await
+		{
+			error!(?err);
+		}
+
+		// save ids and content
+		let relayed_msg = RelayedMessage {
+			user_id: dm_msg.author.id,
+			dm_msg_id: dm_msg.id,
+			thread_msg_id: thread_msg.id,
+			thread_update_msg_id: None,
+			author_id: Some(dm_msg.author.id),
+			content: Some(dm_msg.content.clone()),
+			created_at: Some(dm_msg.timestamp.as_secs()),
+			edited_at: None,
+			deleted_at: None,
+		};
+		self.db.insert_message(&relayed_msg).await?;
+		self.queue_message_webhooks(ticket, &relayed_msg).await?;
+		self.db
+			.reset_inactivity_timer(ticket.user_id, dm_msg.timestamp.as_secs(), false)
+			.await?;
+
+		// link to attachments last, since scanning them can take a while
+		if !dm_msg.attachments.is_empty() {
+			self.relay_attachments(ticket, &dm_msg).await?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Describes the parts of a message that can't be shown in the thread as they are.
+fn content_markers(dm_msg: &Message) -> Vec<String> {
+	let mut markers = Vec::new();
+
+	let is_voice_message = dm_msg
+		.flags
+		.is_some_and(|flags| flags.contains(MessageFlags::IS_VOICE_MESSAGE));
+	if is_voice_message {
+		let duration = dm_msg
+			.attachments
+			.first()
+			.and_then(|attachment| attachment.duration_secs)
+			.unwrap_or_default()
+			.round() as u64;
+		markers.push(format!(
+			"[Voice message: {}:{:02}]",
+			duration / 60,
+			duration % 60
+		));
+	}
+
+	// this version of the discord library doesn't know about forwarded messages, but they're the
+	// only regular messages that reference another one
+	if let (MessageType::Regular, Some(reference)) = (dm_msg.kind, &dm_msg.reference) {
+		let mut marker = "[unsupported content: forwarded message".to_owned();
+		// staff can't open links to messages in someone else's DMs
+		if let (Some(guild_id), Some(channel_id), Some(message_id)) = (
+			reference.guild_id,
+			reference.channel_id,
+			reference.message_id,
+		) {
+			// can't fail
+			let _ = write!(
+				marker,
+				" from https://discord.com/channels/{guild_id}/{channel_id}/{message_id}"
+			);
+		}
+		marker.push(']');
+		markers.push(marker);
+	}
+
+	if dm_msg.activity.is_some() {
+		markers.push("[unsupported content: activity invite]".to_owned());
+	}
+	if let MessageType::Unknown(kind) = dm_msg.kind {
+		markers.push(format!("[unsupported content: message type {kind}]"));
+	}
+
+	// polls and anything newer are left out entirely
+	let is_empty = dm_msg.content.is_empty()
+		&& dm_msg.attachments.is_empty()
+		&& dm_msg.embeds.is_empty()
+		&& dm_msg.sticker_items.is_empty();
+	if is_empty && markers.is_empty() {
+		markers.push("[unsupported content: something the bot can't read, like a poll]".to_owned());
+	}
+
+	markers
+}
@@ -0,0 +1,124 @@
+use super::Bot;
+use std::{
+	fmt::Write as _,
+	time::{Duration, Instant},
+};
+use twilight::{
+	id::{GuildId, StickerId, StickerPackId},
+	model::channel::message::sticker::{MessageSticker, StickerFormatType},
+};
+
+/// Discord rarely adds sticker packs, so the list of them is only downloaded again after this long.
+const PACKS_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Where a sticker is from.
+#[derive(Clone, Copy)]
+pub enum StickerOrigin {
+	Guild(GuildId),
+	Pack(StickerPackId),
+	/// Discord didn't say.
+	Unknown,
+}
+
+impl Bot {
+	/// Names a sticker, and says where it's from if discord knows.
+	pub async fn describe_sticker(&self, sticker: &MessageSticker) -> String {
+		let mut text = format!("[Sticker: {}", sticker.name);
+
+		// lottie stickers can't be shown as images, but the animation can still be downloaded
+		if sticker.format_type == StickerFormatType::Lottie {
+			// can't fail
+			let _ = write!(
+				text,
+				", animated: https://cdn.discordapp.com/stickers/{}.json",
+				sticker.id
+			);
+		}
+
+		match self.sticker_origin(sticker.id).await {
+			StickerOrigin::Guild(guild_id) => match self.discord_cache.guild(guild_id) {
+				Some(guild) => {
+					let _ = write!(text, ", from the server {}", guild.name());
+				},
+				None => {
+					let _ = write!(text, ", from another server ({guild_id})");
+				},
+			},
+			StickerOrigin::Pack(pack_id) => match self.sticker_pack_name(pack_id).await {
+				Some(name) => {
+					let _ = write!(text, ", from the {name} pack");
+				},
+				None => {
+					let _ = write!(text, ", from pack {pack_id}");
+				},
+			},
+			StickerOrigin::Unknown => (),
+		}
+
+		text.push(']');
+		text
+	}
+
+	/// Finds where a sticker is from, only asking discord the first time it's seen.
+	async fn sticker_origin(&self, sticker_id: StickerId) -> StickerOrigin {
+		// stickers from the bot's own servers are already cached
+		if let Some(sticker) = self.discord_cache.sticker(sticker_id) {
+			return StickerOrigin::Guild(sticker.guild_id());
+		}
+		if let Some(origin) = self.sticker_origins.get(&sticker_id) {
+			return *origin;
+		}
+
+		let sticker = match self.http.sticker(sticker_id).await {
+			Ok(response) => response.model().await,
+			Err(err) => {
+				// not remembered, in case it works next time
+				debug!(?err, "unable to get sticker {sticker_id}");
+				return StickerOrigin::Unknown;
+			},
+		};
+		let origin = match sticker {
+			Ok(sticker) => match (sticker.guild_id, sticker.pack_id) {
+				(Some(guild_id), _) => StickerOrigin::Guild(guild_id),
+				(None, Some(pack_id)) => StickerOrigin::Pack(pack_id),
+				(None, None) => StickerOrigin::Unknown,
+			},
+			Err(err) => {
+				debug!(?err, "unable to get sticker {sticker_id}");
+				return StickerOrigin::Unknown;
+			},
+		};
+		self.sticker_origins.insert(sticker_id, origin);
+
+		origin
+	}
+
+	/// The name of one of discord's own sticker packs.
+	async fn sticker_pack_name(&self, pack_id: StickerPackId) -> Option<String> {
+		if let Some(name) = self.sticker_packs.get(&pack_id) {
+			return Some(name.clone());
+		}
+
+		// held while downloading, so only one download happens at a time
+		let mut fetched_at = self.sticker_packs_fetched_at.lock().await;
+		if fetched_at.is_some_and(|fetched_at| fetched_at.elapsed() < PACKS_MAX_AGE) {
+			return self.sticker_packs.get(&pack_id).map(|name| name.clone());
+		}
+
+		let packs = match self.http.nitro_sticker_packs().await {
+			Ok(response) => response.model().await.ok(),
+			Err(err) => {
+				debug!(?err, "unable to get sticker packs");
+				None
+			},
+		};
+		if let Some(packs) = packs {
+			*fetched_at = Some(Instant::now());
+			for pack in packs.sticker_packs {
+				self.sticker_packs.insert(pack.id, pack.name);
+			}
+		}
+
+		self.sticker_packs.get(&pack_id).map(|name| name.clone())
+	}
+}
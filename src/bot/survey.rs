@@ -0,0 +1,320 @@
+use super::Bot;
+use crate::{
+	config::{Rendered, Vars},
+	database::{SurveyResponse, Ticket, TicketEventKind},
+};
+use anyhow::Context;
+use std::time::{SystemTime, UNIX_EPOCH};
+use twilight::{
+	id::{ChannelId, GuildId},
+	model::{
+		application::interaction::{
+			message_component::MessageComponentInteractionData, modal::ModalInteractionData,
+			Interaction,
+		},
+		channel::message::{
+			component::{ActionRow, Button, ButtonStyle, TextInput, TextInputStyle},
+			Component,
+		},
+		http::interaction::{InteractionResponse, InteractionResponseType},
+	},
+	util::builder::InteractionResponseDataBuilder,
+	validate::component::{COMPONENT_BUTTON_LABEL_LENGTH, TEXT_INPUT_LABEL_MAX},
+};
+
+/// Rating buttons have ids like `survey:<thread id>:<guild id>:<closed at>:<rating>`.
+pub const SURVEY_ID_PREFIX: &str = "survey:";
+
+/// The comment button, and the form it opens, have ids like
+/// `survey_comment:<thread id>:<closed at>:<rating>`.
+pub const SURVEY_COMMENT_ID_PREFIX: &str = "survey_comment:";
+
+const COMMENT_INPUT_ID: &str = "comment";
+const COMMENT_MAX_LENGTH: u16 = 1000;
+/// Discord doesn't allow longer form titles.
+const FORM_TITLE_MAX: usize = 45;
+
+impl Bot {
+	/// Asks a user to rate their ticket after it was closed at `closed_at`.
+	pub async fn send_survey(
+		&self,
+		ticket: &Ticket,
+		guild_id: GuildId,
+		closed_at: i64,
+		locale: Option<&str>,
+	) -> anyhow::Result<()> {
+		let prompt_msg = self
+			.config
+			.live()
+			.messages(locale)
+			.survey_prompt
+			.render(&self.ticket_vars(ticket));
+		let buttons = (1..=5)
+			.map(|rating| {
+				Component::Button(Button {
+					custom_id: Some(format!(
+						"{SURVEY_ID_PREFIX}{}:{guild_id}:{closed_at}:{rating}",
+						ticket.thread_id
+					)),
+					disabled: false,
+					emoji: None,
+					label: Some(rating.to_string()),
+					style: ButtonStyle::Secondary,
+					url: None,
+				})
+			})
+			.collect();
+
+		self.http
+			.create_message(ticket.dm_channel_id)
+			.content(&prompt_msg.content)?
+			.embeds(&prompt_msg.embeds)?
+			.components(&[Component::ActionRow(ActionRow {
+				components: buttons,
+			})])?
+			.await?;
+
+		Ok(())
+	}
+
+	/// Saves a rating button being pressed, and lets the user add a comment.
+	pub async fn rate_ticket(
+		&self,
+		interaction: Interaction,
+		data: MessageComponentInteractionData,
+	) -> anyhow::Result<()> {
+		let parts = data
+			.custom_id
+			.strip_prefix(SURVEY_ID_PREFIX)
+			.map(|rest| rest.split(':').collect::<Vec<_>>());
+		let Some([thread_id, guild_id, closed_at, rating]) = parts.as_deref() else {
+			bail!("invalid survey button {}", data.custom_id);
+		};
+		let thread_id: ChannelId = thread_id.parse()?;
+		let guild_id: GuildId = guild_id.parse()?;
+		let closed_at: i64 = closed_at.parse()?;
+		let rating: i32 = rating.parse()?;
+		if !(1..=5).contains(&rating) {
+			bail!("invalid survey rating {rating}");
+		}
+		let user_id = interaction.author_id().context("missing author")?;
+
+		// whoever closed the ticket is who gets credit for it
+		let staff_id = self
+			.db
+			.ticket_events(user_id)
+			.await?
+			.into_iter()
+			.find(|event| event.kind == TicketEventKind::Close && event.created_at == closed_at)
+			.and_then(|event| event.staff_id);
+
+		self.db
+			.insert_survey_response(&SurveyResponse {
+				thread_id,
+				closed_at,
+				user_id,
+				guild_id,
+				staff_id,
+				rating,
+				comment: None,
+				created_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+			})
+			.await?;
+
+		let live_config = self.config.live();
+		let vars = Vars {
+			ticket_id: Some(thread_id),
+			rating: Some(rating),
+			..self.user_vars(user_id, guild_id)
+		};
+		let messages = live_config.messages(interaction.locale.as_deref());
+		let thanks_msg = messages.survey_thanks.render(&vars);
+		let comment_button = Component::ActionRow(ActionRow {
+			components: vec![Component::Button(Button {
+				custom_id: Some(format!(
+					"{SURVEY_COMMENT_ID_PREFIX}{thread_id}:{closed_at}:{rating}"
+				)),
+				disabled: false,
+				emoji: None,
+				label: Some(
+					messages
+						.survey_comment_button
+						.render_text(&vars, COMPONENT_BUTTON_LABEL_LENGTH),
+				),
+				style: ButtonStyle::Primary,
+				url: None,
+			})],
+		});
+		self.interact()
+			.create_response(
+				interaction.id,
+				&interaction.token,
+				&InteractionResponse {
+					kind: InteractionResponseType::UpdateMessage,
+					data: Some(
+						InteractionResponseDataBuilder::new()
+							.content(thanks_msg.content)
+							.embeds(thanks_msg.embeds)
+							.components([comment_button])
+							.build(),
+					),
+				},
+			)
+			.await?;
+
+		let forum_guild_id = self.config.ticket_server(Some(guild_id)).forum_guild_id;
+		let locale = self.guild_locale(forum_guild_id);
+		let rated_msg = live_config
+			.messages(locale.as_deref())
+			.survey_rated
+			.render(&vars);
+		self.post_survey_update(thread_id, &rated_msg).await
+	}
+
+	/// Opens a form for the user to comment on their rating.
+	pub async fn ask_survey_comment(
+		&self,
+		interaction: Interaction,
+		data: MessageComponentInteractionData,
+	) -> anyhow::Result<()> {
+		let live_config = self.config.live();
+		let messages = live_config.messages(interaction.locale.as_deref());
+		let vars = Vars {
+			user_id: interaction.author_id(),
+			..Vars::default()
+		};
+		let input = Component::ActionRow(ActionRow {
+			components: vec![Component::TextInput(TextInput {
+				custom_id: COMMENT_INPUT_ID.to_owned(),
+				label: messages
+					.survey_comment_label
+					.render_text(&vars, TEXT_INPUT_LABEL_MAX),
+				max_length: Some(COMMENT_MAX_LENGTH),
+				min_length: Some(1),
+				placeholder: None,
+				required: Some(true),
+				style: TextInputStyle::Paragraph,
+				value: None,
+			})],
+		});
+		let title = messages
+			.survey_comment_title
+			.render_text(&vars, FORM_TITLE_MAX);
+		self.interact()
+			.create_response(
+				interaction.id,
+				&interaction.token,
+				&InteractionResponse {
+					kind: InteractionResponseType::Modal,
+					data: Some(
+						InteractionResponseDataBuilder::new()
+							.custom_id(data.custom_id)
+							.title(title)
+							.components([input])
+							.build(),
+					),
+				},
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	/// Saves a comment from the survey form, and posts it in the ticket's thread.
+	pub async fn save_survey_comment(
+		&self,
+		interaction: Interaction,
+		data: ModalInteractionData,
+	) -> anyhow::Result<()> {
+		let parts = data
+			.custom_id
+			.strip_prefix(SURVEY_COMMENT_ID_PREFIX)
+			.map(|rest| rest.split(':').collect::<Vec<_>>());
+		let Some([thread_id, closed_at, rating]) = parts.as_deref() else {
+			bail!("invalid survey comment form {}", data.custom_id);
+		};
+		let thread_id: ChannelId = thread_id.parse()?;
+		let closed_at: i64 = closed_at.parse()?;
+		let rating: i32 = rating.parse()?;
+		let comment = data
+			.components
+			.iter()
+			.flat_map(|row| &row.components)
+			.find(|component| component.custom_id == COMMENT_INPUT_ID)
+			.and_then(|component| component.value.as_deref())
+			.map(str::trim)
+			.filter(|comment| !comment.is_empty())
+			.context("missing comment")?;
+		let user_id = interaction.author_id().context("missing author")?;
+
+		self.db
+			.set_survey_comment(thread_id, closed_at, comment)
+			.await?;
+
+		// the comment button can only be used once
+		let guild_id = self.ticket_guild_id(thread_id).await?;
+		let live_config = self.config.live();
+		let vars = Vars {
+			ticket_id: Some(thread_id),
+			rating: Some(rating),
+			message: Some(comment.to_owned()),
+			..self.user_vars(user_id, guild_id)
+		};
+		let thanks_msg = live_config
+			.messages(interaction.locale.as_deref())
+			.survey_thanks
+			.render(&vars);
+		self.interact()
+			.create_response(
+				interaction.id,
+				&interaction.token,
+				&InteractionResponse {
+					kind: InteractionResponseType::UpdateMessage,
+					data: Some(
+						InteractionResponseDataBuilder::new()
+							.content(thanks_msg.content)
+							.embeds(thanks_msg.embeds)
+							.components([])
+							.build(),
+					),
+				},
+			)
+			.await?;
+
+		let forum_guild_id = self.config.ticket_server(Some(guild_id)).forum_guild_id;
+		let locale = self.guild_locale(forum_guild_id);
+		let commented_msg = live_config
+			.messages(locale.as_deref())
+			.survey_commented
+			.render(&vars);
+		self.post_survey_update(thread_id, &commented_msg).await
+	}
+
+	/// The member server of the ticket in a thread, or the default one if it's gone.
+	async fn ticket_guild_id(&self, thread_id: ChannelId) -> anyhow::Result<GuildId> {
+		let ticket = self.db.ticket_by_thread(thread_id).await?;
+		let guild_id = ticket.and_then(|ticket| ticket.guild_id);
+		Ok(self.config.ticket_server(guild_id).guild_id)
+	}
+
+	/// Posts in a ticket's thread, archiving it again if the ticket is still closed.
+	async fn post_survey_update(&self, thread_id: ChannelId, msg: &Rendered) -> anyhow::Result<()> {
+		self.http
+			.create_message(thread_id)
+			.content(&msg.content)?
+			.embeds(&msg.embeds)?
+			.await?;
+
+		// sending a message unarchives the thread
+		let is_open = self
+			.db
+			.ticket_by_thread(thread_id)
+			.await?
+			.is_some_and(|ticket| ticket.is_open);
+		if !is_open {
+			self.http.update_thread(thread_id).archived(true).await?;
+		}
+
+		Ok(())
+	}
+}
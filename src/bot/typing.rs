@@ -0,0 +1,51 @@
+use super::Bot;
+use std::time::{Duration, Instant};
+use twilight::model::gateway::payload::incoming::TypingStart;
+
+/// Discord shows someone as typing for about 10 seconds, so there's no need to trigger it again
+/// sooner than this.
+const TYPING_DEBOUNCE: Duration = Duration::from_secs(8);
+
+impl Bot {
+	/// Shows staff typing in a ticket thread to the user, and the user typing in their DMs to staff.
+	pub async fn relay_typing(&self, typing: &TypingStart) -> anyhow::Result<()> {
+		if typing.user_id == self.user_id || !self.config.live().relay_typing {
+			return Ok(());
+		}
+
+		let ticket = match typing.guild_id {
+			None => self.db.ticket_by_dm_channel(typing.channel_id).await?,
+			Some(_) => self.db.ticket_by_thread(typing.channel_id).await?,
+		};
+		let ticket = match ticket {
+			Some(ticket) if ticket.is_open && !ticket.blocked => ticket,
+			_ => return Ok(()),
+		};
+		let channel_id = match typing.guild_id {
+			None => ticket.thread_id,
+			Some(_) => ticket.dm_channel_id,
+		};
+
+		let now = Instant::now();
+		if let Some(since) = self.typing_since.get(&channel_id) {
+			if now.duration_since(*since) < TYPING_DEBOUNCE {
+				return Ok(());
+			}
+		}
+		self.typing_since.insert(channel_id, now);
+
+		// users can close their DMs, which isn't worth an error
+		if let Err(err) = self.http.create_typing_trigger(channel_id).await {
+			debug!(?err, "unable to relay typing");
+		}
+
+		Ok(())
+	}
+
+	/// Forgets when typing was last relayed in channels that haven't had any in a while.
+	pub fn forget_old_typing(&self) {
+		let now = Instant::now();
+		self.typing_since
+			.retain(|_, since| now.duration_since(*since) < TYPING_DEBOUNCE);
+	}
+}
@@ -0,0 +1,417 @@
+use super::Bot;
+use crate::{
+	config::{WebhookConfig, WebhookEvent},
+	database::{Database, RelayedMessage, Ticket, TicketEvent, TicketEventKind, WebhookDelivery},
+};
+use hyper::{client::connect::Connect, header::CONTENT_TYPE, Body, Request};
+use ring::{
+	hmac,
+	rand::{SecureRandom, SystemRandom},
+};
+use std::{
+	fmt::Write as _,
+	sync::{atomic::Ordering, Arc},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use twilight::id::{ChannelId, GuildId, MessageId, UserId};
+
+/// How often failed deliveries are retried, when nothing new wakes up delivery sooner.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long an endpoint has to respond before the delivery counts as failed.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to wait after the first failure, which doubles after each failure after that.
+const FIRST_RETRY_SECS: i64 = 30;
+const MAX_RETRY_SECS: i64 = 60 * 60;
+
+/// Deliveries are dropped after failing this many times, which takes about a day.
+const MAX_ATTEMPTS: i32 = 30;
+
+/// The JSON that endpoints receive.
+#[derive(serde::Serialize)]
+struct Payload<'a> {
+	/// Random, and the same for every attempt, so endpoints can ignore events they already have.
+	id: String,
+	event: WebhookEvent,
+	created_at: i64,
+	ticket: PayloadTicket,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	staff_id: Option<UserId>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	reason: Option<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	message: Option<PayloadMessage<'a>>,
+}
+
+#[derive(serde::Serialize)]
+struct PayloadTicket {
+	user_id: UserId,
+	guild_id: GuildId,
+	thread_id: ChannelId,
+}
+
+#[derive(serde::Serialize)]
+struct PayloadMessage<'a> {
+	/// The message in the user's DMs.
+	id: MessageId,
+	author_id: Option<UserId>,
+	from_staff: bool,
+	content: Option<&'a str>,
+}
+
+impl Bot {
+	/// Queues webhooks for a ticket event that was just saved.
+	pub async fn queue_ticket_event_webhooks(
+		&self,
+		ticket: &Ticket,
+		event: &TicketEvent,
+	) -> anyhow::Result<()> {
+		let webhook_event = match event.kind {
+			TicketEventKind::Open => WebhookEvent::Open,
+			TicketEventKind::Close => WebhookEvent::Close,
+			TicketEventKind::Block => WebhookEvent::Block,
+			TicketEventKind::Unblock => WebhookEvent::Unblock,
+		};
+		self.queue_webhooks(&Payload {
+			id: random_id()?,
+			event: webhook_event,
+			created_at: event.created_at,
+			ticket: self.payload_ticket(ticket),
+			staff_id: event.staff_id,
+			reason: event.reason.as_deref(),
+			message: None,
+		})
+		.await
+	}
+
+	/// Queues webhooks for a message that was just relayed, in either direction.
+	pub async fn queue_message_webhooks(
+		&self,
+		ticket: &Ticket,
+		message: &RelayedMessage,
+	) -> anyhow::Result<()> {
+		let staff_id = message.author_id.filter(|_| message.is_from_staff());
+		self.queue_webhooks(&Payload {
+			id: random_id()?,
+			event: WebhookEvent::Message,
+			created_at: match message.created_at {
+				Some(created_at) => created_at,
+				None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+			},
+			ticket: self.payload_ticket(ticket),
+			staff_id,
+			reason: None,
+			message: Some(PayloadMessage {
+				id: message.dm_msg_id,
+				author_id: message.author_id,
+				from_staff: message.is_from_staff(),
+				content: message.content.as_deref(),
+			}),
+		})
+		.await
+	}
+
+	fn payload_ticket(&self, ticket: &Ticket) -> PayloadTicket {
+		PayloadTicket {
+			user_id: ticket.user_id,
+			guild_id: self.config.ticket_server(ticket.guild_id).guild_id,
+			thread_id: ticket.thread_id,
+		}
+	}
+
+	/// Saves a payload for every webhook that wants it, so it's sent even if the bot restarts.
+	async fn queue_webhooks(&self, payload: &Payload<'_>) -> anyhow::Result<()> {
+		let urls: Vec<_> = self
+			.config
+			.live()
+			.webhooks
+			.iter()
+			.filter(|webhook| webhook.wants(payload.event))
+			.map(|webhook| webhook.url.clone())
+			.collect();
+		if urls.is_empty() {
+			return Ok(());
+		}
+
+		let json = serde_json::to_string(payload)?;
+		for url in urls {
+			self.db
+				.insert_webhook_delivery(&url, payload.event, &json, payload.created_at)
+				.await?;
+		}
+		self.webhooks_queued.notify_one();
+
+		Ok(())
+	}
+
+	/// Delivers queued webhooks as they come in, and retries failed ones, until the bot stops.
+	pub fn start_webhook_deliveries(self: &Arc<Self>) {
+		let bot = Arc::clone(self);
+		tokio::spawn(async move {
+			loop {
+				if bot.stopping.load(Ordering::Acquire) {
+					break;
+				}
+
+				// run as a task so that shutting down waits for it
+				let bot_handle = Arc::clone(&bot);
+				let delivery = bot.tasks.spawn(async move {
+					if let Err(err) = bot_handle.deliver_webhooks().await {
+						error!(?err, "error delivering webhooks");
+					}
+				});
+				let _ = delivery.await;
+
+				let _ = tokio::time::timeout(CHECK_INTERVAL, bot.webhooks_queued.notified()).await;
+			}
+		});
+	}
+
+	/// Attempts every delivery that's due, rescheduling the ones that fail.
+	async fn deliver_webhooks(&self) -> anyhow::Result<()> {
+		let webhooks = self.config.live().webhooks.clone();
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+		deliver_due_webhooks(&self.db, &self.web, &webhooks, now).await
+	}
+}
+
+/// Attempts every delivery that's due at `now`, rescheduling the ones that fail.
+async fn deliver_due_webhooks<C>(
+	db: &Database,
+	web: &hyper::Client<C>,
+	webhooks: &[WebhookConfig],
+	now: i64,
+) -> anyhow::Result<()>
+where
+	C: Connect + Clone + Send + Sync + 'static,
+{
+	// an endpoint that's down would fail the rest of its deliveries too, slowly
+	let mut failed_urls = Vec::new();
+	for delivery in db.due_webhook_deliveries(now).await? {
+		if failed_urls.contains(&delivery.url) {
+			continue;
+		}
+		let Some(webhook) = webhooks.iter().find(|webhook| webhook.url == delivery.url) else {
+			debug!(
+				"dropping webhook {} to {}, it was removed",
+				delivery.id, delivery.url
+			);
+			db.delete_webhook_delivery(delivery.id).await?;
+			continue;
+		};
+
+		let err = match send_webhook(web, webhook, &delivery).await {
+			Ok(()) => {
+				db.delete_webhook_delivery(delivery.id).await?;
+				continue;
+			},
+			Err(err) => err,
+		};
+		failed_urls.push(delivery.url.clone());
+
+		let attempts = delivery.attempts + 1;
+		if attempts >= MAX_ATTEMPTS {
+			error!(
+				?err,
+				"giving up on webhook {} to {} after {attempts} attempts",
+				delivery.id,
+				delivery.url
+			);
+			db.delete_webhook_delivery(delivery.id).await?;
+		} else {
+			let delay = retry_delay(attempts);
+			warn!(
+				?err,
+				"webhook {} to {} failed, retrying in {delay} seconds", delivery.id, delivery.url
+			);
+			db.retry_webhook_delivery(delivery.id, attempts, now + delay)
+				.await?;
+		}
+	}
+
+	Ok(())
+}
+
+async fn send_webhook<C>(
+	web: &hyper::Client<C>,
+	webhook: &WebhookConfig,
+	delivery: &WebhookDelivery,
+) -> anyhow::Result<()>
+where
+	C: Connect + Clone + Send + Sync + 'static,
+{
+	let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+	let request = Request::post(&webhook.url)
+		.header(CONTENT_TYPE, "application/json")
+		.header("X-Modmail-Event", delivery.event.name())
+		.header("X-Modmail-Timestamp", timestamp)
+		.header(
+			"X-Modmail-Signature",
+			signature(&webhook.secret, timestamp, &delivery.payload),
+		)
+		.body(Body::from(delivery.payload.clone()))?;
+
+	let response = tokio::time::timeout(REQUEST_TIMEOUT, web.request(request))
+		.await
+		.map_err(|_| anyhow!("no response after {REQUEST_TIMEOUT:?}"))??;
+	if !response.status().is_success() {
+		bail!("endpoint responded with {}", response.status());
+	}
+
+	Ok(())
+}
+
+/// How long to wait before the next attempt, after a delivery failed `attempts` times.
+fn retry_delay(attempts: i32) -> i64 {
+	FIRST_RETRY_SECS
+		.saturating_mul(1 << (attempts - 1).clamp(0, 20))
+		.min(MAX_RETRY_SECS)
+}
+
+/// Signs a payload as `sha256=<hex>`, with the HMAC-SHA256 of `<timestamp>.<payload>`.
+///
+/// The timestamp is signed too, so endpoints can reject old requests that are sent again.
+fn signature(secret: &str, timestamp: i64, payload: &str) -> String {
+	let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+	let tag = hmac::sign(&key, format!("{timestamp}.{payload}").as_bytes());
+	format!("sha256={}", hex(tag.as_ref()))
+}
+
+fn random_id() -> anyhow::Result<String> {
+	let mut bytes = [0; 16];
+	SystemRandom::new()
+		.fill(&mut bytes)
+		.map_err(|_| anyhow!("unable to generate a random id"))?;
+	Ok(hex(&bytes))
+}
+
+fn hex(bytes: &[u8]) -> String {
+	let mut text = String::with_capacity(bytes.len() * 2);
+	for byte in bytes {
+		// can't fail
+		let _ = write!(text, "{byte:02x}");
+	}
+	text
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::database::testing;
+	use hyper::{
+		service::{make_service_fn, service_fn},
+		Response, Server, StatusCode,
+	};
+	use std::{
+		convert::Infallible,
+		net::SocketAddr,
+		sync::{atomic::AtomicU32, Mutex},
+	};
+
+	#[test]
+	fn signature_is_hmac_sha256_of_timestamp_and_payload() {
+		assert_eq!(
+			signature("secret", 1700000000, r#"{"id":"abc"}"#),
+			"sha256=5ad265e6615b64b835cae994e1526056136c85c5a0d090d4f35b730288b456de",
+		);
+	}
+
+	#[test]
+	fn retry_delay_doubles_up_to_an_hour() {
+		let delays: Vec<_> = (1..=9).map(retry_delay).collect();
+		assert_eq!(delays, [30, 60, 120, 240, 480, 960, 1920, 3600, 3600]);
+		assert_eq!(retry_delay(MAX_ATTEMPTS), MAX_RETRY_SECS);
+		assert_eq!(retry_delay(i32::MAX), MAX_RETRY_SECS);
+	}
+
+	#[tokio::test]
+	async fn failed_deliveries_are_retried_then_deleted() {
+		for test_db in testing::databases().await {
+			println!("testing {}", test_db.backend);
+			retried_then_deleted(&test_db.db).await;
+			test_db.finish().await;
+		}
+	}
+
+	async fn retried_then_deleted(db: &Database) {
+		// fails the first request, and records the headers of every request
+		let requests = Arc::new(AtomicU32::new(0));
+		let received = Arc::new(Mutex::new(Vec::new()));
+		let make_service = {
+			let requests = Arc::clone(&requests);
+			let received = Arc::clone(&received);
+			make_service_fn(move |_| {
+				let requests = Arc::clone(&requests);
+				let received = Arc::clone(&received);
+				async move {
+					Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+						let requests = Arc::clone(&requests);
+						let received = Arc::clone(&received);
+						async move {
+							let header = |name| {
+								let value = request.headers().get(name).unwrap();
+								value.to_str().unwrap().to_owned()
+							};
+							let timestamp: i64 = header("X-Modmail-Timestamp").parse().unwrap();
+							let signature = header("X-Modmail-Signature");
+							let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+							let body = String::from_utf8(body.to_vec()).unwrap();
+							received.lock().unwrap().push((timestamp, signature, body));
+
+							let status = match requests.fetch_add(1, Ordering::SeqCst) {
+								0 => StatusCode::INTERNAL_SERVER_ERROR,
+								_ => StatusCode::NO_CONTENT,
+							};
+							let mut response = Response::new(Body::empty());
+							*response.status_mut() = status;
+							Ok::<_, Infallible>(response)
+						}
+					}))
+				}
+			})
+		};
+		let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+		let url = format!("http://{}/hook", server.local_addr());
+		tokio::spawn(server);
+
+		let webhooks = [WebhookConfig {
+			url: url.clone(),
+			secret: "secret".to_owned(),
+			events: None,
+		}];
+		let web = hyper::Client::new();
+		let payload = r#"{"id":"abc"}"#;
+		let now = 1700000000;
+		db.insert_webhook_delivery(&url, WebhookEvent::Open, payload, now)
+			.await
+			.unwrap();
+
+		// the first attempt fails, so it's pushed back
+		deliver_due_webhooks(db, &web, &webhooks, now)
+			.await
+			.unwrap();
+		assert!(db.due_webhook_deliveries(now).await.unwrap().is_empty());
+		let retry_at = now + retry_delay(1);
+		let due = db.due_webhook_deliveries(retry_at).await.unwrap();
+		assert_eq!(due.len(), 1);
+		assert_eq!(due[0].attempts, 1);
+
+		// the second one works, so it's gone
+		deliver_due_webhooks(db, &web, &webhooks, retry_at)
+			.await
+			.unwrap();
+		assert!(db
+			.due_webhook_deliveries(i64::MAX)
+			.await
+			.unwrap()
+			.is_empty());
+
+		let received = received.lock().unwrap();
+		assert_eq!(received.len(), 2);
+		for (timestamp, signature, body) in received.iter() {
+			assert_eq!(body, payload);
+			assert_eq!(*signature, super::signature("secret", *timestamp, payload));
+		}
+	}
+}
@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+
+#[derive(clap::Parser, Clone)]
+#[command(version, about, after_help = after_help())]
+pub struct Args {
+	#[command(subcommand)]
+	pub command: Option<Command>,
+
+	/// Path to the config file [default: <DATA_DIR>/config.yml]
+	#[arg(long, global = true, env = "MODMAIL_CONFIG", value_name = "PATH")]
+	pub config: Option<PathBuf>,
+
+	/// SQLite database path or PostgreSQL URL, overriding `database` in the config file
+	/// [default: <DATA_DIR>/db.sqlite]
+	#[arg(
+		long,
+		global = true,
+		env = "MODMAIL_DATABASE",
+		value_name = "PATH_OR_URL"
+	)]
+	pub database: Option<String>,
+
+	/// Directory that holds the default config and database files
+	#[arg(
+		long,
+		global = true,
+		env = "MODMAIL_DATA_DIR",
+		value_name = "DIR",
+		default_value = "."
+	)]
+	pub data_dir: PathBuf,
+}
+
+#[derive(clap::Subcommand, Clone)]
+pub enum Command {
+	/// Check the config for problems without starting the bot
+	///
+	/// Every problem in the config file is reported at once. This doesn't connect to anything
+	/// unless --online is given.
+	CheckConfig {
+		/// Also connect to discord and check that the configured servers, channel, and roles exist
+		#[arg(long)]
+		online: bool,
+	},
+}
+
+impl Args {
+	pub fn config_path(&self) -> PathBuf {
+		match &self.config {
+			Some(path) => path.clone(),
+			None => self.data_dir.join("config.yml"),
+		}
+	}
+
+	/// Resolves a path from the config file, which is relative to the data directory.
+	pub fn data_path(&self, path: &Path) -> PathBuf {
+		self.data_dir.join(path)
+	}
+}
+
+fn after_help() -> String {
+	formatdoc! {"
+		Settings are taken from these places, highest priority first:
+		  1. Command-line flags
+		  2. Environment variables
+		  3. The config file
+		  4. Defaults
+
+		Every config file field except messages, auto_close, filters, attachments.scanner, and
+		webhooks can be set with an environment variable instead:
+		  MODMAIL_TOKEN                           token
+		  MODMAIL_GUILD_ID                        guild_id
+		  MODMAIL_FORUM_CHANNEL_ID                forum_channel_id
+		  MODMAIL_FORUM_GUILD_ID                  forum_guild_id
+		  MODMAIL_PING_ROLES                      ping_roles, as comma-separated role IDs
+		  MODMAIL_MOD_ACTIONS                     mod_actions, as comma-separated action names
+		  MODMAIL_REACTIONS_ALLOWED               reactions.allowed, as comma-separated emoji
+		  MODMAIL_REACTIONS_PER_MINUTE            reactions.per_minute
+		  MODMAIL_RELAY_TYPING                    relay_typing
+		  MODMAIL_SURVEY                          survey
+		  MODMAIL_CLOSE_COOLDOWN_MINUTES          close_cooldown.minutes
+		  MODMAIL_CLOSE_COOLDOWN_ACTION           close_cooldown.action
+		  MODMAIL_RATE_LIMIT_PER_MINUTE           rate_limit.per_minute
+		  MODMAIL_RATE_LIMIT_BURST                rate_limit.burst
+		  MODMAIL_RATE_LIMIT_BLOCK_AFTER          rate_limit.block_after
+		  MODMAIL_ATTACHMENTS_ALLOWED_EXTENSIONS  attachments.allowed_extensions, comma-separated
+		  MODMAIL_ATTACHMENTS_DENIED_EXTENSIONS   attachments.denied_extensions, comma-separated
+		  MODMAIL_ATTACHMENTS_ALLOWED_TYPES       attachments.allowed_types, comma-separated
+		  MODMAIL_ATTACHMENTS_DENIED_TYPES        attachments.denied_types, comma-separated
+		  MODMAIL_ATTACHMENTS_MAX_SIZE_MB         attachments.max_size_mb
+		  MODMAIL_ATTACHMENTS_SPOILER_IMAGES      attachments.spoiler_images
+		  MODMAIL_OPEN_MESSAGE                    open_message
+		  MODMAIL_CLOSE_MESSAGE                   close_message
+		  MODMAIL_LOCALES_DIR                     locales_dir
+		  MODMAIL_DEFAULT_LOCALE                  default_locale
+		  MODMAIL_DATABASE                        database, the same as --database
+
+		Relative paths inside the config file are resolved from the data directory. Relative paths
+		given as flags or environment variables are resolved from the working directory.
+
+		If --config isn't given and the default config file doesn't exist, every required field
+		(token, guild_id, forum_channel_id) must be set with environment variables.
+
+		The bot refuses to start if a value is invalid, and the error names the field. IDs must be
+		non-zero integers.
+
+		The README describes what each config field does.
+	"}
+}
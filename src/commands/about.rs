@@ -1,4 +1,4 @@
-use crate::{bot::Bot, util::InteractionResponseDataExt};
+use crate::{bot::Bot, config::Locales, util::InteractionResponseDataExt};
 use std::sync::Arc;
 use twilight::{
 	model::{
@@ -13,12 +13,14 @@ use twilight::{
 
 pub const NAME: &str = "about";
 
-pub fn info() -> Command {
+pub fn info(locales: &Locales) -> Command {
 	CommandBuilder::new(
 		NAME,
 		"View information about the bot",
 		CommandType::ChatInput,
 	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
 	.build()
 }
 
@@ -0,0 +1,34 @@
+use crate::{bot::Bot, config::Locales, database::ModerationKind};
+use std::sync::Arc;
+use twilight::{
+	model::{
+		application::{
+			command::{Command, CommandType},
+			interaction::{application_command::CommandData, Interaction},
+		},
+		guild::Permissions,
+	},
+	util::builder::command::CommandBuilder,
+};
+
+pub const NAME: &str = "ban";
+
+pub fn info(locales: &Locales) -> Command {
+	let command = CommandBuilder::new(
+		NAME,
+		"Ban this ticket's user from the server",
+		CommandType::ChatInput,
+	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
+	.default_member_permissions(Permissions::BAN_MEMBERS);
+	super::moderation::options(command, NAME, locales).build()
+}
+
+pub async fn run(
+	bot: &Arc<Bot>,
+	interaction: Interaction,
+	cmd_data: CommandData,
+) -> anyhow::Result<()> {
+	super::moderation::run(bot, interaction, cmd_data, ModerationKind::Ban).await
+}
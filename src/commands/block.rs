@@ -0,0 +1,138 @@
+use crate::{
+	bot::Bot,
+	config::Locales,
+	database::{TicketEvent, TicketEventKind},
+	util::{DEFER, GREEN, RED},
+};
+use anyhow::Context;
+use std::{
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+use twilight::{
+	model::{
+		application::{
+			command::{Command, CommandOption, CommandType},
+			interaction::{
+				application_command::{CommandData, CommandOptionValue},
+				Interaction,
+			},
+		},
+		guild::Permissions,
+	},
+	util::builder::{
+		command::{CommandBuilder, StringBuilder},
+		embed::{EmbedBuilder, EmbedFieldBuilder},
+	},
+	validate::embed::FIELD_VALUE_LENGTH,
+};
+
+pub const NAME: &str = "block";
+
+pub fn info(locales: &Locales) -> Command {
+	CommandBuilder::new(
+		NAME,
+		"Ignore everything this ticket's user sends",
+		CommandType::ChatInput,
+	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
+	.default_member_permissions(Permissions::ADMINISTRATOR)
+	.option(reason_option(NAME, locales))
+	.build()
+}
+
+/// The `reason` option, which `/unblock` and `/close` have too.
+pub fn reason_option(name: &str, locales: &Locales) -> CommandOption {
+	StringBuilder::new("reason", "Why, for the user's history")
+		.name_localizations(locales.option_names(name, "reason"))
+		.description_localizations(locales.option_descriptions(name, "reason"))
+		.min_length(1)
+		.max_length(FIELD_VALUE_LENGTH as u16)
+		.build()
+}
+
+pub async fn run(
+	bot: &Arc<Bot>,
+	interaction: Interaction,
+	cmd_data: CommandData,
+) -> anyhow::Result<()> {
+	set_blocked(bot, interaction, cmd_data, true).await
+}
+
+/// Blocks or unblocks the user of the ticket the command was used in.
+pub async fn set_blocked(
+	bot: &Arc<Bot>,
+	interaction: Interaction,
+	cmd_data: CommandData,
+	blocked: bool,
+) -> anyhow::Result<()> {
+	let Some(thread_id) = super::only_in_modmail_thread(bot, &interaction).await? else {
+		return Ok(());
+	};
+
+	// get argument
+	let reason = match cmd_data.options.into_iter().next().map(|opt| opt.value) {
+		Some(CommandOptionValue::String(reason)) => Some(reason),
+		_ => None,
+	};
+
+	bot.interact()
+		.create_response(interaction.id, &interaction.token, &DEFER)
+		.await?;
+
+	let ticket = bot
+		.db
+		.ticket_by_thread(thread_id)
+		.await?
+		.context("missing ticket")?;
+
+	if ticket.blocked == blocked {
+		bot.interact()
+			.update_response(&interaction.token)
+			.content(Some(if blocked {
+				"This user is already blocked."
+			} else {
+				"This user isn't blocked."
+			}))?
+			.await?;
+		return Ok(());
+	}
+
+	let staff_id = interaction.author_id().context("missing author")?;
+	bot.db.set_ticket_blocked(ticket.user_id, blocked).await?;
+	let event = TicketEvent {
+		user_id: ticket.user_id,
+		kind: if blocked {
+			TicketEventKind::Block
+		} else {
+			TicketEventKind::Unblock
+		},
+		staff_id: Some(staff_id),
+		reason: reason.clone(),
+		created_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+	};
+	bot.db.insert_ticket_event(&event).await?;
+	bot.queue_ticket_event_webhooks(&ticket, &event).await?;
+
+	// respond to the interaction
+	let mut embed = if blocked {
+		EmbedBuilder::new().color(RED).description(format!(
+			"⛔ User was blocked by <@{staff_id}>. Their messages will be ignored until they're \
+			 unblocked."
+		))
+	} else {
+		EmbedBuilder::new()
+			.color(GREEN)
+			.description(format!("✅ User was unblocked by <@{staff_id}>."))
+	};
+	if let Some(reason) = reason {
+		embed = embed.field(EmbedFieldBuilder::new("Reason", reason).build());
+	}
+	bot.interact()
+		.update_response(&interaction.token)
+		.embeds(Some(&[embed.build()]))?
+		.await?;
+
+	Ok(())
+}
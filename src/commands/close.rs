@@ -1,4 +1,4 @@
-use crate::{bot::Bot, util::DEFER};
+use crate::{bot::Bot, config::Locales, util::DEFER};
 use anyhow::Context;
 use std::sync::Arc;
 use twilight::{
@@ -17,13 +17,27 @@ use twilight::{
 
 pub const NAME: &str = "close";
 
-pub fn info() -> Command {
+pub fn info(locales: &Locales) -> Command {
 	CommandBuilder::new(NAME, "Close this modmail ticket", CommandType::ChatInput)
+		.name_localizations(locales.command_names(NAME))
+		.description_localizations(locales.command_descriptions(NAME))
 		.default_member_permissions(Permissions::ADMINISTRATOR)
-		.option(BooleanBuilder::new(
-			"silent",
-			"Whether to close the ticket without sending the user a message",
-		))
+		.option(
+			BooleanBuilder::new(
+				"silent",
+				"Whether to close the ticket without sending the user a message",
+			)
+			.name_localizations(locales.option_names(NAME, "silent"))
+			.description_localizations(locales.option_descriptions(NAME, "silent"))
+			.build(),
+		)
+		.option(super::block::reason_option(NAME, locales))
+		.option(
+			BooleanBuilder::new("share_reason", "Whether to tell the user the reason")
+				.name_localizations(locales.option_names(NAME, "share_reason"))
+				.description_localizations(locales.option_descriptions(NAME, "share_reason"))
+				.build(),
+		)
 		.build()
 }
 
@@ -37,11 +51,19 @@ pub async fn run(
 		return Ok(());
 	};
 
-	// get argument
-	let silent = match cmd_data.options.into_iter().next().map(|opt| opt.value) {
-		Some(CommandOptionValue::Boolean(value)) => value,
-		_ => false,
-	};
+	// get arguments
+	let mut silent = false;
+	let mut reason = None;
+	let mut share_reason = false;
+	for opt in cmd_data.options {
+		match (&opt.name as &str, opt.value) {
+			("silent", CommandOptionValue::Boolean(value)) => silent = value,
+			("reason", CommandOptionValue::String(s)) => reason = Some(s),
+			("share_reason", CommandOptionValue::Boolean(value)) => share_reason = value,
+			_ => (),
+		}
+	}
+	let staff_id = interaction.author_id().context("missing author")?;
 
 	bot.interact()
 		.create_response(interaction.id, &interaction.token, &DEFER)
@@ -66,7 +88,8 @@ pub async fn run(
 		error!(?err);
 	}
 
-	bot.close_ticket(&mut ticket, !silent).await?;
+	bot.close_ticket(&mut ticket, !silent, Some(staff_id), reason, share_reason)
+		.await?;
 
 	Ok(())
 }
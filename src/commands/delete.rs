@@ -1,9 +1,9 @@
 use crate::{
 	bot::Bot,
-	util::{InteractionResponseDataExt, SqliteId, DEFER, RED},
+	config::Locales,
+	util::{InteractionResponseDataExt, DEFER, RED},
 };
 use anyhow::Context;
-use sqlx::Row;
 use std::{str::FromStr, sync::Arc};
 use twilight::{
 	id::MessageId,
@@ -27,15 +27,19 @@ use twilight::{
 
 pub const NAME: &str = "delete";
 
-pub fn info() -> Command {
+pub fn info(locales: &Locales) -> Command {
 	CommandBuilder::new(
 		NAME,
 		"Delete a message sent in this ticket",
 		CommandType::ChatInput,
 	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
 	.default_member_permissions(Permissions::ADMINISTRATOR)
 	.option(
 		StringBuilder::new("id", "The ID of the message to delete")
+			.name_localizations(locales.option_names(NAME, "id"))
+			.description_localizations(locales.option_descriptions(NAME, "id"))
 			.required(true)
 			.min_length(1)
 			.max_length(20)
@@ -55,7 +59,7 @@ pub async fn run(
 	};
 
 	// parse options
-	let Some(thread_msg_id) = cmd_data.options.get(0).and_then(|opt| {
+	let Some(thread_msg_id) = cmd_data.options.first().and_then(|opt| {
 		if let CommandOptionValue::String(id_str) = &opt.value {
 			MessageId::from_str(id_str).ok()
 		} else {
@@ -79,58 +83,23 @@ pub async fn run(
 		.create_response(interaction.id, &interaction.token, &DEFER)
 		.await?;
 
-	// fetch the thread message they want to delete
-	let thread_msg = match bot.http.message(thread_id, thread_msg_id).await {
-		Ok(response) => response.model().await?,
-		Err(_) => {
-			bot.interact()
-				.update_response(&interaction.token)
-				.content(Some(
-					"I couldn't find a message to delete. Did you copy the correct ID?",
-				))?
-				.await?;
-			return Ok(());
-		},
-	};
-
-	// make sure we can actually use it
-	if thread_msg.author.id != bot.user_id
-		|| thread_msg.interaction.as_ref().map_or(true, |i| {
-			!super::VALID_SENDING_COMMANDS.iter().any(|&n| i.name == n)
-		}) {
-		bot.interact()
-			.update_response(&interaction.token)
-			.content(Some("I can only delete messages I sent."))?
-			.await?;
-		return Ok(());
-	}
-
 	let ticket = bot
 		.db
 		.ticket_by_thread(thread_id)
 		.await?
 		.context("missing ticket")?;
 
-	// we don't need to delete it from the database.
-	// ../events/message_delete.rs already handles that for us
-	let dm_msg_id = sqlx::query(indoc! {"
-		SELECT dm_msg_id FROM messages
-		WHERE user_id = ? AND thread_msg_id = ?
-		ORDER BY rowid DESC
-	"})
-	.bind(SqliteId(ticket.user_id))
-	.bind(SqliteId(thread_msg_id))
-	.try_map(|row| {
-		let id: SqliteId<MessageId> = row.try_get(0)?;
-		Ok(*id)
-	})
-	.fetch_optional(&bot.db.connection)
-	.await?
-	.context("missing dm_msg_id")?;
+	let Some(msg) =
+		super::staff_msg_in_thread(bot, &interaction, ticket.user_id, thread_msg_id, "delete")
+			.await?
+	else {
+		return Ok(());
+	};
 
-	// delete the dm
+	// we don't need to mark it as deleted in the database.
+	// ../events/message_delete.rs already handles that for us
 	bot.http
-		.delete_message(ticket.dm_channel_id, dm_msg_id)
+		.delete_message(ticket.dm_channel_id, msg.dm_msg_id)
 		.await?;
 
 	// respond to the interaction
@@ -138,7 +107,9 @@ pub async fn run(
 		.color(RED)
 		.description(format!(
 			"🗑️ Deleted https://discord.com/channels/{}/{}/{}",
-			bot.config.guild_id, thread_id, thread_msg_id,
+			interaction.guild_id.context("missing guild")?,
+			thread_id,
+			thread_msg_id,
 		))
 		.build();
 	bot.interact()
@@ -148,23 +119,12 @@ pub async fn run(
 
 	// edit the corresponding thread message
 
-	let mut old_embed: EmbedBuilder = thread_msg
-		.embeds
-		.into_iter()
-		.next()
-		.context("missing embed")?
-		.into();
+	let mut old_embed = EmbedBuilder::new()
+		.color(RED)
+		.description(msg.content.as_ref().context("missing content")?);
 
-	old_embed = old_embed.color(RED);
 	let deleting_author_id = interaction.author_id().context("missing author")?;
-	let original_author_id = thread_msg
-		.interaction
-		.as_ref()
-		.context("missing interaction")?
-		.user
-		.id;
-
-	if deleting_author_id == original_author_id {
+	if Some(deleting_author_id) == msg.author_id {
 		old_embed = old_embed.footer(EmbedFooterBuilder::new("🗑️ Deleted"));
 	} else {
 		old_embed = old_embed.field(EmbedFieldBuilder::new(
@@ -1,10 +1,14 @@
 use crate::{
 	bot::Bot,
-	util::{InteractionResponseDataExt, SqliteId, DEFER, GREEN, YELLOW},
+	config::{Locales, Vars},
+	util::{InteractionResponseDataExt, DEFER, GREEN, YELLOW},
 };
 use anyhow::Context;
-use sqlx::Row;
-use std::{str::FromStr, sync::Arc};
+use std::{
+	str::FromStr,
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
 use twilight::{
 	id::MessageId,
 	model::{
@@ -28,15 +32,19 @@ use twilight::{
 
 pub const NAME: &str = "edit";
 
-pub fn info() -> Command {
+pub fn info(locales: &Locales) -> Command {
 	CommandBuilder::new(
 		NAME,
 		"Edit a message sent in this ticket",
 		CommandType::ChatInput,
 	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
 	.default_member_permissions(Permissions::ADMINISTRATOR)
 	.option(
 		StringBuilder::new("id", "The ID of the message to edit")
+			.name_localizations(locales.option_names(NAME, "id"))
+			.description_localizations(locales.option_descriptions(NAME, "id"))
 			.required(true)
 			.min_length(1)
 			.max_length(20)
@@ -44,6 +52,8 @@ pub fn info() -> Command {
 	)
 	.option(
 		StringBuilder::new("to", "What to edit the message to")
+			.name_localizations(locales.option_names(NAME, "to"))
+			.description_localizations(locales.option_descriptions(NAME, "to"))
 			.required(true)
 			.min_length(1)
 			.max_length(MESSAGE_CONTENT_LENGTH_MAX as u16)
@@ -92,76 +102,56 @@ pub async fn run(
 		.create_response(interaction.id, &interaction.token, &DEFER)
 		.await?;
 
-	// fetch the thread message that they want to edit
-	let thread_msg = match bot.http.message(thread_id, thread_msg_id).await {
-		Ok(response) => response.model().await?,
-		Err(_) => {
-			bot.interact()
-				.update_response(&interaction.token)
-				.content(Some(
-					"I couldn't find a message to edit. Did you copy the correct ID?",
-				))?
-				.await?;
-			return Ok(());
-		},
-	};
-
-	// make sure we can actually use it
-	if thread_msg.author.id != bot.user_id
-		|| thread_msg.interaction.as_ref().map_or(true, |i| {
-			!super::VALID_SENDING_COMMANDS.iter().any(|&n| i.name == n)
-		}) {
-		bot.interact()
-			.update_response(&interaction.token)
-			.content(Some("I can only edit messages I sent."))?
-			.await?;
-		return Ok(());
-	}
-
 	let ticket = bot
 		.db
 		.ticket_by_thread(thread_id)
 		.await?
 		.context("missing ticket")?;
 
-	let editing_dm_msg_id = sqlx::query(indoc! {"
-		SELECT dm_msg_id FROM messages
-		WHERE user_id = ? AND thread_msg_id = ?
-		ORDER BY rowid DESC
-	"})
-	.bind(SqliteId(ticket.user_id))
-	.bind(SqliteId(thread_msg_id))
-	.try_map(|row| {
-		let id: SqliteId<MessageId> = row.try_get(0)?;
-		Ok(*id)
-	})
-	.fetch_optional(&bot.db.connection)
-	.await?
-	.context("missing dm_msg_id")?;
-
-	// edit the dm
-	bot.http
-		.update_message(ticket.dm_channel_id, editing_dm_msg_id)
-		.content(Some(new_content))?
-		.await?;
+	let Some(msg) =
+		super::staff_msg_in_thread(bot, &interaction, ticket.user_id, thread_msg_id, "edit")
+			.await?
+	else {
+		return Ok(());
+	};
 
-	let mut old_embed = thread_msg
-		.embeds
-		.into_iter()
-		.next()
-		.context("missing embed")?;
+	// edit the dm, sending it the same way as the original reply
+	let staff_id = msg.author_id.context("missing author")?;
+	let live_config = bot.config.live();
+	let locale = bot
+		.user_locale(
+			ticket.user_id,
+			bot.config.ticket_server(ticket.guild_id).guild_id,
+		)
+		.await?;
+	let dm_msg = live_config.messages(locale.as_deref()).reply.render(&Vars {
+		staff_id: Some(staff_id),
+		staff_name: bot
+			.discord_cache
+			.user(staff_id)
+			.map(|user| user.name.clone()),
+		message: Some(new_content.clone()),
+		..bot.ticket_vars(&ticket)
+	});
+	let edited_dm_msg = bot
+		.http
+		.update_message(ticket.dm_channel_id, msg.dm_msg_id)
+		.content(Some(&dm_msg.content))?
+		.embeds(Some(&dm_msg.embeds))?
+		.await?
+		.model()
+		.await?;
 
-	let old_content = old_embed
-		.description
-		.as_ref()
-		.context("missing description")?;
+	let old_content = msg.content.as_ref().context("missing content")?;
 
 	// respond to the interaction
 	let embed = EmbedBuilder::new()
 		.color(YELLOW)
 		.description(format!(
 			"✏️ Edited https://discord.com/channels/{}/{}/{}",
-			bot.config.forum_guild_id, thread_id, thread_msg_id,
+			interaction.guild_id.context("missing guild")?,
+			thread_id,
+			thread_msg_id,
 		))
 		.field(EmbedFieldBuilder::new("Before", old_content).build())
 		.field(EmbedFieldBuilder::new("After", new_content).build())
@@ -173,21 +163,11 @@ pub async fn run(
 
 	// edit the corresponding thread message
 
-	old_embed.fields.clear(); // get rid of existing "edited by" field if it's there
-	let mut old_embed = EmbedBuilder::from(old_embed)
-		.color(GREEN)
-		.description(new_content);
+	let mut new_embed = EmbedBuilder::new().color(GREEN).description(new_content);
 
 	let editing_author_id = interaction.author_id().context("missing author")?;
-	let original_author_id = thread_msg
-		.interaction
-		.as_ref()
-		.context("missing interaction")?
-		.user
-		.id;
-
-	if editing_author_id != original_author_id {
-		old_embed = old_embed.field(EmbedFieldBuilder::new(
+	if Some(editing_author_id) != msg.author_id {
+		new_embed = new_embed.field(EmbedFieldBuilder::new(
 			"✏️ Edited by",
 			format!("<@{editing_author_id}>"),
 		));
@@ -195,7 +175,15 @@ pub async fn run(
 
 	bot.http
 		.update_message(thread_id, thread_msg_id)
-		.embeds(Some(&[old_embed.build()]))?
+		.embeds(Some(&[new_embed.build()]))?
+		.await?;
+
+	let edited_at = match edited_dm_msg.edited_timestamp {
+		Some(timestamp) => timestamp.as_secs(),
+		None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+	};
+	bot.db
+		.edit_message(ticket.user_id, msg.dm_msg_id, None, new_content, edited_at)
 		.await?;
 
 	Ok(())
@@ -0,0 +1,132 @@
+use crate::{
+	bot::{spoiler, Bot},
+	config::Locales,
+	util::{formatting, InteractionResponseDataExt, BLURPLE},
+};
+use std::{
+	collections::BTreeMap,
+	fmt::Write as _,
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+use twilight::{
+	model::{
+		application::{
+			command::{Command, CommandType},
+			interaction::{
+				application_command::{CommandData, CommandOptionValue},
+				Interaction,
+			},
+		},
+		channel::message::MessageFlags,
+		guild::Permissions,
+	},
+	util::builder::{
+		command::{CommandBuilder, IntegerBuilder},
+		embed::{EmbedBuilder, EmbedFieldBuilder},
+		InteractionResponseDataBuilder,
+	},
+	validate::embed::FIELD_VALUE_LENGTH,
+};
+
+pub const NAME: &str = "filter-hits";
+
+const DEFAULT_DAYS: i64 = 30;
+const LATEST_COUNT: usize = 10;
+
+pub fn info(locales: &Locales) -> Command {
+	CommandBuilder::new(
+		NAME,
+		"View which messages matched content filters recently",
+		CommandType::ChatInput,
+	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
+	.default_member_permissions(Permissions::ADMINISTRATOR)
+	.option(
+		IntegerBuilder::new("days", "How many days to look back (30 by default)")
+			.name_localizations(locales.option_names(NAME, "days"))
+			.description_localizations(locales.option_descriptions(NAME, "days"))
+			.min_value(1)
+			.max_value(365)
+			.build(),
+	)
+	.build()
+}
+
+pub async fn run(
+	bot: &Arc<Bot>,
+	interaction: Interaction,
+	cmd_data: CommandData,
+) -> anyhow::Result<()> {
+	// get argument
+	let days = match cmd_data.options.into_iter().next().map(|opt| opt.value) {
+		Some(CommandOptionValue::Integer(days)) => days,
+		_ => DEFAULT_DAYS,
+	};
+
+	let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+	let hits = bot.db.filter_hits(now - days * 24 * 60 * 60).await?;
+
+	let mut embed = EmbedBuilder::new()
+		.color(BLURPLE)
+		.title("Filter hits")
+		.description(format!("{} hit(s) in the last {days} day(s)", hits.len()));
+
+	if !hits.is_empty() {
+		let mut by_filter = BTreeMap::<&str, usize>::new();
+		for hit in &hits {
+			*by_filter.entry(&hit.filter).or_default() += 1;
+		}
+		let mut by_filter: Vec<_> = by_filter.into_iter().collect();
+		by_filter.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+		let mut filter_text = String::new();
+		for (filter, count) in by_filter {
+			writeln!(
+				filter_text,
+				"**{}**: {count}",
+				formatting::escape_markdown(filter)
+			)?;
+		}
+
+		// a line at a time, so spoilers are never cut off
+		let mut latest_text = String::new();
+		for hit in hits.iter().take(LATEST_COUNT) {
+			let line = format!(
+				"<t:{}:f> <@{}> **{}** ({}): {}\n",
+				hit.created_at,
+				hit.user_id,
+				formatting::escape_markdown(&hit.filter),
+				hit.action.name(),
+				spoiler(&formatting::escape_markdown(&hit.matched), 100),
+			);
+			if latest_text.chars().count() + line.chars().count() > FIELD_VALUE_LENGTH {
+				break;
+			}
+			latest_text.push_str(&line);
+		}
+
+		embed = embed
+			.field(
+				EmbedFieldBuilder::new(
+					"By filter",
+					formatting::truncate(&filter_text, FIELD_VALUE_LENGTH),
+				)
+				.build(),
+			)
+			.field(EmbedFieldBuilder::new("Latest", latest_text).build());
+	}
+
+	bot.interact()
+		.create_response(
+			interaction.id,
+			&interaction.token,
+			&InteractionResponseDataBuilder::new()
+				.flags(MessageFlags::EPHEMERAL)
+				.embeds([embed.build()])
+				.into_response(),
+		)
+		.await?;
+
+	Ok(())
+}
@@ -0,0 +1,210 @@
+use crate::{
+	bot::Bot,
+	config::Locales,
+	util::{formatting, InteractionResponseDataExt, BLURPLE, DEFER},
+};
+use anyhow::Context;
+use std::{fmt::Write as _, str::FromStr, sync::Arc};
+use twilight::{
+	id::MessageId,
+	model::{
+		application::{
+			command::{Command, CommandType},
+			interaction::{
+				application_command::{CommandData, CommandOptionValue},
+				Interaction,
+			},
+		},
+		channel::message::MessageFlags,
+		guild::Permissions,
+	},
+	util::builder::{
+		command::{CommandBuilder, StringBuilder},
+		embed::{EmbedBuilder, EmbedFieldBuilder},
+		InteractionResponseDataBuilder,
+	},
+	validate::embed::{EMBED_TOTAL_LENGTH, FIELD_COUNT},
+};
+
+pub const NAME: &str = "history";
+
+/// Leaves room for the title and description.
+const FIELDS_TOTAL_LENGTH: usize = EMBED_TOTAL_LENGTH - 500;
+const PREVIEW_LENGTH: usize = 900;
+
+pub fn info(locales: &Locales) -> Command {
+	CommandBuilder::new(
+		NAME,
+		"View every version of a message sent in this ticket",
+		CommandType::ChatInput,
+	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
+	.default_member_permissions(Permissions::ADMINISTRATOR)
+	.option(
+		StringBuilder::new("message", "The ID of the thread message")
+			.name_localizations(locales.option_names(NAME, "message"))
+			.description_localizations(locales.option_descriptions(NAME, "message"))
+			.required(true)
+			.min_length(1)
+			.max_length(20)
+			.build(),
+	)
+	.build()
+}
+
+pub async fn run(
+	bot: &Arc<Bot>,
+	interaction: Interaction,
+	cmd_data: CommandData,
+) -> anyhow::Result<()> {
+	let Some(thread_id) = super::only_in_modmail_thread(bot, &interaction).await?
+	else {
+		return Ok(());
+	};
+
+	// parse argument
+	let Some(thread_msg_id) = (match cmd_data.options.first().map(|opt| &opt.value) {
+		Some(CommandOptionValue::String(s)) => MessageId::from_str(s).ok(),
+		_ => None,
+	}) else {
+		bot.interact().create_response(
+			interaction.id,
+			&interaction.token,
+			&InteractionResponseDataBuilder::new()
+				.content("Please specify a valid message ID.")
+				.flags(MessageFlags::EPHEMERAL)
+				.into_response()
+		)
+		.await?;
+		return Ok(());
+	};
+
+	bot.interact()
+		.create_response(interaction.id, &interaction.token, &DEFER)
+		.await?;
+
+	let guild_id = interaction.guild_id.context("missing guild")?;
+	let ticket = bot
+		.db
+		.ticket_by_thread(thread_id)
+		.await?
+		.context("missing ticket")?;
+
+	let msg = match bot
+		.db
+		.message_by_thread_msg(ticket.user_id, thread_msg_id)
+		.await?
+	{
+		Some(msg) if msg.content.is_some() => msg,
+		Some(_) => {
+			bot.interact()
+				.update_response(&interaction.token)
+				.content(Some(
+					"That message was sent before I started saving message content, so I don't have its history.",
+				))?
+				.await?;
+			return Ok(());
+		},
+		None => {
+			bot.interact()
+				.update_response(&interaction.token)
+				.content(Some(
+					"I couldn't find that message in the database. Did you copy the correct ID?",
+				))?
+				.await?;
+			return Ok(());
+		},
+	};
+
+	let revisions = bot
+		.db
+		.message_revisions(ticket.user_id, msg.dm_msg_id)
+		.await?;
+
+	// (name, thread message id, timestamp, content), oldest first
+	let mut versions = Vec::with_capacity(revisions.len() + 1);
+	for (i, revision) in revisions.iter().enumerate() {
+		let name = if i == 0 {
+			"Original".to_owned()
+		} else {
+			format!("Edit {i}")
+		};
+		versions.push((
+			name,
+			revision.thread_msg_id,
+			Some(revision.created_at),
+			revision.content.as_str(),
+		));
+	}
+	let mut current_name = match revisions.len() {
+		0 => "Original".to_owned(),
+		n => format!("Edit {n}"),
+	};
+	if msg.deleted_at.is_some() {
+		current_name.push_str(" (deleted)");
+	}
+	versions.push((
+		current_name,
+		msg.latest_thread_msg_id(),
+		msg.edited_at.or(msg.created_at),
+		msg.content.as_deref().unwrap_or_default(),
+	));
+
+	// show the newest versions that fit in the embed
+	let mut fields = Vec::new();
+	let mut total_length = 0;
+	for (name, version_thread_msg_id, timestamp, content) in versions.iter().rev() {
+		let mut value = String::new();
+		if let Some(timestamp) = timestamp {
+			write!(value, "<t:{timestamp}:f> · ")?;
+		}
+		writeln!(
+			value,
+			"[Jump](https://discord.com/channels/{}/{}/{})",
+			guild_id, thread_id, version_thread_msg_id,
+		)?;
+		if content.is_empty() {
+			value.push_str("*No text*");
+		} else {
+			value.push_str(&formatting::truncate(content, PREVIEW_LENGTH));
+		}
+
+		total_length += name.chars().count() + value.chars().count();
+		if fields.len() == FIELD_COUNT || total_length > FIELDS_TOTAL_LENGTH {
+			break;
+		}
+		fields.push(EmbedFieldBuilder::new(name, value).build());
+	}
+	fields.reverse();
+
+	let mut description = format!(
+		"{} version(s) of https://discord.com/channels/{}/{}/{}",
+		versions.len(),
+		guild_id,
+		thread_id,
+		msg.thread_msg_id,
+	);
+	if fields.len() < versions.len() {
+		write!(
+			description,
+			"\nThe {} oldest version(s) are too long to show here.",
+			versions.len() - fields.len(),
+		)?;
+	}
+
+	let mut embed = EmbedBuilder::new()
+		.color(BLURPLE)
+		.title("📜 Message history")
+		.description(description);
+	for field in fields {
+		embed = embed.field(field);
+	}
+
+	bot.interact()
+		.update_response(&interaction.token)
+		.embeds(Some(&[embed.build()]))?
+		.await?;
+
+	Ok(())
+}
@@ -1,5 +1,6 @@
 use crate::{
-	bot::Bot,
+	bot::{profile_buttons, Bot, ProfilePage},
+	config::Locales,
 	util::{formatting, DEFER},
 };
 use anyhow::Context;
@@ -17,12 +18,14 @@ use twilight::{
 
 pub const NAME: &str = "info";
 
-pub fn info() -> Command {
+pub fn info(locales: &Locales) -> Command {
 	CommandBuilder::new(
 		NAME,
 		"Get information about the user in this modmail thread",
 		CommandType::ChatInput,
 	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
 	.default_member_permissions(Permissions::ADMINISTRATOR)
 	.build()
 }
@@ -43,12 +46,18 @@ pub async fn run(bot: &Arc<Bot>, interaction: Interaction, _: CommandData) -> an
 		.await?
 		.context("missing ticket")?;
 
-	let info_embed = formatting::user_info_embed(bot, ticket.user_id).await?;
+	let guild_id = bot.config.ticket_server(ticket.guild_id).guild_id;
+	let info_embed = formatting::user_info_embed(bot, ticket.user_id, guild_id).await?;
 
 	bot.interact()
 		.update_response(&interaction.token)
 		.content(Some(&format!("<@{}>", ticket.user_id)))?
 		.embeds(Some(&[info_embed]))?
+		.components(Some(&[profile_buttons(
+			ticket.user_id,
+			guild_id,
+			ProfilePage::Overview,
+		)]))?
 		.await?;
 
 	Ok(())
@@ -1,9 +1,9 @@
 use crate::{
 	bot::Bot,
-	util::{InteractionResponseDataExt, SqliteId, DEFER},
+	config::Locales,
+	util::{InteractionResponseDataExt, DEFER},
 };
 use anyhow::Context;
-use sqlx::Row;
 use std::{str::FromStr, sync::Arc};
 use twilight::{
 	id::MessageId,
@@ -26,15 +26,19 @@ use twilight::{
 
 pub const NAME: &str = "link";
 
-pub fn info() -> Command {
+pub fn info(locales: &Locales) -> Command {
 	CommandBuilder::new(
 		NAME,
 		"Get a message link from this modmail thread (useful for reporting)",
 		CommandType::ChatInput,
 	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
 	.default_member_permissions(Permissions::ADMINISTRATOR)
 	.option(
 		StringBuilder::new("id", "The thread message ID to get a link for")
+			.name_localizations(locales.option_names(NAME, "id"))
+			.description_localizations(locales.option_descriptions(NAME, "id"))
 			.min_length(1)
 			.max_length(20)
 			.required(true)
@@ -54,7 +58,7 @@ pub async fn run(
 	};
 
 	// parse argument
-	let Some(thread_msg_id) = (match cmd_data.options.get(0).map(|opt| &opt.value) {
+	let Some(thread_msg_id) = (match cmd_data.options.first().map(|opt| &opt.value) {
 		Some(CommandOptionValue::String(s)) => MessageId::from_str(s).ok(),
 		_ => None,
 	}) else {
@@ -80,19 +84,11 @@ pub async fn run(
 		.await?
 		.context("missing ticket")?;
 
-	let dm_msg_id = sqlx::query(indoc! {"
-		SELECT dm_msg_id FROM messages
-		WHERE user_id = ? AND thread_msg_id = ?
-		ORDER BY rowid DESC
-	"})
-	.bind(SqliteId(ticket.user_id))
-	.bind(SqliteId(thread_msg_id))
-	.try_map(|row| {
-		let id: SqliteId<MessageId> = row.try_get(0)?;
-		Ok(*id)
-	})
-	.fetch_optional(&bot.db.connection)
-	.await?;
+	let dm_msg_id = bot
+		.db
+		.message_by_thread_msg(ticket.user_id, thread_msg_id)
+		.await?
+		.map(|msg| msg.dm_msg_id);
 
 	let Some(dm_msg_id) = dm_msg_id
 	else {
@@ -1,8 +1,10 @@
-use crate::{bot::Bot, util::InteractionResponseDataExt};
+use crate::{
+	bot::Bot, config::Locales, database::RelayedMessage, util::InteractionResponseDataExt,
+};
 use anyhow::Context;
 use std::sync::Arc;
 use twilight::{
-	id::ChannelId,
+	id::{ChannelId, MessageId, UserId},
 	model::{
 		application::{
 			command::Command,
@@ -13,14 +15,15 @@ use twilight::{
 	util::builder::InteractionResponseDataBuilder,
 };
 
-const VALID_SENDING_COMMANDS: &[&str] = &["reply", "tag"];
-
 async fn only_in_modmail_thread(
 	bot: &Arc<Bot>,
 	interaction: &Interaction,
 ) -> anyhow::Result<Option<ChannelId>> {
 	let thread = interaction.channel.as_ref().context("missing channel")?;
-	if thread.parent_id != Some(bot.config.forum_channel_id) {
+	let in_forum = thread
+		.parent_id
+		.is_some_and(|parent_id| bot.config.server_by_forum(parent_id).is_some());
+	if !in_forum {
 		bot.interact()
 			.create_response(
 				interaction.id,
@@ -38,10 +41,39 @@ async fn only_in_modmail_thread(
 	}
 }
 
+/// Finds a message that staff sent in a ticket, for commands that modify it.
+///
+/// This must be used after deferring. If the message can't be used, this responds with the reason
+/// and returns [`None`].
+async fn staff_msg_in_thread(
+	bot: &Arc<Bot>,
+	interaction: &Interaction,
+	user_id: UserId,
+	thread_msg_id: MessageId,
+	verb: &str,
+) -> anyhow::Result<Option<RelayedMessage>> {
+	let error_text = match bot.db.message_by_thread_msg(user_id, thread_msg_id).await? {
+		None => format!("I couldn't find a message to {verb}. Did you copy the correct ID?"),
+		Some(msg) if msg.author_id.is_none() || msg.content.is_none() => format!(
+			"That message was sent before I started saving message content, so I can't {verb} it."
+		),
+		Some(msg) if !msg.is_from_staff() => format!("I can only {verb} messages I sent."),
+		Some(msg) if msg.deleted_at.is_some() => "That message was already deleted.".to_owned(),
+		Some(msg) => return Ok(Some(msg)),
+	};
+
+	bot.interact()
+		.update_response(&interaction.token)
+		.content(Some(&error_text))?
+		.await?;
+
+	Ok(None)
+}
+
 macro_rules! commands {
 	($($mod_name:ident),*$(,)?) => {
-		pub fn info() -> Vec<Command> {
-			vec![$($mod_name::info()),*]
+		pub fn info(locales: &Locales) -> Vec<Command> {
+			vec![$($mod_name::info(locales)),*]
 		}
 
 		pub async fn handle_command(
@@ -57,25 +89,51 @@ macro_rules! commands {
 	};
 }
 
+mod moderation;
+
 // modules need to be outside of macros for rustfmt to find them
 mod about;
+mod ban;
+mod block;
 mod close;
 mod delete;
 mod edit;
+mod filter_hits;
+mod history;
 mod info;
+mod kick;
 mod link;
 mod modmail;
+mod note;
 mod ping;
+mod reload_config;
+mod reopen;
 mod reply;
+mod satisfaction;
+mod timeout;
+mod unblock;
+mod warn;
 
 commands! {
 	about,
+	ban,
+	block,
 	close,
 	delete,
 	edit,
+	filter_hits,
+	history,
 	info,
+	kick,
 	link,
 	modmail,
+	note,
 	ping,
+	reload_config,
+	reopen,
 	reply,
+	satisfaction,
+	timeout,
+	unblock,
+	warn,
 }
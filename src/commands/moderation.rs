@@ -0,0 +1,273 @@
+use crate::{
+	bot::Bot,
+	config::{Locales, ModAction, Vars},
+	database::{Moderation, ModerationKind, RelayedMessage},
+	util::{DEFER, GREEN, RED, YELLOW},
+};
+use anyhow::Context;
+use std::{
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+use twilight::{
+	http::request::AuditLogReason,
+	model::{
+		application::interaction::{
+			application_command::{CommandData, CommandOptionValue},
+			Interaction,
+		},
+		util::Timestamp,
+	},
+	util::builder::{
+		command::{BooleanBuilder, CommandBuilder, StringBuilder},
+		embed::{EmbedBuilder, EmbedFieldBuilder},
+	},
+};
+
+/// Discord doesn't accept longer audit log reasons.
+const REASON_LENGTH_MAX: u16 = 512;
+
+/// Adds the options that every moderation command has, after any of its own.
+pub fn options(command: CommandBuilder, name: &str, locales: &Locales) -> CommandBuilder {
+	command
+		.option(
+			StringBuilder::new("reason", "Why you're doing this")
+				.name_localizations(locales.option_names(name, "reason"))
+				.description_localizations(locales.option_descriptions(name, "reason"))
+				.required(true)
+				.min_length(1)
+				.max_length(REASON_LENGTH_MAX)
+				.build(),
+		)
+		.option(
+			BooleanBuilder::new("notify", "Whether to send the user the reason")
+				.name_localizations(locales.option_names(name, "notify"))
+				.description_localizations(locales.option_descriptions(name, "notify"))
+				.build(),
+		)
+}
+
+pub async fn run(
+	bot: &Arc<Bot>,
+	interaction: Interaction,
+	cmd_data: CommandData,
+	kind: ModerationKind,
+) -> anyhow::Result<()> {
+	let Some(thread_id) = super::only_in_modmail_thread(bot, &interaction).await? else {
+		return Ok(());
+	};
+
+	// parse arguments
+	let mut reason = None;
+	let mut notify = false;
+	let mut minutes = None;
+	for opt in cmd_data.options {
+		match (&opt.name as &str, opt.value) {
+			("reason", CommandOptionValue::String(s)) => reason = Some(s),
+			("notify", CommandOptionValue::Boolean(value)) => notify = value,
+			("minutes", CommandOptionValue::Integer(value)) => minutes = Some(value),
+			_ => (),
+		}
+	}
+	let reason = reason.context("missing reason arg")?;
+
+	bot.interact()
+		.create_response(interaction.id, &interaction.token, &DEFER)
+		.await?;
+
+	let ticket = bot
+		.db
+		.ticket_by_thread(thread_id)
+		.await?
+		.context("missing ticket")?;
+	let server = bot.config.ticket_server(ticket.guild_id);
+	let staff = interaction.author().context("missing author")?;
+	let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+	let expires_at = minutes.map(|minutes| now + minutes * 60);
+
+	// tell the user first, since the bot can't dm them once they've left every server it's in
+	let mut notice = None;
+	if notify {
+		let live_config = bot.config.live();
+		let locale = bot.user_locale(ticket.user_id, server.guild_id).await?;
+		let messages = live_config.messages(locale.as_deref());
+		let template = match kind {
+			ModerationKind::Warn => &messages.warn_notice,
+			ModerationKind::Timeout => &messages.timeout_notice,
+			ModerationKind::Kick => &messages.kick_notice,
+			ModerationKind::Ban => &messages.ban_notice,
+		};
+		let notice_msg = template.render(&Vars {
+			staff_id: Some(staff.id),
+			staff_name: Some(staff.name.clone()),
+			reason: Some(reason.clone()),
+			expires: expires_at,
+			..bot.ticket_vars(&ticket)
+		});
+		if let Ok(response) = bot
+			.http
+			.create_message(ticket.dm_channel_id)
+			.content(&notice_msg.content)?
+			.embeds(&notice_msg.embeds)?
+			.await
+		{
+			notice = Some((response.model().await?, notice_msg));
+		}
+	}
+
+	// the audit log entry for this would be relayed, but it's already logged below
+	let action = match kind {
+		ModerationKind::Warn => None,
+		ModerationKind::Timeout => Some(ModAction::Timeout),
+		ModerationKind::Kick => Some(ModAction::Kick),
+		ModerationKind::Ban => Some(ModAction::Ban),
+	};
+	if let Some(action) = action {
+		bot.logged_mod_actions.insert((ticket.user_id, action));
+	}
+
+	let result = match kind {
+		ModerationKind::Warn => Ok(()),
+		ModerationKind::Timeout => {
+			let until = Timestamp::from_secs(expires_at.context("missing minutes arg")?)?;
+			bot.http
+				.update_guild_member(server.guild_id, ticket.user_id)
+				.communication_disabled_until(Some(until))?
+				.reason(&reason)?
+				.await
+				.map(drop)
+		},
+		ModerationKind::Kick => bot
+			.http
+			.remove_guild_member(server.guild_id, ticket.user_id)
+			.reason(&reason)?
+			.await
+			.map(drop),
+		ModerationKind::Ban => bot
+			.http
+			.create_ban(server.guild_id, ticket.user_id)
+			.reason(&reason)?
+			.await
+			.map(drop),
+	};
+
+	// respond to the interaction
+	let succeeded = match result {
+		Ok(()) => {
+			let notified = match (notify, &notice) {
+				(false, _) => "No",
+				(true, Some(_)) => "Yes",
+				(true, None) => "No, I couldn't DM them",
+			};
+			let mut embed = EmbedBuilder::new()
+				.color(match kind {
+					ModerationKind::Ban => RED,
+					_ => YELLOW,
+				})
+				.description(format!(
+					"{} User was {} by <@{}>.",
+					kind.emoji(),
+					kind.past_tense(),
+					staff.id,
+				))
+				.field(EmbedFieldBuilder::new("Reason", &reason).build());
+			if let Some(expires_at) = expires_at {
+				embed = embed.field(
+					EmbedFieldBuilder::new("Expires", format!("<t:{expires_at}:R>"))
+						.inline()
+						.build(),
+				);
+			}
+			embed = embed.field(
+				EmbedFieldBuilder::new("Notified", notified)
+					.inline()
+					.build(),
+			);
+
+			bot.interact()
+				.update_response(&interaction.token)
+				.embeds(Some(&[embed.build()]))?
+				.await?;
+			true
+		},
+		Err(err) => {
+			debug!(?err, "moderation action failed");
+			if let Some(action) = action {
+				bot.logged_mod_actions.remove(&(ticket.user_id, action));
+			}
+
+			let mut error_text = format!(
+				"⚠️ Unable to {} the user. I might be missing permissions, or their highest role \
+				 might be above mine.",
+				match kind {
+					ModerationKind::Warn => "warn",
+					ModerationKind::Timeout => "time out",
+					ModerationKind::Kick => "kick",
+					ModerationKind::Ban => "ban",
+				},
+			);
+			if notice.is_some() {
+				error_text.push_str("\nThey were already sent the reason.");
+			}
+			bot.interact()
+				.update_response(&interaction.token)
+				.content(Some(&error_text))?
+				.await?;
+			false
+		},
+	};
+
+	// the notice is part of the conversation, so show it like a reply
+	if let Some((dm_msg, notice_msg)) = notice {
+		let mut embeds = Vec::new();
+		if !notice_msg.content.is_empty() {
+			embeds.push(
+				EmbedBuilder::new()
+					.color(GREEN)
+					.description(&notice_msg.content)
+					.build(),
+			);
+		}
+		embeds.extend(notice_msg.embeds);
+		let thread_msg = bot
+			.http
+			.create_message(thread_id)
+			.embeds(&embeds)?
+			.await?
+			.model()
+			.await?;
+
+		let relayed_msg = RelayedMessage {
+			user_id: ticket.user_id,
+			dm_msg_id: dm_msg.id,
+			thread_msg_id: thread_msg.id,
+			thread_update_msg_id: None,
+			author_id: Some(staff.id),
+			content: Some(notice_msg.content),
+			created_at: Some(thread_msg.timestamp.as_secs()),
+			edited_at: None,
+			deleted_at: None,
+		};
+		bot.db.insert_message(&relayed_msg).await?;
+		bot.queue_message_webhooks(&ticket, &relayed_msg).await?;
+		bot.db
+			.reset_inactivity_timer(ticket.user_id, thread_msg.timestamp.as_secs(), true)
+			.await?;
+	}
+
+	if succeeded {
+		bot.db
+			.insert_moderation(&Moderation {
+				user_id: ticket.user_id,
+				guild_id: server.guild_id,
+				staff_id: staff.id,
+				kind,
+				reason: Some(reason),
+				expires_at,
+				created_at: now,
+			})
+			.await?;
+	}
+
+	Ok(())
+}
@@ -1,4 +1,8 @@
-use crate::{bot::Bot, util::DEFER_EPHEMERAL};
+use crate::{
+	bot::Bot,
+	config::{Locales, Vars},
+	util::DEFER_EPHEMERAL,
+};
 use anyhow::Context;
 use std::sync::Arc;
 use twilight::{
@@ -11,8 +15,11 @@ use twilight::{
 
 pub const NAME: &str = "modmail";
 
-pub fn info() -> Command {
-	CommandBuilder::new(NAME, "Open a modmail ticket", CommandType::ChatInput).build()
+pub fn info(locales: &Locales) -> Command {
+	CommandBuilder::new(NAME, "Open a modmail ticket", CommandType::ChatInput)
+		.name_localizations(locales.command_names(NAME))
+		.description_localizations(locales.command_descriptions(NAME))
+		.build()
 }
 
 pub async fn run(bot: &Arc<Bot>, interaction: Interaction, _: CommandData) -> anyhow::Result<()> {
@@ -22,13 +29,20 @@ pub async fn run(bot: &Arc<Bot>, interaction: Interaction, _: CommandData) -> an
 		.create_response(interaction.id, &interaction.token, &DEFER_EPHEMERAL)
 		.await?;
 
+	// the user can see these, so use their locale
+	let live_config = bot.config.live();
+	let messages = live_config.messages(interaction.locale.as_deref());
+	let guild_id = interaction
+		.guild_id
+		.unwrap_or_else(|| bot.config.default_server().guild_id);
+	let vars = bot.user_vars(user_id, guild_id);
+
 	let send_error_msg = || async {
+		let error_msg = messages.modmail_dm_failed.render(&vars);
 		bot.interact()
 			.update_response(&interaction.token)
-			.content(Some(indoc! {"
-				⚠️ **I couldn't send you a message.**
-				Make sure that you're accepting DMs from this server and that you haven't blocked me.
-			"}))?
+			.content(Some(&error_msg.content))?
+			.embeds(Some(&error_msg.embeds))?
 			.await?;
 		Ok::<_, anyhow::Error>(())
 	};
@@ -41,10 +55,12 @@ pub async fn run(bot: &Arc<Bot>, interaction: Interaction, _: CommandData) -> an
 		},
 	};
 
+	let prompt_msg = messages.modmail_prompt.render(&vars);
 	let dm_msg = match bot
 		.http
 		.create_message(dm_channel.id)
-		.content("Send a message here to open a ticket.")?
+		.content(&prompt_msg.content)?
+		.embeds(&prompt_msg.embeds)?
 		.await
 	{
 		Ok(response) => response.model().await?,
@@ -54,12 +70,17 @@ pub async fn run(bot: &Arc<Bot>, interaction: Interaction, _: CommandData) -> an
 		},
 	};
 
+	let link_msg = messages.modmail_link.render(&Vars {
+		link: Some(format!(
+			"https://discord.com/channels/@me/{}/{}",
+			dm_channel.id, dm_msg.id,
+		)),
+		..vars
+	});
 	bot.interact()
 		.update_response(&interaction.token)
-		.content(Some(&format!(
-			"Click here: https://discord.com/channels/@me/{}/{}",
-			dm_channel.id, dm_msg.id,
-		)))?
+		.content(Some(&link_msg.content))?
+		.embeds(Some(&link_msg.embeds))?
 		.await?;
 
 	Ok(())
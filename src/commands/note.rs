@@ -0,0 +1,98 @@
+use crate::{
+	bot::Bot,
+	config::Locales,
+	database::StaffNote,
+	util::{BLURPLE, DEFER},
+};
+use anyhow::Context;
+use std::{
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+use twilight::{
+	model::{
+		application::{
+			command::{Command, CommandType},
+			interaction::{
+				application_command::{CommandData, CommandOptionValue},
+				Interaction,
+			},
+		},
+		guild::Permissions,
+	},
+	util::builder::{
+		command::{CommandBuilder, StringBuilder},
+		embed::EmbedBuilder,
+	},
+	validate::embed::FIELD_VALUE_LENGTH,
+};
+
+pub const NAME: &str = "note";
+
+pub fn info(locales: &Locales) -> Command {
+	CommandBuilder::new(
+		NAME,
+		"Write down something about this ticket's user, which they won't see",
+		CommandType::ChatInput,
+	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
+	.default_member_permissions(Permissions::ADMINISTRATOR)
+	.option(
+		StringBuilder::new("text", "The note")
+			.name_localizations(locales.option_names(NAME, "text"))
+			.description_localizations(locales.option_descriptions(NAME, "text"))
+			.required(true)
+			.min_length(1)
+			.max_length(FIELD_VALUE_LENGTH as u16)
+			.build(),
+	)
+	.build()
+}
+
+pub async fn run(
+	bot: &Arc<Bot>,
+	interaction: Interaction,
+	cmd_data: CommandData,
+) -> anyhow::Result<()> {
+	let Some(thread_id) = super::only_in_modmail_thread(bot, &interaction).await? else {
+		return Ok(());
+	};
+
+	// get argument
+	let text = match cmd_data.options.into_iter().next().map(|opt| opt.value) {
+		Some(CommandOptionValue::String(text)) => text,
+		_ => bail!("missing argument"),
+	};
+
+	bot.interact()
+		.create_response(interaction.id, &interaction.token, &DEFER)
+		.await?;
+
+	let ticket = bot
+		.db
+		.ticket_by_thread(thread_id)
+		.await?
+		.context("missing ticket")?;
+
+	let staff_id = interaction.author_id().context("missing author")?;
+	let embed = EmbedBuilder::new()
+		.color(BLURPLE)
+		.description(format!("📝 Note by <@{staff_id}>:\n{text}"))
+		.build();
+	bot.db
+		.insert_staff_note(&StaffNote {
+			user_id: ticket.user_id,
+			staff_id,
+			content: text,
+			created_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+		})
+		.await?;
+
+	bot.interact()
+		.update_response(&interaction.token)
+		.embeds(Some(&[embed]))?
+		.await?;
+
+	Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::{bot::Bot, util::DEFER_EPHEMERAL};
+use crate::{bot::Bot, config::Locales, util::DEFER_EPHEMERAL};
 use std::{
 	sync::Arc,
 	time::{Duration, Instant},
@@ -13,8 +13,11 @@ use twilight::{
 
 pub const NAME: &str = "ping";
 
-pub fn info() -> Command {
-	CommandBuilder::new(NAME, "Check if the bot is alive", CommandType::ChatInput).build()
+pub fn info(locales: &Locales) -> Command {
+	CommandBuilder::new(NAME, "Check if the bot is alive", CommandType::ChatInput)
+		.name_localizations(locales.command_names(NAME))
+		.description_localizations(locales.command_descriptions(NAME))
+		.build()
 }
 
 pub async fn run(bot: &Arc<Bot>, interaction: Interaction, _: CommandData) -> anyhow::Result<()> {
@@ -29,9 +32,17 @@ pub async fn run(bot: &Arc<Bot>, interaction: Interaction, _: CommandData) -> an
 	// only show millisecond precision
 	time = Duration::from_millis(time.as_millis().try_into()?);
 
+	let cache_stats = bot.db.cache_stats();
+
 	client
 		.update_response(&interaction.token)
-		.content(Some(&format!("Pong! `{time:?}`")))?
+		.content(Some(&formatdoc! {"
+			Pong! `{time:?}`
+			Ticket cache: {} hits, {} misses ({:.1}% hit rate)",
+			cache_stats.hits,
+			cache_stats.misses,
+			cache_stats.hit_rate() * 100.0,
+		}))?
 		.await?;
 
 	Ok(())
@@ -0,0 +1,57 @@
+use crate::{
+	bot::Bot,
+	config::Locales,
+	util::{formatting, DEFER_EPHEMERAL},
+};
+use std::sync::Arc;
+use twilight::{
+	model::{
+		application::{
+			command::{Command, CommandType},
+			interaction::{application_command::CommandData, Interaction},
+		},
+		guild::Permissions,
+	},
+	util::builder::command::CommandBuilder,
+};
+
+pub const NAME: &str = "reload-config";
+
+pub fn info(locales: &Locales) -> Command {
+	CommandBuilder::new(
+		NAME,
+		"Reload the config file without restarting",
+		CommandType::ChatInput,
+	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
+	.default_member_permissions(Permissions::ADMINISTRATOR)
+	.build()
+}
+
+pub async fn run(bot: &Arc<Bot>, interaction: Interaction, _: CommandData) -> anyhow::Result<()> {
+	bot.interact()
+		.create_response(interaction.id, &interaction.token, &DEFER_EPHEMERAL)
+		.await?;
+
+	let text = match bot.reload_config() {
+		Ok(needs_restart) if needs_restart.is_empty() => "✅ Config reloaded.".to_owned(),
+		Ok(needs_restart) => formatdoc! {"
+			✅ Config reloaded.
+			⚠️ These changes need a restart to apply: `{}`",
+			needs_restart.join("`, `"),
+		},
+		Err(err) => {
+			error!(?err, "unable to reload config");
+			let err = formatting::truncate(&format!("{err:#}"), 1_800);
+			format!("⚠️ The config wasn't reloaded because it's invalid:\n```\n{err}\n```")
+		},
+	};
+
+	bot.interact()
+		.update_response(&interaction.token)
+		.content(Some(&text))?
+		.await?;
+
+	Ok(())
+}
@@ -0,0 +1,85 @@
+use crate::{bot::Bot, config::Locales, util::DEFER};
+use anyhow::Context;
+use std::sync::Arc;
+use twilight::{
+	model::{
+		application::{
+			command::{Command, CommandType},
+			interaction::{
+				application_command::{CommandData, CommandOptionValue},
+				Interaction,
+			},
+		},
+		guild::Permissions,
+	},
+	util::builder::command::{BooleanBuilder, CommandBuilder},
+};
+
+pub const NAME: &str = "reopen";
+
+pub fn info(locales: &Locales) -> Command {
+	CommandBuilder::new(NAME, "Reopen this modmail ticket", CommandType::ChatInput)
+		.name_localizations(locales.command_names(NAME))
+		.description_localizations(locales.command_descriptions(NAME))
+		.default_member_permissions(Permissions::ADMINISTRATOR)
+		.option(
+			BooleanBuilder::new(
+				"silent",
+				"Whether to reopen the ticket without sending the user a message",
+			)
+			.name_localizations(locales.option_names(NAME, "silent"))
+			.description_localizations(locales.option_descriptions(NAME, "silent"))
+			.build(),
+		)
+		.build()
+}
+
+pub async fn run(
+	bot: &Arc<Bot>,
+	interaction: Interaction,
+	cmd_data: CommandData,
+) -> anyhow::Result<()> {
+	let Some(thread_id) = super::only_in_modmail_thread(bot, &interaction).await? else {
+		return Ok(());
+	};
+
+	// get argument
+	let silent = match cmd_data.options.into_iter().next().map(|opt| opt.value) {
+		Some(CommandOptionValue::Boolean(value)) => value,
+		_ => false,
+	};
+	let staff_id = interaction.author_id().context("missing author")?;
+
+	bot.interact()
+		.create_response(interaction.id, &interaction.token, &DEFER)
+		.await?;
+
+	let mut ticket = bot
+		.db
+		.ticket_by_thread(thread_id)
+		.await?
+		.context("missing ticket")?;
+
+	let response = if ticket.is_open {
+		"This ticket is already open."
+	} else if ticket.blocked {
+		"This user is blocked. Unblock them to reopen their ticket."
+	} else if silent {
+		"Reopened silently."
+	} else {
+		"Reopened."
+	};
+	bot.interact()
+		.update_response(&interaction.token)
+		.content(Some(response))?
+		.await?;
+	if ticket.is_open || ticket.blocked {
+		return Ok(());
+	}
+
+	bot.open_ticket(&mut ticket, !silent, Some(staff_id))
+		.await?;
+	bot.relay_held_dms(&ticket).await?;
+
+	Ok(())
+}
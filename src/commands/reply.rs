@@ -1,6 +1,8 @@
 use crate::{
 	bot::Bot,
-	util::{SqliteId, DEFER, GREEN},
+	config::{Locales, Vars},
+	database::RelayedMessage,
+	util::{DEFER, GREEN},
 };
 use anyhow::Context;
 use std::sync::Arc;
@@ -24,11 +26,15 @@ use twilight::{
 
 pub const NAME: &str = "reply";
 
-pub fn info() -> Command {
+pub fn info(locales: &Locales) -> Command {
 	CommandBuilder::new(NAME, "Reply to a modmail ticket", CommandType::ChatInput)
+		.name_localizations(locales.command_names(NAME))
+		.description_localizations(locales.command_descriptions(NAME))
 		.default_member_permissions(Permissions::ADMINISTRATOR)
 		.option(
 			StringBuilder::new("with", "The text to reply with")
+				.name_localizations(locales.option_names(NAME, "with"))
+				.description_localizations(locales.option_descriptions(NAME, "with"))
 				.required(true)
 				.min_length(1)
 				.max_length(MESSAGE_CONTENT_LENGTH_MAX as u16)
@@ -50,7 +56,7 @@ pub async fn run(
 	// get argument
 	let text = cmd_data
 		.options
-		.get(0)
+		.first()
 		.and_then(|option| {
 			if let CommandOptionValue::String(text) = &option.value {
 				Some(text)
@@ -70,20 +76,40 @@ pub async fn run(
 		.await?
 		.context("missing ticket")?;
 
+	let author = interaction.author().context("missing author")?;
+	let live_config = bot.config.live();
+	let vars = Vars {
+		staff_id: Some(author.id),
+		staff_name: Some(author.name.clone()),
+		message: Some(text.clone()),
+		..bot.ticket_vars(&ticket)
+	};
+
 	// send the dm
+	let locale = bot
+		.user_locale(
+			ticket.user_id,
+			bot.config.ticket_server(ticket.guild_id).guild_id,
+		)
+		.await?;
+	let dm_msg = live_config.messages(locale.as_deref()).reply.render(&vars);
 	let dm_msg_id = match bot
 		.http
 		.create_message(ticket.dm_channel_id)
-		.content(text)?
+		.content(&dm_msg.content)?
+		.embeds(&dm_msg.embeds)?
 		.await
 	{
 		Ok(response) => response.model().await?.id,
 		Err(_) => {
+			let error_msg = live_config
+				.messages(interaction.locale.as_deref())
+				.reply_dm_failed
+				.render(&vars);
 			bot.interact()
 				.update_response(&interaction.token)
-				.content(Some(
-					"⚠️ Unable to send DM. The user may have DMs closed or they blocked me.",
-				))?
+				.content(Some(&error_msg.content))?
+				.embeds(Some(&error_msg.embeds))?
 				.await?;
 			return Ok(());
 		},
@@ -100,25 +126,31 @@ pub async fn run(
 		error!(?err);
 	}
 
-	// save message ids
+	// save message ids and content
 
-	let response_msg_id = bot
+	let response_msg = bot
 		.interact()
 		.response(&interaction.token)
 		.await?
 		.model()
-		.await?
-		.id;
+		.await?;
 
-	sqlx::query(indoc! {"
-		INSERT INTO messages (user_id, dm_msg_id, thread_msg_id)
-		VALUES (?, ?, ?)
-	"})
-	.bind(SqliteId(ticket.user_id))
-	.bind(SqliteId(dm_msg_id))
-	.bind(SqliteId(response_msg_id))
-	.execute(&bot.db.connection)
-	.await?;
+	let relayed_msg = RelayedMessage {
+		user_id: ticket.user_id,
+		dm_msg_id,
+		thread_msg_id: response_msg.id,
+		thread_update_msg_id: None,
+		author_id: Some(author.id),
+		content: Some(text.clone()),
+		created_at: Some(response_msg.timestamp.as_secs()),
+		edited_at: None,
+		deleted_at: None,
+	};
+	bot.db.insert_message(&relayed_msg).await?;
+	bot.queue_message_webhooks(&ticket, &relayed_msg).await?;
+	bot.db
+		.reset_inactivity_timer(ticket.user_id, response_msg.timestamp.as_secs(), true)
+		.await?;
 
 	Ok(())
 }
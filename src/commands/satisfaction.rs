@@ -0,0 +1,120 @@
+use crate::{
+	bot::Bot,
+	config::Locales,
+	util::{formatting, InteractionResponseDataExt, BLURPLE},
+};
+use std::{collections::HashMap, fmt::Write as _, hash::Hash, sync::Arc};
+use twilight::{
+	model::{
+		application::{
+			command::{Command, CommandType},
+			interaction::{application_command::CommandData, Interaction},
+		},
+		channel::message::MessageFlags,
+		guild::Permissions,
+	},
+	util::builder::{
+		command::CommandBuilder,
+		embed::{EmbedBuilder, EmbedFieldBuilder},
+		InteractionResponseDataBuilder,
+	},
+	validate::embed::FIELD_VALUE_LENGTH,
+};
+
+pub const NAME: &str = "satisfaction";
+
+pub fn info(locales: &Locales) -> Command {
+	CommandBuilder::new(
+		NAME,
+		"View how users rated their tickets, by server and staff member",
+		CommandType::ChatInput,
+	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
+	.default_member_permissions(Permissions::ADMINISTRATOR)
+	.build()
+}
+
+pub async fn run(bot: &Arc<Bot>, interaction: Interaction, _: CommandData) -> anyhow::Result<()> {
+	let responses = bot.db.survey_responses().await?;
+
+	let mut embed = EmbedBuilder::new().color(BLURPLE).title("Satisfaction");
+	if responses.is_empty() {
+		embed = embed.description("Nobody has rated a ticket yet.");
+	} else {
+		let by_server = averages(responses.iter().map(|r| (r.guild_id, r.rating)));
+		let mut server_text = String::new();
+		for (guild_id, average, count) in by_server {
+			let name = match bot.discord_cache.guild(guild_id) {
+				Some(guild) => formatting::escape_markdown(guild.name()),
+				None => guild_id.to_string(),
+			};
+			writeln!(
+				server_text,
+				"**{name}**: {average:.2} from {count} rating(s)"
+			)?;
+		}
+
+		let by_staff = averages(responses.iter().map(|r| (r.staff_id, r.rating)));
+		let mut staff_text = String::new();
+		for (staff_id, average, count) in by_staff {
+			match staff_id {
+				Some(staff_id) => write!(staff_text, "<@{staff_id}>")?,
+				None => staff_text.push_str("Closed automatically"),
+			}
+			writeln!(staff_text, ": {average:.2} from {count} rating(s)")?;
+		}
+
+		let total: i32 = responses.iter().map(|r| r.rating).sum();
+		embed = embed
+			.description(format!(
+				"**Overall:** {:.2} from {} rating(s)",
+				f64::from(total) / responses.len() as f64,
+				responses.len()
+			))
+			.field(
+				EmbedFieldBuilder::new(
+					"By server",
+					formatting::truncate(&server_text, FIELD_VALUE_LENGTH),
+				)
+				.build(),
+			)
+			.field(
+				EmbedFieldBuilder::new(
+					"By staff member",
+					formatting::truncate(&staff_text, FIELD_VALUE_LENGTH),
+				)
+				.build(),
+			);
+	}
+
+	bot.interact()
+		.create_response(
+			interaction.id,
+			&interaction.token,
+			&InteractionResponseDataBuilder::new()
+				.flags(MessageFlags::EPHEMERAL)
+				.embeds([embed.build()])
+				.into_response(),
+		)
+		.await?;
+
+	Ok(())
+}
+
+/// The average rating and number of ratings for each key, best first.
+fn averages<K: Eq + Hash>(ratings: impl Iterator<Item = (K, i32)>) -> Vec<(K, f64, usize)> {
+	let mut totals: HashMap<K, (i32, usize)> = HashMap::new();
+	for (key, rating) in ratings {
+		let (sum, count) = totals.entry(key).or_default();
+		*sum += rating;
+		*count += 1;
+	}
+
+	let mut averages: Vec<_> = totals
+		.into_iter()
+		.map(|(key, (sum, count))| (key, f64::from(sum) / count as f64, count))
+		.collect();
+	averages.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.2.cmp(&a.2)));
+	averages
+}
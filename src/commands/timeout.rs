@@ -0,0 +1,42 @@
+use crate::{bot::Bot, config::Locales, database::ModerationKind};
+use std::sync::Arc;
+use twilight::{
+	model::{
+		application::{
+			command::{Command, CommandType},
+			interaction::{application_command::CommandData, Interaction},
+		},
+		guild::Permissions,
+	},
+	util::builder::command::{CommandBuilder, IntegerBuilder},
+};
+
+pub const NAME: &str = "timeout";
+
+/// Discord doesn't allow timeouts longer than 28 days.
+const MINUTES_MAX: i64 = 28 * 24 * 60;
+
+pub fn info(locales: &Locales) -> Command {
+	let command = CommandBuilder::new(NAME, "Time out this ticket's user", CommandType::ChatInput)
+		.name_localizations(locales.command_names(NAME))
+		.description_localizations(locales.command_descriptions(NAME))
+		.default_member_permissions(Permissions::MODERATE_MEMBERS)
+		.option(
+			IntegerBuilder::new("minutes", "How long the timeout lasts")
+				.name_localizations(locales.option_names(NAME, "minutes"))
+				.description_localizations(locales.option_descriptions(NAME, "minutes"))
+				.required(true)
+				.min_value(1)
+				.max_value(MINUTES_MAX)
+				.build(),
+		);
+	super::moderation::options(command, NAME, locales).build()
+}
+
+pub async fn run(
+	bot: &Arc<Bot>,
+	interaction: Interaction,
+	cmd_data: CommandData,
+) -> anyhow::Result<()> {
+	super::moderation::run(bot, interaction, cmd_data, ModerationKind::Timeout).await
+}
@@ -0,0 +1,35 @@
+use crate::{bot::Bot, config::Locales};
+use std::sync::Arc;
+use twilight::{
+	model::{
+		application::{
+			command::{Command, CommandType},
+			interaction::{application_command::CommandData, Interaction},
+		},
+		guild::Permissions,
+	},
+	util::builder::command::CommandBuilder,
+};
+
+pub const NAME: &str = "unblock";
+
+pub fn info(locales: &Locales) -> Command {
+	CommandBuilder::new(
+		NAME,
+		"Stop ignoring this ticket's user",
+		CommandType::ChatInput,
+	)
+	.name_localizations(locales.command_names(NAME))
+	.description_localizations(locales.command_descriptions(NAME))
+	.default_member_permissions(Permissions::ADMINISTRATOR)
+	.option(super::block::reason_option(NAME, locales))
+	.build()
+}
+
+pub async fn run(
+	bot: &Arc<Bot>,
+	interaction: Interaction,
+	cmd_data: CommandData,
+) -> anyhow::Result<()> {
+	super::block::set_blocked(bot, interaction, cmd_data, false).await
+}
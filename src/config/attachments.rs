@@ -0,0 +1,304 @@
+use std::path::PathBuf;
+use twilight::model::channel::Attachment;
+
+/// Which attachments from users are relayed, and how.
+#[derive(serde::Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AttachmentsConfig {
+	/// File extensions that are relayed. Every extension is allowed if this isn't set.
+	#[serde(default)]
+	pub allowed_extensions: Option<Vec<String>>,
+	#[serde(default)]
+	pub denied_extensions: Vec<String>,
+	/// MIME types that are relayed, like `image/png` or `image/*`. Every type is allowed if this
+	/// isn't set.
+	#[serde(default)]
+	pub allowed_types: Option<Vec<String>>,
+	#[serde(default)]
+	pub denied_types: Vec<String>,
+	#[serde(default)]
+	pub max_size_mb: Option<u32>,
+	/// Whether images are hidden behind spoiler tags in the thread.
+	#[serde(default)]
+	pub spoiler_images: bool,
+	/// Checks files before they're relayed, and quarantines the ones it doesn't trust.
+	#[serde(default)]
+	pub scanner: Option<ScannerConfig>,
+}
+
+/// Which scanner to check attachments with.
+#[derive(serde::Deserialize, Clone)]
+#[serde(tag = "backend", rename_all = "lowercase", deny_unknown_fields)]
+pub enum ScannerConfig {
+	/// A ClamAV daemon, listening on a unix socket or a TCP address.
+	Clamd {
+		#[serde(default)]
+		socket: Option<PathBuf>,
+		#[serde(default)]
+		address: Option<String>,
+	},
+	/// A program that's given the file on stdin, and exits with 0 if it's clean or 1 if it isn't.
+	Command { command: Vec<String> },
+}
+
+/// What happens to an attachment before it's relayed.
+pub enum AttachmentDecision {
+	Allowed,
+	/// It's an image that should be spoilered.
+	Spoilered,
+	/// It breaks the policy, with the reason why.
+	Denied(String),
+}
+
+/// The attachment rules from the config, ready to check attachments with.
+#[derive(Default)]
+pub struct AttachmentPolicy {
+	allowed_extensions: Option<Vec<String>>,
+	denied_extensions: Vec<String>,
+	allowed_types: Option<Vec<String>>,
+	denied_types: Vec<String>,
+	max_size_mb: Option<u32>,
+	spoiler_images: bool,
+	pub scanner: Option<ScannerConfig>,
+}
+
+impl AttachmentPolicy {
+	pub fn new(config: AttachmentsConfig) -> anyhow::Result<Self> {
+		match &config.scanner {
+			Some(ScannerConfig::Clamd { socket, address })
+				if socket.is_some() == address.is_some() =>
+			{
+				bail!("attachments.scanner needs either a socket or an address for clamd");
+			},
+			Some(ScannerConfig::Clamd {
+				socket: Some(_), ..
+			}) if cfg!(not(unix)) => {
+				bail!("attachments.scanner.socket only works on unix, use an address instead");
+			},
+			Some(ScannerConfig::Command { command }) if command.is_empty() => {
+				bail!("attachments.scanner.command can't be empty");
+			},
+			_ => (),
+		}
+		if config.max_size_mb == Some(0) {
+			bail!("attachments.max_size_mb must be at least 1");
+		}
+
+		fn extensions(list: Vec<String>) -> Vec<String> {
+			list.iter()
+				.map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+				.collect()
+		}
+		fn types(list: Vec<String>) -> Vec<String> {
+			list.iter().map(|kind| kind.trim().to_lowercase()).collect()
+		}
+
+		Ok(Self {
+			allowed_extensions: config.allowed_extensions.map(extensions),
+			denied_extensions: extensions(config.denied_extensions),
+			allowed_types: config.allowed_types.map(types),
+			denied_types: types(config.denied_types),
+			max_size_mb: config.max_size_mb,
+			spoiler_images: config.spoiler_images,
+			scanner: config.scanner,
+		})
+	}
+
+	/// Decides what to do with an attachment, going by what discord says about it.
+	pub fn check(&self, attachment: &Attachment) -> AttachmentDecision {
+		let extension = attachment
+			.filename
+			.rsplit_once('.')
+			.map(|(_, ext)| ext.to_lowercase())
+			.unwrap_or_default();
+		// like `text/plain; charset=utf-8`
+		let kind = attachment
+			.content_type
+			.as_deref()
+			.and_then(|kind| kind.split(';').next())
+			.map(|kind| kind.trim().to_lowercase());
+
+		if self.denied_extensions.contains(&extension)
+			|| self
+				.allowed_extensions
+				.as_ref()
+				.is_some_and(|allowed| !allowed.contains(&extension))
+		{
+			return AttachmentDecision::Denied(if extension.is_empty() {
+				"files without an extension aren't allowed".to_owned()
+			} else {
+				format!(".{extension} files aren't allowed")
+			});
+		}
+
+		let type_matches = |patterns: &[String]| {
+			kind.as_ref()
+				.is_some_and(|kind| patterns.iter().any(|pattern| mime_matches(pattern, kind)))
+		};
+		if type_matches(&self.denied_types)
+			|| self
+				.allowed_types
+				.as_ref()
+				.is_some_and(|allowed| !type_matches(allowed))
+		{
+			return AttachmentDecision::Denied(match &kind {
+				Some(kind) => format!("{kind} files aren't allowed"),
+				None => "files of an unknown type aren't allowed".to_owned(),
+			});
+		}
+
+		if let Some(max_size_mb) = self.max_size_mb {
+			if attachment.size > u64::from(max_size_mb) * 1024 * 1024 {
+				return AttachmentDecision::Denied(format!("it's bigger than {max_size_mb} MB"));
+			}
+		}
+
+		let is_image = kind.as_ref().is_some_and(|kind| kind.starts_with("image/"));
+		if self.spoiler_images && is_image {
+			AttachmentDecision::Spoilered
+		} else {
+			AttachmentDecision::Allowed
+		}
+	}
+}
+
+/// Checks a MIME type against a pattern like `image/png` or `image/*`.
+fn mime_matches(pattern: &str, kind: &str) -> bool {
+	match pattern.strip_suffix("/*") {
+		Some(prefix) => kind
+			.split_once('/')
+			.is_some_and(|(kind_prefix, _)| kind_prefix == prefix),
+		None => pattern == kind,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use twilight::id::Id;
+
+	fn attachment(filename: &str, content_type: Option<&str>, size: u64) -> Attachment {
+		Attachment {
+			content_type: content_type.map(str::to_owned),
+			ephemeral: false,
+			duration_secs: None,
+			filename: filename.to_owned(),
+			description: None,
+			height: None,
+			id: Id::new(1),
+			proxy_url: String::new(),
+			size,
+			url: String::new(),
+			waveform: None,
+			width: None,
+		}
+	}
+
+	fn policy(config: AttachmentsConfig) -> AttachmentPolicy {
+		AttachmentPolicy::new(config).unwrap()
+	}
+
+	fn is_denied(decision: AttachmentDecision) -> bool {
+		matches!(decision, AttachmentDecision::Denied(_))
+	}
+
+	#[test]
+	fn mime_patterns() {
+		assert!(mime_matches("image/png", "image/png"));
+		assert!(!mime_matches("image/png", "image/gif"));
+		assert!(mime_matches("image/*", "image/gif"));
+		assert!(!mime_matches("image/*", "video/mp4"));
+		assert!(!mime_matches("image/*", "imagefoo"));
+		assert!(!mime_matches("image/*", "image"));
+	}
+
+	#[test]
+	fn everything_is_allowed_by_default() {
+		let policy = AttachmentPolicy::default();
+		let decision = policy.check(&attachment("cat.png", Some("image/png"), 1000));
+		assert!(matches!(decision, AttachmentDecision::Allowed));
+		let decision = policy.check(&attachment("README", None, 1000));
+		assert!(matches!(decision, AttachmentDecision::Allowed));
+	}
+
+	#[test]
+	fn extensions() {
+		let denied = policy(AttachmentsConfig {
+			denied_extensions: vec![".EXE".to_owned()],
+			..Default::default()
+		});
+		assert!(is_denied(denied.check(&attachment("setup.exe", None, 1))));
+		assert!(is_denied(denied.check(&attachment("SETUP.Exe", None, 1))));
+		assert!(!is_denied(denied.check(&attachment("notes.txt", None, 1))));
+
+		let allowed = policy(AttachmentsConfig {
+			allowed_extensions: Some(vec!["png".to_owned(), "jpg".to_owned()]),
+			..Default::default()
+		});
+		let check = |filename| allowed.check(&attachment(filename, None, 1));
+		assert!(!is_denied(check("cat.PNG")));
+		assert!(is_denied(check("cat.png.exe")));
+		assert!(is_denied(check("cat")));
+	}
+
+	#[test]
+	fn types() {
+		let policy = policy(AttachmentsConfig {
+			allowed_types: Some(vec!["image/*".to_owned()]),
+			denied_types: vec!["image/svg+xml".to_owned()],
+			..Default::default()
+		});
+		let check = |content_type| policy.check(&attachment("file", content_type, 1));
+		assert!(!is_denied(check(Some("image/png"))));
+		assert!(!is_denied(check(Some("Image/PNG; charset=binary"))));
+		assert!(is_denied(check(Some("image/svg+xml"))));
+		assert!(is_denied(check(Some("application/pdf"))));
+		// an unknown type can't be shown to be allowed
+		assert!(is_denied(check(None)));
+	}
+
+	#[test]
+	fn size() {
+		let policy = policy(AttachmentsConfig {
+			max_size_mb: Some(1),
+			..Default::default()
+		});
+		let check = |size| policy.check(&attachment("a.txt", None, size));
+		assert!(!is_denied(check(1024 * 1024)));
+		assert!(is_denied(check(1024 * 1024 + 1)));
+	}
+
+	#[test]
+	fn spoilers() {
+		let policy = policy(AttachmentsConfig {
+			spoiler_images: true,
+			..Default::default()
+		});
+		let decision = policy.check(&attachment("cat.png", Some("image/png"), 1));
+		assert!(matches!(decision, AttachmentDecision::Spoilered));
+		let decision = policy.check(&attachment("cat.txt", Some("text/plain"), 1));
+		assert!(matches!(decision, AttachmentDecision::Allowed));
+	}
+
+	#[test]
+	fn invalid_configs() {
+		assert!(AttachmentPolicy::new(AttachmentsConfig {
+			max_size_mb: Some(0),
+			..Default::default()
+		})
+		.is_err());
+		assert!(AttachmentPolicy::new(AttachmentsConfig {
+			scanner: Some(ScannerConfig::Command { command: vec![] }),
+			..Default::default()
+		})
+		.is_err());
+		assert!(AttachmentPolicy::new(AttachmentsConfig {
+			scanner: Some(ScannerConfig::Clamd {
+				socket: None,
+				address: None,
+			}),
+			..Default::default()
+		})
+		.is_err());
+	}
+}
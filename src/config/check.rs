@@ -0,0 +1,447 @@
+use super::{
+	validate_servers, AttachmentsConfig, AutoCloseConfig, CloseCooldownConfig, Config,
+	DatabaseConfig, FilterConfig, ModAction, PartialMessages, RateLimitConfig, RawConfig,
+	RawServer, ReactionsConfig, Server, Template, WebhookConfig,
+};
+use crate::cli::Args;
+use serde_yaml::{Mapping, Value};
+use std::{
+	fmt::{self, Display},
+	fs,
+	io::ErrorKind,
+	path::{Path, PathBuf},
+	process::ExitCode,
+};
+use twilight::{
+	http::Client,
+	id::{ChannelId, GuildId, RoleId},
+	model::channel::ChannelType,
+};
+
+const FIELDS: &[&str] = &[
+	"token",
+	"guild_id",
+	"forum_channel_id",
+	"forum_guild_id",
+	"ping_roles",
+	"servers",
+	"mod_actions",
+	"reactions",
+	"relay_typing",
+	"survey",
+	"auto_close",
+	"close_cooldown",
+	"rate_limit",
+	"filters",
+	"attachments",
+	"webhooks",
+	"open_message",
+	"close_message",
+	"messages",
+	"locales_dir",
+	"default_locale",
+	"database",
+];
+
+/// Where a problem was found.
+enum Source {
+	File { line: Option<usize>, column: usize },
+	Environment,
+	Discord,
+}
+
+struct Problem {
+	source: Source,
+	message: String,
+}
+
+struct DisplayProblem<'a>(&'a Path, &'a Problem);
+
+impl Display for DisplayProblem<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let DisplayProblem(path, problem) = self;
+		match problem.source {
+			Source::File {
+				line: Some(line),
+				column,
+			} => write!(f, "{}:{line}:{column}: ", path.display())?,
+			Source::File { line: None, .. } => write!(f, "{}: ", path.display())?,
+			Source::Environment => f.write_str("environment: ")?,
+			Source::Discord => f.write_str("discord: ")?,
+		}
+		f.write_str(&problem.message)
+	}
+}
+
+/// Checks the config and prints every problem found.
+///
+/// This doesn't touch the network unless `online` is set.
+pub fn check(args: &Args, online: bool) -> ExitCode {
+	let path = args.config_path();
+	let mut problems = Vec::new();
+
+	let raw_config = check_file(args, &path, &mut problems);
+	check_env(raw_config, &mut problems);
+
+	// catches anything that depends on more than one field, like translation bundles
+	if problems.is_empty() {
+		match Config::load(args) {
+			Ok(config) if online => check_online(&config, &mut problems),
+			Ok(_) => (),
+			Err(err) => problems.push(Problem {
+				source: Source::File {
+					line: None,
+					column: 1,
+				},
+				message: format!("{err:#}"),
+			}),
+		}
+	} else if online {
+		println!("skipping online checks until the problems below are fixed");
+	}
+
+	for problem in &problems {
+		println!("{}", DisplayProblem(&path, problem));
+	}
+
+	if problems.is_empty() {
+		println!("no problems found");
+		ExitCode::SUCCESS
+	} else {
+		println!("{} problem(s) found", problems.len());
+		ExitCode::FAILURE
+	}
+}
+
+/// Finds where a top-level field is defined, since [`Value`] doesn't keep track of that.
+fn field_line(text: &str, field: &str) -> Option<usize> {
+	text.lines()
+		.position(|line| {
+			line.strip_prefix(field)
+				.is_some_and(|rest| rest.trim_start().starts_with(':'))
+		})
+		.map(|index| index + 1)
+}
+
+fn file_problem(text: &str, field: &str, message: String) -> Problem {
+	Problem {
+		source: Source::File {
+			line: field_line(text, field),
+			column: 1,
+		},
+		message,
+	}
+}
+
+fn yaml_problem(err: serde_yaml::Error) -> Problem {
+	let mut message = err.to_string();
+	// the location is shown separately
+	if let Some(index) = message.rfind(" at line ") {
+		message.truncate(index);
+	}
+	Problem {
+		source: Source::File {
+			line: err.location().map(|location| location.line()),
+			column: err.location().map_or(1, |location| location.column()),
+		},
+		message,
+	}
+}
+
+/// Deserializes a single field from the whole document, so errors point at the right place.
+macro_rules! field_error {
+	($text:expr, $field:ident: $ty:ty) => {{
+		#[derive(serde::Deserialize)]
+		struct Only {
+			#[serde(default)]
+			#[allow(dead_code)]
+			$field: Option<$ty>,
+		}
+		serde_yaml::from_str::<Option<Only>>($text).err()
+	}};
+}
+
+/// Returns [`None`] if the file couldn't be fully read.
+fn check_file(args: &Args, path: &Path, problems: &mut Vec<Problem>) -> Option<RawConfig> {
+	let text = match fs::read_to_string(path) {
+		Ok(text) => text,
+		Err(err) if err.kind() == ErrorKind::NotFound && args.config.is_none() => {
+			return Some(RawConfig::default());
+		},
+		Err(err) => {
+			problems.push(Problem {
+				source: Source::File {
+					line: None,
+					column: 1,
+				},
+				message: format!("unable to read the config file: {err}"),
+			});
+			return None;
+		},
+	};
+
+	let fields = match serde_yaml::from_str(&text) {
+		Ok(Value::Mapping(fields)) => fields,
+		Ok(Value::Null) => Mapping::new(),
+		Ok(_) => {
+			problems.push(Problem {
+				source: Source::File {
+					line: Some(1),
+					column: 1,
+				},
+				message: "the config must be a list of `field: value` pairs".to_owned(),
+			});
+			return None;
+		},
+		Err(err) => {
+			// nothing else can be checked if the yaml is broken
+			problems.push(yaml_problem(err));
+			return None;
+		},
+	};
+
+	for key in fields.keys() {
+		match key.as_str() {
+			Some(key) if FIELDS.contains(&key) => (),
+			Some(key) => problems.push(file_problem(
+				&text,
+				key,
+				format!(
+					"unknown field `{key}`, expected one of `{}`",
+					FIELDS.join("`, `")
+				),
+			)),
+			None => problems.push(Problem {
+				source: Source::File {
+					line: None,
+					column: 1,
+				},
+				message: format!("field names must be text, found {key:?}"),
+			}),
+		}
+	}
+
+	let type_errors = [
+		field_error!(&text, token: String),
+		field_error!(&text, guild_id: GuildId),
+		field_error!(&text, forum_channel_id: ChannelId),
+		field_error!(&text, forum_guild_id: GuildId),
+		field_error!(&text, ping_roles: Vec<RoleId>),
+		field_error!(&text, servers: Vec<RawServer>),
+		field_error!(&text, mod_actions: Vec<ModAction>),
+		field_error!(&text, reactions: ReactionsConfig),
+		field_error!(&text, relay_typing: bool),
+		field_error!(&text, survey: bool),
+		field_error!(&text, auto_close: AutoCloseConfig),
+		field_error!(&text, close_cooldown: CloseCooldownConfig),
+		field_error!(&text, rate_limit: RateLimitConfig),
+		field_error!(&text, filters: Vec<FilterConfig>),
+		field_error!(&text, attachments: AttachmentsConfig),
+		field_error!(&text, webhooks: Vec<WebhookConfig>),
+		field_error!(&text, open_message: Template),
+		field_error!(&text, close_message: Template),
+		field_error!(&text, messages: PartialMessages),
+		field_error!(&text, locales_dir: PathBuf),
+		field_error!(&text, default_locale: String),
+		field_error!(&text, database: DatabaseConfig),
+	];
+	let mut has_type_errors = false;
+	for err in type_errors.into_iter().flatten() {
+		has_type_errors = true;
+		problems.push(yaml_problem(err));
+	}
+	if has_type_errors {
+		return None;
+	}
+
+	// every field has the right type now, so this can't fail
+	let raw_config: RawConfig = match serde_yaml::from_str::<Option<RawConfig>>(&text) {
+		Ok(raw_config) => raw_config.unwrap_or_default(),
+		Err(err) => {
+			problems.push(yaml_problem(err));
+			return None;
+		},
+	};
+
+	// out of range values
+	if let Some(DatabaseConfig::Sqlite { path }) = &raw_config.database {
+		if path.as_os_str().is_empty() {
+			problems.push(file_problem(
+				&text,
+				"database",
+				"database path can't be empty".to_owned(),
+			));
+		}
+	}
+	if let Some(DatabaseConfig::Postgres { url }) = &raw_config.database {
+		if !url.starts_with("postgres://") && !url.starts_with("postgresql://") {
+			problems.push(file_problem(
+				&text,
+				"database",
+				"database url must start with postgres:// or postgresql://".to_owned(),
+			));
+		}
+	}
+
+	if let Some(auto_close) = &raw_config.auto_close {
+		if let Err(err) = auto_close.validate() {
+			problems.push(file_problem(&text, "auto_close", err.to_string()));
+		}
+	}
+	if let Some(rate_limit) = &raw_config.rate_limit {
+		if let Err(err) = rate_limit.validate() {
+			problems.push(file_problem(&text, "rate_limit", err.to_string()));
+		}
+	}
+
+	// the top-level server can come from the environment instead, and check_env reports any
+	// problems with that
+	let mut env_config = RawConfig::default();
+	let _ = env_config.apply_env();
+	let top_level = RawConfig {
+		guild_id: env_config.guild_id.or(raw_config.guild_id),
+		forum_channel_id: env_config.forum_channel_id.or(raw_config.forum_channel_id),
+		forum_guild_id: env_config.forum_guild_id.or(raw_config.forum_guild_id),
+		..RawConfig::default()
+	}
+	.top_level_server();
+	// without either, check_env reports the missing top-level fields instead
+	if top_level.is_some() || raw_config.servers.is_some() {
+		let servers: Vec<_> = top_level
+			.into_iter()
+			.chain(raw_config.servers.iter().flatten().map(RawServer::server))
+			.collect();
+		if let Err(err) = validate_servers(&servers) {
+			let field = match raw_config.servers {
+				Some(_) => "servers",
+				None => "guild_id",
+			};
+			problems.push(file_problem(&text, field, err.to_string()));
+		}
+	}
+
+	Some(raw_config)
+}
+
+fn check_env(file_config: Option<RawConfig>, problems: &mut Vec<Problem>) {
+	// missing fields can't be reported if the file had fields that couldn't be read
+	let file_complete = file_config.is_some();
+	let mut raw_config = file_config.unwrap_or_default();
+
+	let env_problem = |message: String| Problem {
+		source: Source::Environment,
+		message,
+	};
+
+	if let Err(err) = raw_config.apply_env() {
+		problems.push(env_problem(err.to_string()));
+	}
+
+	// the top level server is optional when there's a `servers` list, and check_file reports it
+	// being empty
+	let has_servers = raw_config.servers.is_some();
+	for (field, var, missing) in [
+		("token", "MODMAIL_TOKEN", raw_config.token.is_none()),
+		(
+			"guild_id",
+			"MODMAIL_GUILD_ID",
+			raw_config.guild_id.is_none() && !has_servers,
+		),
+		(
+			"forum_channel_id",
+			"MODMAIL_FORUM_CHANNEL_ID",
+			raw_config.forum_channel_id.is_none() && !has_servers,
+		),
+	] {
+		if missing && file_complete {
+			problems.push(env_problem(format!(
+				"missing required field `{field}` (set it in the config file or with {var})"
+			)));
+		}
+	}
+}
+
+fn check_online(config: &Config, problems: &mut Vec<Problem>) {
+	let mut discord_problem = |message: String| {
+		problems.push(Problem {
+			source: Source::Discord,
+			message,
+		})
+	};
+
+	let runtime = match tokio::runtime::Builder::new_current_thread()
+		.enable_all()
+		.build()
+	{
+		Ok(runtime) => runtime,
+		Err(err) => return discord_problem(format!("unable to start tokio runtime: {err}")),
+	};
+
+	runtime.block_on(async {
+		let http = Client::new(config.token.clone());
+
+		if let Err(err) = http.current_user().await {
+			return discord_problem(format!("token was rejected: {err}"));
+		}
+
+		for server in &config.servers {
+			check_server(&http, config, server, &mut discord_problem).await;
+		}
+	});
+}
+
+async fn check_server(
+	http: &Client,
+	config: &Config,
+	server: &Server,
+	discord_problem: &mut impl FnMut(String),
+) {
+	let mut guild_ids = vec![("guild_id", server.guild_id)];
+	if server.forum_guild_id != server.guild_id {
+		guild_ids.push(("forum_guild_id", server.forum_guild_id));
+	}
+	for (field, guild_id) in guild_ids {
+		if http.guild(guild_id).await.is_err() {
+			discord_problem(format!(
+				"{field} {guild_id} doesn't exist, or the bot isn't in it"
+			));
+		}
+	}
+
+	match http.channel(server.forum_channel_id).await {
+		Ok(response) => match response.model().await {
+			Ok(channel) if channel.kind != ChannelType::GuildForum => discord_problem(format!(
+				"forum_channel_id {} isn't a forum channel",
+				channel.id
+			)),
+			Ok(channel) if channel.guild_id != Some(server.forum_guild_id) => {
+				discord_problem(format!(
+					"forum_channel_id {} isn't in forum_guild_id {}",
+					channel.id, server.forum_guild_id,
+				))
+			},
+			Ok(_) => (),
+			Err(err) => discord_problem(format!("unable to read forum channel: {err}")),
+		},
+		Err(_) => discord_problem(format!(
+			"forum_channel_id {} doesn't exist, or the bot can't see it",
+			server.forum_channel_id,
+		)),
+	}
+
+	let ping_roles = config.live().ping_roles(server.guild_id).to_vec();
+	if !ping_roles.is_empty() {
+		let roles = match http.roles(server.forum_guild_id).await {
+			Ok(response) => response.models().await.unwrap_or_default(),
+			Err(_) => Vec::new(),
+		};
+		for role_id in ping_roles {
+			if !roles.iter().any(|role| role.id == role_id) {
+				discord_problem(format!(
+					"ping_roles: role {role_id} doesn't exist in forum_guild_id {}",
+					server.forum_guild_id,
+				));
+			}
+		}
+	}
+}
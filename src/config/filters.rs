@@ -0,0 +1,201 @@
+use regex::{Regex, RegexBuilder};
+use std::str::FromStr;
+
+/// A filter as it's written in the config file.
+///
+/// A message matches if anything that's set matches.
+#[derive(serde::Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FilterConfig {
+	/// Shown to staff and saved with each hit. Defaults to the filter's position in the list.
+	#[serde(default)]
+	name: Option<String>,
+	/// Whole words or phrases, ignoring case.
+	#[serde(default)]
+	words: Vec<String>,
+	#[serde(default)]
+	regex: Option<String>,
+	/// Whether server invite links match.
+	#[serde(default)]
+	invites: bool,
+	/// Links to these domains or their subdomains match.
+	#[serde(default)]
+	domains: Vec<String>,
+	action: FilterAction,
+}
+
+/// What happens to a message that matches a filter, from least to most strict.
+#[derive(serde::Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterAction {
+	/// Relay it with a warning for staff.
+	Flag,
+	/// Relay it behind spoiler tags, with a warning for staff.
+	Spoiler,
+	/// Don't relay it, and tell staff that it was blocked.
+	Block,
+}
+
+impl FilterAction {
+	const ALL: &[Self] = &[Self::Flag, Self::Spoiler, Self::Block];
+
+	/// How this is stored in the database.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Flag => "flag",
+			Self::Spoiler => "spoiler",
+			Self::Block => "block",
+		}
+	}
+}
+
+impl FromStr for FilterAction {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::ALL
+			.iter()
+			.copied()
+			.find(|action| action.name() == s)
+			.ok_or_else(|| format!("unknown filter action `{s}`"))
+	}
+}
+
+/// Part of a message that matched a filter.
+pub struct FilterMatch {
+	pub filter: String,
+	pub action: FilterAction,
+	pub matched: String,
+}
+
+struct Filter {
+	name: String,
+	words: Option<Regex>,
+	regex: Option<Regex>,
+	invites: bool,
+	domains: Vec<String>,
+	action: FilterAction,
+}
+
+/// Every filter from the config, ready to check messages with.
+pub struct Filters {
+	filters: Vec<Filter>,
+	invite: Regex,
+	link: Regex,
+}
+
+impl Default for Filters {
+	fn default() -> Self {
+		Self {
+			filters: Vec::new(),
+			invite: Regex::new(
+				r"(?i)(?:discord(?:app)?\.com/invite|discord\.gg|discord\.me|dsc\.gg)/[\w-]+",
+			)
+			.expect("invite regex should be valid"),
+			link: Regex::new(r"(?i)\bhttps?://(?:[^\s/@]*@)?([^\s/:?#>|]+)")
+				.expect("link regex should be valid"),
+		}
+	}
+}
+
+impl Filters {
+	pub fn compile(configs: Vec<FilterConfig>) -> anyhow::Result<Self> {
+		let mut filters = Vec::with_capacity(configs.len());
+		for (i, config) in configs.into_iter().enumerate() {
+			let name = config.name.unwrap_or_else(|| format!("filter {}", i + 1));
+
+			let words = if config.words.is_empty() {
+				None
+			} else {
+				let words: Vec<_> = config
+					.words
+					.iter()
+					.map(|word| regex::escape(word.trim()))
+					.collect();
+				Some(
+					RegexBuilder::new(&format!(r"\b(?:{})\b", words.join("|")))
+						.case_insensitive(true)
+						.build()
+						.map_err(|err| anyhow!("filters: `{name}` has invalid words: {err}"))?,
+				)
+			};
+			let regex = match config.regex {
+				Some(pattern) => Some(
+					Regex::new(&pattern)
+						.map_err(|err| anyhow!("filters: `{name}` has an invalid regex: {err}"))?,
+				),
+				None => None,
+			};
+			let domains: Vec<_> = config
+				.domains
+				.iter()
+				.map(|domain| domain.trim().trim_start_matches('.').to_lowercase())
+				.collect();
+
+			if words.is_none() && regex.is_none() && !config.invites && domains.is_empty() {
+				bail!("filters: `{name}` needs words, regex, invites, or domains");
+			}
+
+			filters.push(Filter {
+				name,
+				words,
+				regex,
+				invites: config.invites,
+				domains,
+				action: config.action,
+			});
+		}
+
+		Ok(Self {
+			filters,
+			..Self::default()
+		})
+	}
+
+	/// Every filter that some text matches, with the first part that matched each one.
+	pub fn check(&self, text: &str) -> Vec<FilterMatch> {
+		let mut matches = Vec::new();
+		if self.filters.is_empty() || text.is_empty() {
+			return matches;
+		}
+
+		let hosts: Vec<_> = self
+			.link
+			.captures_iter(text)
+			.filter_map(|captures| captures.get(1))
+			.map(|host| host.as_str().trim_end_matches('.').to_lowercase())
+			.collect();
+
+		for filter in &self.filters {
+			let matched = filter
+				.words
+				.iter()
+				.chain(&filter.regex)
+				.chain(filter.invites.then_some(&self.invite))
+				.find_map(|regex| regex.find(text))
+				.map(|found| found.as_str().to_owned())
+				.or_else(|| {
+					hosts
+						.iter()
+						.find(|host| {
+							filter.domains.iter().any(|domain| {
+								*host == domain
+									|| host
+										.strip_suffix(domain.as_str())
+										.is_some_and(|rest| rest.ends_with('.'))
+							})
+						})
+						.cloned()
+				});
+			if let Some(matched) = matched {
+				matches.push(FilterMatch {
+					filter: filter.name.clone(),
+					action: filter.action,
+					matched,
+				});
+			}
+		}
+
+		matches
+	}
+}
@@ -0,0 +1,160 @@
+use super::messages::{Messages, PartialMessages};
+use anyhow::Context;
+use std::{collections::BTreeMap, fs, io::ErrorKind, path::Path};
+
+/// Every locale that discord supports.
+pub const LOCALES: &[&str] = &[
+	"bg", "cs", "da", "de", "el", "en-GB", "en-US", "es-419", "es-ES", "fi", "fr", "hi", "hr",
+	"hu", "id", "it", "ja", "ko", "lt", "nl", "no", "pl", "pt-BR", "ro", "ru", "sv-SE", "th", "tr",
+	"uk", "vi", "zh-CN", "zh-TW",
+];
+
+/// A translation bundle, read from `<locale>.yml` in the locales directory.
+#[derive(serde::Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct RawBundle {
+	#[serde(default)]
+	commands: BTreeMap<String, CommandText>,
+	#[serde(default)]
+	messages: PartialMessages,
+}
+
+/// The translated name and description of a command or command option.
+#[derive(serde::Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct CommandText {
+	#[serde(default)]
+	name: Option<String>,
+	#[serde(default)]
+	description: Option<String>,
+	#[serde(default)]
+	options: BTreeMap<String, CommandText>,
+}
+
+struct Bundle {
+	commands: BTreeMap<String, CommandText>,
+	messages: Messages,
+}
+
+/// Every translation bundle, by locale.
+#[derive(Default)]
+pub struct Locales {
+	bundles: BTreeMap<String, Bundle>,
+}
+
+impl Locales {
+	/// Loads every bundle in a directory. Messages that a bundle doesn't have are taken from `base`.
+	pub fn load(dir: &Path, base: &Messages) -> anyhow::Result<Self> {
+		let entries = match fs::read_dir(dir) {
+			Ok(entries) => entries,
+			// translations are optional
+			Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+			Err(err) => return Err(err).context(format!("unable to open {}", dir.display())),
+		};
+
+		let mut bundles = BTreeMap::new();
+		for entry in entries {
+			let path = entry?.path();
+			if !matches!(
+				path.extension().and_then(|ext| ext.to_str()),
+				Some("yml" | "yaml")
+			) {
+				continue;
+			}
+
+			let locale = path.file_stem().and_then(|stem| stem.to_str());
+			let Some(locale) = locale.filter(|locale| LOCALES.contains(locale)) else {
+				bail!(
+					"{} must be named after a discord locale, one of: {}",
+					path.display(),
+					LOCALES.join(", "),
+				);
+			};
+
+			let text = fs::read_to_string(&path)
+				.with_context(|| format!("unable to read {}", path.display()))?;
+			// an empty file is valid yaml, but doesn't deserialize to a struct
+			let raw_bundle: Option<RawBundle> = serde_yaml::from_str(&text)
+				.with_context(|| format!("invalid translation bundle {}", path.display()))?;
+			let raw_bundle = raw_bundle.unwrap_or_default();
+
+			bundles.insert(
+				locale.to_owned(),
+				Bundle {
+					commands: raw_bundle.commands,
+					messages: base.merged(raw_bundle.messages),
+				},
+			);
+		}
+
+		Ok(Self { bundles })
+	}
+
+	/// Finds the bundle for a locale, or for another locale of the same language.
+	fn bundle(&self, locale: &str) -> Option<&Bundle> {
+		let language = |locale: &str| locale.split('-').next().map(str::to_owned);
+		self.bundles.get(locale).or_else(|| {
+			self.bundles
+				.iter()
+				.find(|(other, _)| language(other) == language(locale))
+				.map(|(_, bundle)| bundle)
+		})
+	}
+
+	pub fn messages(&self, locale: &str) -> Option<&Messages> {
+		self.bundle(locale).map(|bundle| &bundle.messages)
+	}
+
+	/// Collects one translated field of a command, or of one of its options, for every locale.
+	///
+	/// Discord only accepts exact locales here, so this doesn't fall back to similar ones.
+	fn command_text(
+		&self,
+		command: &str,
+		option: Option<&str>,
+		field: fn(&CommandText) -> &Option<String>,
+	) -> Vec<(String, String)> {
+		let mut localizations = Vec::new();
+		for (locale, bundle) in &self.bundles {
+			let Some(mut text) = bundle.commands.get(command) else {
+				continue;
+			};
+			if let Some(option) = option {
+				match text.options.get(option) {
+					Some(option_text) => text = option_text,
+					None => continue,
+				}
+			}
+			if let Some(value) = field(text) {
+				localizations.push((locale.clone(), value.clone()));
+			}
+		}
+		localizations
+	}
+
+	pub fn command_names(&self, command: &str) -> Vec<(String, String)> {
+		self.command_text(command, None, |text| &text.name)
+	}
+
+	pub fn command_descriptions(&self, command: &str) -> Vec<(String, String)> {
+		self.command_text(command, None, |text| &text.description)
+	}
+
+	pub fn option_names(&self, command: &str, option: &str) -> Vec<(String, String)> {
+		self.command_text(command, Some(option), |text| &text.name)
+	}
+
+	pub fn option_descriptions(&self, command: &str, option: &str) -> Vec<(String, String)> {
+		self.command_text(command, Some(option), |text| &text.description)
+	}
+
+	/// Whether both have the same command translations, so commands don't need to be synced again.
+	pub fn same_commands(&self, other: &Self) -> bool {
+		self.bundles.len() == other.bundles.len()
+			&& self.bundles.iter().zip(&other.bundles).all(
+				|((locale, bundle), (other_locale, other_bundle))| {
+					locale == other_locale && bundle.commands == other_bundle.commands
+				},
+			)
+	}
+}
@@ -0,0 +1,138 @@
+use super::Template;
+
+fn template(text: &str) -> Template {
+	text.parse().expect("default templates should be valid")
+}
+
+macro_rules! messages {
+	($($(#[doc = $doc:literal])* $name:ident: $ty:ty = $default:expr,)*) => {
+		/// Templates for the messages the bot sends.
+		#[derive(Clone)]
+		pub struct Messages {
+			$($(#[doc = $doc])* pub $name: $ty,)*
+		}
+
+		/// Messages from the config file or a translation bundle.
+		///
+		/// Anything left out keeps the value it's merged onto.
+		#[derive(serde::Deserialize, Default)]
+		#[serde(deny_unknown_fields)]
+		pub struct PartialMessages {
+			$(#[serde(default)] $name: Option<$ty>,)*
+		}
+
+		impl Default for Messages {
+			fn default() -> Self {
+				Self { $($name: $default,)* }
+			}
+		}
+
+		impl Messages {
+			/// Replaces every message that `partial` has.
+			pub fn merged(&self, partial: PartialMessages) -> Self {
+				Self { $($name: partial.$name.unwrap_or_else(|| self.$name.clone()),)* }
+			}
+		}
+	};
+}
+
+messages! {
+	/// Sent to the user when their ticket is opened.
+	open_message: Option<Template> = None,
+	/// Sent to the user when their ticket is closed, unless it's closed silently. `{reason}` is
+	/// only filled in if staff choose to share it.
+	close_message: Option<Template> = None,
+	/// Sent in DMs by `/modmail`.
+	modmail_prompt: Template = template("Send a message here to open a ticket."),
+	/// The response to `/modmail`, with a `{link}` to the DM.
+	modmail_link: Template = template("Click here: {link}"),
+	/// The response to `/modmail` if the user can't be DMed.
+	modmail_dm_failed: Template = template(indoc! {"
+		⚠️ **I couldn't send you a message.**
+		Make sure that you're accepting DMs from this server and that you haven't blocked me.
+	"}),
+	/// How staff replies are sent to the user, with the reply as `{message}`.
+	reply: Template = template("{message}"),
+	/// Shown in the thread if a reply couldn't be sent.
+	reply_dm_failed: Template = template(
+		"⚠️ Unable to send DM. The user may have DMs closed or they blocked me.",
+	),
+	/// Shown in the thread after a message with attachments. `{attachments}` has a line for each
+	/// one, with its url or why it wasn't relayed.
+	attachments: Template = template("Attachments:\n{attachments}"),
+	/// Sent to users who are in more than one server, to ask which one they need help with.
+	guild_picker: Template = template("Which server do you need help with?"),
+	/// Replaces the server picker once the user picks one.
+	guild_picked: Template = template("Sending your messages to {server.name}."),
+	/// Sent to the user by `/warn` if staff choose to notify them, with the `{reason}`.
+	warn_notice: Template = template("⚠️ You were warned in {server.name}: {reason}"),
+	/// Sent to the user by `/timeout` if staff choose to notify them. `{expires}` is when it ends.
+	timeout_notice: Template = template(
+		"🔇 You were timed out in {server.name} until {expires}: {reason}",
+	),
+	/// Sent to the user by `/kick` if staff choose to notify them.
+	kick_notice: Template = template("👢 You were kicked from {server.name}: {reason}"),
+	/// Sent to the user by `/ban` if staff choose to notify them.
+	ban_notice: Template = template("🔨 You were banned from {server.name}: {reason}"),
+	/// Sent to the user when their ticket is about to be closed for inactivity. `{expires}` is when.
+	inactivity_warning: Template = template(
+		"⏰ This ticket will be closed {expires} unless you reply.",
+	),
+	/// Shown in the thread when the user is warned that their ticket will be closed.
+	inactivity_warned: Template = template(
+		"⏰ The user was told that this ticket will be closed {expires} without a reply.",
+	),
+	/// Shown in the thread when a ticket is closed for inactivity, with the `{reason}`.
+	inactivity_closed: Template = template("⏰ Ticket closed automatically: {reason}."),
+	/// The reason a ticket is closed when the user didn't reply to staff for `{hours}`.
+	inactivity_user_reason: Template = template("the user didn't reply for {hours} hours"),
+	/// The reason a ticket is closed when staff didn't reply to the user for `{hours}`.
+	inactivity_staff_reason: Template = template("staff didn't reply for {hours} hours"),
+	/// Sent to the user after their ticket is closed if `survey` is on, with buttons to rate it.
+	survey_prompt: Template = template("How did we do? Rate your experience from 1 to 5."),
+	/// Replaces the survey once the user rates their ticket, with their `{rating}`.
+	survey_thanks: Template = template("Thanks for your feedback! You rated us {rating}/5."),
+	/// Shown in the thread when the user rates their ticket.
+	survey_rated: Template = template("⭐ The user rated this ticket {rating}/5."),
+	/// Shown in the thread when the user comments on their rating, with the comment as `{message}`.
+	survey_commented: Template = template("💬 The user commented on their rating: {message}"),
+	/// The label of the button that lets the user comment on their rating.
+	survey_comment_button: Template = template("Add a comment"),
+	/// The title of the form that the comment button opens.
+	survey_comment_title: Template = template("Add a comment"),
+	/// The label of the text box in that form.
+	survey_comment_label: Template = template("Comment"),
+	/// Sent to a user who DMs soon after their ticket was closed, if `close_cooldown.action` is
+	/// `prompt`. `{expires}` is when the cooldown ends.
+	reopen_prompt: Template = template(
+		"Your ticket was just closed. Press the button to reopen it, or send another message \
+		after {expires} to open a new one.",
+	),
+	/// The label of the button on the reopen prompt.
+	reopen_button: Template = template("Reopen"),
+	/// Replaces the reopen prompt once the user reopens their ticket.
+	reopened: Template = template("Your ticket was reopened."),
+	/// Sent to a user when their messages stop being relayed for being sent too quickly.
+	rate_limited: Template = template(
+		"⚠️ You're sending messages too quickly, so some of them weren't delivered. Please wait a \
+		moment before sending more.",
+	),
+	/// Shown in the thread when a user is blocked for sending too many messages, with the
+	/// `{reason}`.
+	rate_limit_blocked: Template = template(
+		"⛔ User was blocked automatically: {reason}. Their messages will be ignored until they're \
+		unblocked.",
+	),
+	/// The reason a user is blocked for sending too many messages.
+	rate_limit_reason: Template = template("they sent too many messages too quickly"),
+	/// Sent to a user when their message isn't relayed because it matched a filter.
+	filter_blocked: Template = template(
+		"🚫 Your message wasn't delivered because it contains something that isn't allowed.",
+	),
+	/// Shown in the thread when staff reopen a ticket, under the user's mention.
+	staff_reopened: Template = template("Reopened by {staff.mention}"),
+	/// Shown in the thread when the user leaves the ticket's server.
+	user_left: Template = template("👋 User left the server."),
+	/// Shown in the thread when the user joins the ticket's server again.
+	user_rejoined: Template = template("👋 User rejoined the server."),
+}
@@ -0,0 +1,784 @@
+use crate::cli::Args;
+use anyhow::Context;
+use std::{
+	collections::HashMap,
+	env,
+	fs::File,
+	io::{BufReader, ErrorKind},
+	path::{Path, PathBuf},
+	str::FromStr,
+	sync::{Arc, PoisonError, RwLock},
+};
+use twilight::id::{ChannelId, GuildId, RoleId};
+
+mod check;
+pub use check::check;
+
+mod template;
+pub use template::{Rendered, Template, Vars};
+
+mod messages;
+pub use messages::Messages;
+use messages::PartialMessages;
+
+mod locales;
+pub use locales::Locales;
+use locales::LOCALES;
+
+mod attachments;
+use attachments::AttachmentsConfig;
+pub use attachments::{AttachmentDecision, AttachmentPolicy, ScannerConfig};
+
+mod filters;
+use filters::FilterConfig;
+pub use filters::{FilterAction, FilterMatch, Filters};
+
+mod webhooks;
+pub use webhooks::{WebhookConfig, WebhookEvent};
+
+/// Everything is optional here, since any field can also come from the environment.
+#[derive(serde::Deserialize, Default)]
+struct RawConfig {
+	#[serde(default)]
+	token: Option<String>,
+	#[serde(default)]
+	guild_id: Option<GuildId>,
+	#[serde(default)]
+	forum_channel_id: Option<ChannelId>,
+	#[serde(default)]
+	forum_guild_id: Option<GuildId>,
+	#[serde(default)]
+	ping_roles: Option<Vec<RoleId>>,
+	#[serde(default)]
+	servers: Option<Vec<RawServer>>,
+	#[serde(default)]
+	mod_actions: Option<Vec<ModAction>>,
+	#[serde(default)]
+	reactions: Option<ReactionsConfig>,
+	#[serde(default)]
+	relay_typing: Option<bool>,
+	#[serde(default)]
+	survey: Option<bool>,
+	#[serde(default)]
+	auto_close: Option<AutoCloseConfig>,
+	#[serde(default)]
+	close_cooldown: Option<CloseCooldownConfig>,
+	#[serde(default)]
+	rate_limit: Option<RateLimitConfig>,
+	#[serde(default)]
+	filters: Option<Vec<FilterConfig>>,
+	#[serde(default)]
+	attachments: Option<AttachmentsConfig>,
+	#[serde(default)]
+	webhooks: Option<Vec<WebhookConfig>>,
+	#[serde(default)]
+	open_message: Option<Template>,
+	#[serde(default)]
+	close_message: Option<Template>,
+	#[serde(default)]
+	messages: Option<PartialMessages>,
+	#[serde(default)]
+	locales_dir: Option<PathBuf>,
+	#[serde(default)]
+	default_locale: Option<String>,
+	#[serde(default)]
+	database: Option<DatabaseConfig>,
+}
+
+impl RawConfig {
+	fn read(args: &Args) -> anyhow::Result<Self> {
+		let path = args.config_path();
+		let file = match File::open(&path) {
+			Ok(file) => file,
+			// the environment might have everything we need
+			Err(err) if err.kind() == ErrorKind::NotFound && args.config.is_none() => {
+				return Ok(Self::default());
+			},
+			Err(err) => return Err(err).context(format!("unable to open {}", path.display())),
+		};
+
+		// an empty file is valid yaml, but doesn't deserialize to a struct
+		let raw_config: Option<Self> = serde_yaml::from_reader(BufReader::new(file))
+			.with_context(|| format!("invalid config file {}", path.display()))?;
+		Ok(raw_config.unwrap_or_default())
+	}
+
+	/// The server from the top-level fields, if they're set.
+	fn top_level_server(&self) -> Option<Server> {
+		let guild_id = self.guild_id?;
+		Some(Server {
+			guild_id,
+			forum_channel_id: self.forum_channel_id?,
+			forum_guild_id: self.forum_guild_id.unwrap_or(guild_id),
+		})
+	}
+
+	fn apply_env(&mut self) -> anyhow::Result<()> {
+		fn var<T: FromStr>(name: &str, expected: &str) -> anyhow::Result<Option<T>> {
+			match env::var(name) {
+				Ok(value) => match value.trim().parse() {
+					Ok(value) => Ok(Some(value)),
+					Err(_) => bail!("{name} must be {expected}"),
+				},
+				Err(env::VarError::NotPresent) => Ok(None),
+				Err(env::VarError::NotUnicode(_)) => bail!("{name} must be valid unicode"),
+			}
+		}
+
+		fn var_list(name: &str) -> anyhow::Result<Option<Vec<String>>> {
+			Ok(var::<String>(name, "text")?.map(|list| {
+				list.split(',')
+					.map(str::trim)
+					.filter(|s| !s.is_empty())
+					.map(str::to_owned)
+					.collect()
+			}))
+		}
+
+		const ID: &str = "a non-zero integer";
+		if let Ok(token) = env::var("MODMAIL_TOKEN") {
+			self.token = Some(token);
+		}
+		if let Some(id) = var("MODMAIL_GUILD_ID", ID)? {
+			self.guild_id = Some(id);
+		}
+		if let Some(id) = var("MODMAIL_FORUM_CHANNEL_ID", ID)? {
+			self.forum_channel_id = Some(id);
+		}
+		if let Some(id) = var("MODMAIL_FORUM_GUILD_ID", ID)? {
+			self.forum_guild_id = Some(id);
+		}
+		if let Some(list) = var::<String>("MODMAIL_PING_ROLES", "text")? {
+			let mut ping_roles = Vec::new();
+			for id in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+				ping_roles.push(id.parse().ok().with_context(|| {
+					format!("MODMAIL_PING_ROLES must be a comma-separated list of {ID}s")
+				})?);
+			}
+			self.ping_roles = Some(ping_roles);
+		}
+		if let Some(list) = var::<String>("MODMAIL_MOD_ACTIONS", "text")? {
+			let mut mod_actions = Vec::new();
+			for name in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+				mod_actions.push(name.parse().ok().with_context(|| {
+					let names: Vec<_> = ModAction::ALL.iter().map(|action| action.name()).collect();
+					format!(
+						"MODMAIL_MOD_ACTIONS must be a comma-separated list of: {}",
+						names.join(", ")
+					)
+				})?);
+			}
+			self.mod_actions = Some(mod_actions);
+		}
+		if let Some(allowed) = var_list("MODMAIL_REACTIONS_ALLOWED")? {
+			self.reactions.get_or_insert_with(Default::default).allowed = Some(allowed);
+		}
+		if let Some(per_minute) = var("MODMAIL_REACTIONS_PER_MINUTE", "a whole number")? {
+			self.reactions
+				.get_or_insert_with(Default::default)
+				.per_minute = per_minute;
+		}
+		if let Some(relay_typing) = var("MODMAIL_RELAY_TYPING", "true or false")? {
+			self.relay_typing = Some(relay_typing);
+		}
+		if let Some(survey) = var("MODMAIL_SURVEY", "true or false")? {
+			self.survey = Some(survey);
+		}
+		if let Some(minutes) = var("MODMAIL_CLOSE_COOLDOWN_MINUTES", "a whole number")? {
+			self.close_cooldown
+				.get_or_insert_with(Default::default)
+				.minutes = minutes;
+		}
+		if let Some(action) = var("MODMAIL_CLOSE_COOLDOWN_ACTION", "append or prompt")? {
+			self.close_cooldown
+				.get_or_insert_with(Default::default)
+				.action = action;
+		}
+		if let Some(per_minute) = var("MODMAIL_RATE_LIMIT_PER_MINUTE", "a whole number")? {
+			self.rate_limit
+				.get_or_insert_with(Default::default)
+				.per_minute = per_minute;
+		}
+		if let Some(burst) = var("MODMAIL_RATE_LIMIT_BURST", "a whole number")? {
+			self.rate_limit.get_or_insert_with(Default::default).burst = burst;
+		}
+		if let Some(block_after) = var("MODMAIL_RATE_LIMIT_BLOCK_AFTER", "a whole number")? {
+			self.rate_limit
+				.get_or_insert_with(Default::default)
+				.block_after = Some(block_after);
+		}
+		if let Some(extensions) = var_list("MODMAIL_ATTACHMENTS_ALLOWED_EXTENSIONS")? {
+			self.attachments
+				.get_or_insert_with(Default::default)
+				.allowed_extensions = Some(extensions);
+		}
+		if let Some(extensions) = var_list("MODMAIL_ATTACHMENTS_DENIED_EXTENSIONS")? {
+			self.attachments
+				.get_or_insert_with(Default::default)
+				.denied_extensions = extensions;
+		}
+		if let Some(types) = var_list("MODMAIL_ATTACHMENTS_ALLOWED_TYPES")? {
+			self.attachments
+				.get_or_insert_with(Default::default)
+				.allowed_types = Some(types);
+		}
+		if let Some(types) = var_list("MODMAIL_ATTACHMENTS_DENIED_TYPES")? {
+			self.attachments
+				.get_or_insert_with(Default::default)
+				.denied_types = types;
+		}
+		if let Some(max_size_mb) = var("MODMAIL_ATTACHMENTS_MAX_SIZE_MB", "a whole number")? {
+			self.attachments
+				.get_or_insert_with(Default::default)
+				.max_size_mb = Some(max_size_mb);
+		}
+		if let Some(spoiler_images) = var("MODMAIL_ATTACHMENTS_SPOILER_IMAGES", "true or false")? {
+			self.attachments
+				.get_or_insert_with(Default::default)
+				.spoiler_images = spoiler_images;
+		}
+		if let Some(template) = var::<String>("MODMAIL_OPEN_MESSAGE", "text")? {
+			let template = template
+				.parse()
+				.map_err(|err| anyhow!("MODMAIL_OPEN_MESSAGE is invalid: {err}"))?;
+			self.open_message = Some(template);
+		}
+		if let Some(template) = var::<String>("MODMAIL_CLOSE_MESSAGE", "text")? {
+			let template = template
+				.parse()
+				.map_err(|err| anyhow!("MODMAIL_CLOSE_MESSAGE is invalid: {err}"))?;
+			self.close_message = Some(template);
+		}
+		if let Some(path) = var::<PathBuf>("MODMAIL_LOCALES_DIR", "a path")? {
+			// unlike paths in the config file, this is relative to the working directory
+			self.locales_dir = Some(env::current_dir()?.join(path));
+		}
+		if let Some(locale) = var("MODMAIL_DEFAULT_LOCALE", "text")? {
+			self.default_locale = Some(locale);
+		}
+
+		Ok(())
+	}
+}
+
+/// A server from the `servers` list, which works like the top-level fields.
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawServer {
+	guild_id: GuildId,
+	forum_channel_id: ChannelId,
+	#[serde(default)]
+	forum_guild_id: Option<GuildId>,
+	#[serde(default)]
+	ping_roles: Vec<RoleId>,
+}
+
+impl RawServer {
+	fn server(&self) -> Server {
+		Server {
+			guild_id: self.guild_id,
+			forum_channel_id: self.forum_channel_id,
+			forum_guild_id: self.forum_guild_id.unwrap_or(self.guild_id),
+		}
+	}
+}
+
+/// Makes sure that there's at least one server, and that no two share a server or a forum.
+fn validate_servers(servers: &[Server]) -> anyhow::Result<()> {
+	if servers.is_empty() {
+		bail!("servers can't be empty");
+	}
+	for (i, server) in servers.iter().enumerate() {
+		let others = &servers[..i];
+		if others.iter().any(|other| other.guild_id == server.guild_id) {
+			bail!(
+				"guild_id {} is used by more than one server",
+				server.guild_id
+			);
+		}
+		if others
+			.iter()
+			.any(|other| other.forum_channel_id == server.forum_channel_id)
+		{
+			bail!(
+				"forum_channel_id {} is used by more than one server",
+				server.forum_channel_id
+			);
+		}
+	}
+	Ok(())
+}
+
+/// Which storage backend to use, and how to connect to it.
+#[derive(serde::Deserialize, PartialEq)]
+#[serde(tag = "backend", rename_all = "lowercase", deny_unknown_fields)]
+pub enum DatabaseConfig {
+	Sqlite {
+		#[serde(default = "default_sqlite_path")]
+		path: PathBuf,
+	},
+	Postgres {
+		url: String,
+	},
+}
+
+impl Default for DatabaseConfig {
+	fn default() -> Self {
+		Self::Sqlite {
+			path: default_sqlite_path(),
+		}
+	}
+}
+
+fn default_sqlite_path() -> PathBuf {
+	PathBuf::from("db.sqlite")
+}
+
+/// Which reactions are mirrored between users' DMs and their ticket threads.
+#[derive(serde::Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ReactionsConfig {
+	/// Unicode emoji, or the ids of custom emoji. Every emoji is allowed if this isn't set.
+	#[serde(default)]
+	pub allowed: Option<Vec<String>>,
+	/// How many reactions are relayed for each ticket per minute.
+	#[serde(default = "default_reactions_per_minute")]
+	pub per_minute: u32,
+}
+
+impl Default for ReactionsConfig {
+	fn default() -> Self {
+		Self {
+			allowed: None,
+			per_minute: default_reactions_per_minute(),
+		}
+	}
+}
+
+fn default_reactions_per_minute() -> u32 {
+	10
+}
+
+impl ReactionsConfig {
+	/// `emoji` is either a unicode emoji or a custom emoji's id.
+	pub fn allows(&self, emoji: &str) -> bool {
+		self.allowed
+			.as_ref()
+			.is_none_or(|allowed| allowed.iter().any(|allowed| allowed == emoji))
+	}
+}
+
+/// When to close tickets that nobody has replied to.
+#[derive(serde::Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AutoCloseConfig {
+	/// For tickets where staff sent the last message.
+	#[serde(default)]
+	pub waiting_on_user: Option<InactivityRule>,
+	/// For tickets where the user sent the last message.
+	#[serde(default)]
+	pub waiting_on_staff: Option<InactivityRule>,
+}
+
+impl AutoCloseConfig {
+	fn validate(&self) -> anyhow::Result<()> {
+		for rule in [self.waiting_on_user, self.waiting_on_staff]
+			.into_iter()
+			.flatten()
+		{
+			if rule.warn_after_hours == Some(0) || rule.close_after_hours == 0 {
+				bail!("auto_close hours must be at least 1");
+			}
+		}
+		Ok(())
+	}
+}
+
+#[derive(serde::Deserialize, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct InactivityRule {
+	/// Hours without a message before the user is told that the ticket will be closed.
+	#[serde(default)]
+	pub warn_after_hours: Option<u32>,
+	/// Hours after the warning, or after the last message if there's no warning, before the
+	/// ticket is closed.
+	pub close_after_hours: u32,
+}
+
+/// What happens to DMs that a user sends soon after their ticket was closed.
+#[derive(serde::Deserialize, Clone, Copy, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CloseCooldownConfig {
+	/// How long after a ticket is closed that DMs don't reopen it. 0 turns this off.
+	#[serde(default)]
+	pub minutes: u32,
+	#[serde(default)]
+	pub action: CooldownAction,
+}
+
+#[derive(serde::Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CooldownAction {
+	/// Add the DMs to the closed ticket's thread, without reopening it.
+	Append,
+	/// Ask the user whether they want to reopen the ticket.
+	#[default]
+	Prompt,
+}
+
+impl FromStr for CooldownAction {
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"append" => Ok(Self::Append),
+			"prompt" => Ok(Self::Prompt),
+			_ => Err(()),
+		}
+	}
+}
+
+/// How many DMs each user can send before the bot stops relaying them.
+#[derive(serde::Deserialize, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitConfig {
+	/// How many messages a user can send per minute, on average. 0 turns rate limiting off.
+	#[serde(default = "default_rate_limit_per_minute")]
+	pub per_minute: u32,
+	/// How many messages a user can send at once, after not sending any for a while.
+	#[serde(default = "default_rate_limit_burst")]
+	pub burst: u32,
+	/// How many messages in a row can be dropped before the user is blocked.
+	#[serde(default)]
+	pub block_after: Option<u32>,
+}
+
+impl Default for RateLimitConfig {
+	fn default() -> Self {
+		Self {
+			per_minute: default_rate_limit_per_minute(),
+			burst: default_rate_limit_burst(),
+			block_after: None,
+		}
+	}
+}
+
+impl RateLimitConfig {
+	fn validate(&self) -> anyhow::Result<()> {
+		if self.per_minute > 0 && self.burst == 0 {
+			bail!("rate_limit.burst must be at least 1");
+		}
+		if self.block_after == Some(0) {
+			bail!("rate_limit.block_after must be at least 1");
+		}
+		Ok(())
+	}
+}
+
+fn default_rate_limit_per_minute() -> u32 {
+	20
+}
+
+fn default_rate_limit_burst() -> u32 {
+	10
+}
+
+/// A kind of moderation action that can be relayed to the ticket of the user it was taken on.
+#[derive(serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ModAction {
+	Ban,
+	Unban,
+	Kick,
+	/// Timeouts being added, changed, or removed early.
+	Timeout,
+	Roles,
+	Nickname,
+	/// Staff deleting the user's messages.
+	MessageDelete,
+}
+
+impl ModAction {
+	pub const ALL: &[Self] = &[
+		Self::Ban,
+		Self::Unban,
+		Self::Kick,
+		Self::Timeout,
+		Self::Roles,
+		Self::Nickname,
+		Self::MessageDelete,
+	];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Ban => "ban",
+			Self::Unban => "unban",
+			Self::Kick => "kick",
+			Self::Timeout => "timeout",
+			Self::Roles => "roles",
+			Self::Nickname => "nickname",
+			Self::MessageDelete => "message_delete",
+		}
+	}
+}
+
+impl FromStr for ModAction {
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::ALL
+			.iter()
+			.copied()
+			.find(|action| action.name() == s)
+			.ok_or(())
+	}
+}
+
+pub struct Config {
+	pub token: String,
+	/// Every server that tickets can be opened from. The first one is the default.
+	pub servers: Vec<Server>,
+	pub database: DatabaseConfig,
+	live: RwLock<Arc<LiveConfig>>,
+	args: Args,
+}
+
+/// A server whose members can open tickets, and the forum those tickets go to.
+#[derive(Clone, PartialEq)]
+pub struct Server {
+	/// The server that members are in.
+	pub guild_id: GuildId,
+	pub forum_channel_id: ChannelId,
+	/// The server that the forum is in, which is usually the same as `guild_id`.
+	pub forum_guild_id: GuildId,
+}
+
+/// The parts of the config that can be reloaded while the bot is running.
+pub struct LiveConfig {
+	/// Roles to ping in new tickets, by the member server they're for.
+	pub ping_roles: HashMap<GuildId, Vec<RoleId>>,
+	/// Moderation actions to relay to tickets.
+	pub mod_actions: Vec<ModAction>,
+	pub reactions: ReactionsConfig,
+	/// Whether users and staff see each other typing.
+	pub relay_typing: bool,
+	/// Whether users are asked to rate their ticket after it's closed.
+	pub survey: bool,
+	pub auto_close: AutoCloseConfig,
+	pub close_cooldown: CloseCooldownConfig,
+	pub rate_limit: RateLimitConfig,
+	/// Checked against every DM before it's relayed.
+	pub filters: Filters,
+	/// Decides which attachments from users are relayed.
+	pub attachments: AttachmentPolicy,
+	/// Endpoints that are told about ticket events.
+	pub webhooks: Vec<WebhookConfig>,
+	/// Overrides the servers' own locales for users whose locale isn't known.
+	pub default_locale: Option<String>,
+	pub locales: Locales,
+	messages: Messages,
+}
+
+impl LiveConfig {
+	pub fn ping_roles(&self, guild_id: GuildId) -> &[RoleId] {
+		self.ping_roles.get(&guild_id).map_or(&[], Vec::as_slice)
+	}
+
+	/// Messages translated for a locale, if there's a bundle for it.
+	pub fn messages(&self, locale: Option<&str>) -> &Messages {
+		locale
+			.and_then(|locale| self.locales.messages(locale))
+			.unwrap_or(&self.messages)
+	}
+}
+
+impl Config {
+	/// Loads the config from the config file, environment variables, and command-line flags.
+	///
+	/// See `modmail --help` for the order these are applied in.
+	pub fn load(args: &Args) -> anyhow::Result<Self> {
+		let mut raw_config = RawConfig::read(args)?;
+		raw_config.apply_env()?;
+
+		// the top-level fields are the first server, for configs from before `servers` existed
+		let mut servers = Vec::new();
+		let mut ping_roles = HashMap::new();
+		match (raw_config.guild_id, raw_config.forum_channel_id) {
+			(Some(guild_id), Some(_)) => {
+				servers.extend(raw_config.top_level_server());
+				ping_roles.insert(guild_id, raw_config.ping_roles.unwrap_or_default());
+			},
+			(None, None) if raw_config.servers.is_some() => (),
+			(None, _) => bail!(
+				"missing guild_id (set it in the config file or with MODMAIL_GUILD_ID, or use servers)"
+			),
+			(_, None) => bail!(
+				"missing forum_channel_id (set it in the config file or with MODMAIL_FORUM_CHANNEL_ID, or use servers)"
+			),
+		}
+		for raw_server in raw_config.servers.unwrap_or_default() {
+			let server = raw_server.server();
+			ping_roles.insert(server.guild_id, raw_server.ping_roles);
+			servers.push(server);
+		}
+		validate_servers(&servers)?;
+
+		// flags are relative to the working directory, the config file is relative to the data dir
+		let database = match &args.database {
+			Some(url) if url.starts_with("postgres://") || url.starts_with("postgresql://") => {
+				DatabaseConfig::Postgres { url: url.clone() }
+			},
+			Some(path) => DatabaseConfig::Sqlite {
+				path: PathBuf::from(path),
+			},
+			None => match raw_config.database.unwrap_or_default() {
+				DatabaseConfig::Sqlite { path } => DatabaseConfig::Sqlite {
+					path: args.data_path(&path),
+				},
+				postgres => postgres,
+			},
+		};
+
+		let mut messages = Messages::default().merged(raw_config.messages.unwrap_or_default());
+		if let Some(template) = raw_config.open_message {
+			messages.open_message = Some(template);
+		}
+		if let Some(template) = raw_config.close_message {
+			messages.close_message = Some(template);
+		}
+
+		let locales_dir = args.data_path(
+			raw_config
+				.locales_dir
+				.as_deref()
+				.unwrap_or(Path::new("locales")),
+		);
+		let locales = Locales::load(&locales_dir, &messages)?;
+
+		if let Some(locale) = &raw_config.default_locale {
+			if !LOCALES.contains(&locale.as_str()) {
+				bail!(
+					"default_locale must be a discord locale, one of: {}",
+					LOCALES.join(", ")
+				);
+			}
+		}
+
+		let auto_close = raw_config.auto_close.unwrap_or_default();
+		auto_close.validate()?;
+
+		let rate_limit = raw_config.rate_limit.unwrap_or_default();
+		rate_limit.validate()?;
+
+		let mut attachments = raw_config.attachments.unwrap_or_default();
+		if let Some(ScannerConfig::Clamd {
+			socket: Some(socket),
+			..
+		}) = &mut attachments.scanner
+		{
+			*socket = args.data_path(socket);
+		}
+
+		let webhooks = raw_config.webhooks.unwrap_or_default();
+		webhooks::validate(&webhooks)?;
+
+		let live = LiveConfig {
+			ping_roles,
+			mod_actions: raw_config
+				.mod_actions
+				.unwrap_or_else(|| ModAction::ALL.to_vec()),
+			reactions: raw_config.reactions.unwrap_or_default(),
+			relay_typing: raw_config.relay_typing.unwrap_or(true),
+			survey: raw_config.survey.unwrap_or(false),
+			auto_close,
+			close_cooldown: raw_config.close_cooldown.unwrap_or_default(),
+			rate_limit,
+			filters: Filters::compile(raw_config.filters.unwrap_or_default())?,
+			attachments: AttachmentPolicy::new(attachments)?,
+			webhooks,
+			default_locale: raw_config.default_locale,
+			locales,
+			messages,
+		};
+
+		Ok(Self {
+			token: raw_config
+				.token
+				.context("missing token (set it in the config file or with MODMAIL_TOKEN)")?,
+			servers,
+			database,
+			live: RwLock::new(Arc::new(live)),
+			args: args.clone(),
+		})
+	}
+
+	/// The server that was listed first, for things that don't belong to any server.
+	pub fn default_server(&self) -> &Server {
+		&self.servers[0]
+	}
+
+	/// Finds a server by the id of the server that its members are in.
+	pub fn server(&self, guild_id: GuildId) -> Option<&Server> {
+		self.servers
+			.iter()
+			.find(|server| server.guild_id == guild_id)
+	}
+
+	/// Finds the server that a ticket belongs to.
+	///
+	/// Tickets from before there could be more than one server, or from a server that was removed
+	/// from the config, belong to the default server.
+	pub fn ticket_server(&self, guild_id: Option<GuildId>) -> &Server {
+		guild_id
+			.and_then(|guild_id| self.server(guild_id))
+			.unwrap_or_else(|| self.default_server())
+	}
+
+	/// Finds a server by its modmail forum.
+	pub fn server_by_forum(&self, forum_channel_id: ChannelId) -> Option<&Server> {
+		self.servers
+			.iter()
+			.find(|server| server.forum_channel_id == forum_channel_id)
+	}
+
+	/// The current version of the reloadable parts of the config.
+	///
+	/// Don't hold onto this for long, or you might miss a reload.
+	pub fn live(&self) -> Arc<LiveConfig> {
+		Arc::clone(&self.live.read().unwrap_or_else(PoisonError::into_inner))
+	}
+
+	/// Loads the config again and swaps in the reloadable parts.
+	///
+	/// Nothing changes if the new config is invalid. Returns the fields that changed but can't be
+	/// applied until the bot restarts.
+	pub fn reload(&self) -> anyhow::Result<Vec<&'static str>> {
+		let new_config = Self::load(&self.args)?;
+
+		let mut needs_restart = Vec::new();
+		if new_config.token != self.token {
+			needs_restart.push("token");
+		}
+		if new_config.servers != self.servers {
+			needs_restart.push("servers");
+		}
+		if new_config.database != self.database {
+			needs_restart.push("database");
+		}
+		if !new_config
+			.live()
+			.locales
+			.same_commands(&self.live().locales)
+		{
+			needs_restart.push("command translations");
+		}
+
+		let new_live = new_config
+			.live
+			.into_inner()
+			.unwrap_or_else(PoisonError::into_inner);
+		*self.live.write().unwrap_or_else(PoisonError::into_inner) = new_live;
+
+		Ok(needs_restart)
+	}
+}
@@ -0,0 +1,439 @@
+use crate::util::formatting;
+use serde::de::{self, value::MapAccessDeserializer, Deserializer, MapAccess, Visitor};
+use std::{fmt, str::FromStr};
+use twilight::{
+	id::{ChannelId, GuildId, UserId},
+	model::channel::message::Embed,
+	util::builder::embed::{EmbedBuilder, EmbedFooterBuilder, ImageSource},
+	validate::{embed, message::MESSAGE_CONTENT_LENGTH_MAX},
+};
+
+/// A message that the bot sends, with `{variable}` placeholders.
+///
+/// In the config file, this is either text for the message content, or a map with `content`
+/// and/or `embed`.
+#[derive(Clone)]
+pub struct Template {
+	content: Option<Text>,
+	embed: Option<EmbedTemplate>,
+}
+
+#[derive(serde::Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+struct EmbedTemplate {
+	#[serde(default)]
+	title: Option<Text>,
+	#[serde(default)]
+	description: Option<Text>,
+	#[serde(default)]
+	color: Option<u32>,
+	#[serde(default)]
+	footer: Option<Text>,
+	#[serde(default)]
+	image: Option<Text>,
+	#[serde(default)]
+	thumbnail: Option<Text>,
+}
+
+/// Text with placeholders, parsed ahead of time so mistakes are caught when the config loads.
+#[derive(Clone)]
+struct Text(Vec<Segment>);
+
+#[derive(Clone)]
+enum Segment {
+	Literal(String),
+	Var(Var),
+}
+
+#[derive(Clone, Copy)]
+enum Var {
+	UserName,
+	UserId,
+	UserMention,
+	ServerName,
+	ServerId,
+	TicketId,
+	StaffName,
+	StaffId,
+	StaffMention,
+	Message,
+	Link,
+	Attachments,
+	Reason,
+	Expires,
+	Rating,
+	Hours,
+}
+
+const VARS: &[(&str, Var)] = &[
+	("user.name", Var::UserName),
+	("user.id", Var::UserId),
+	("user.mention", Var::UserMention),
+	("server.name", Var::ServerName),
+	("server.id", Var::ServerId),
+	("ticket.id", Var::TicketId),
+	("staff.name", Var::StaffName),
+	("staff.id", Var::StaffId),
+	("staff.mention", Var::StaffMention),
+	("message", Var::Message),
+	("link", Var::Link),
+	("attachments", Var::Attachments),
+	("reason", Var::Reason),
+	("expires", Var::Expires),
+	("rating", Var::Rating),
+	("hours", Var::Hours),
+];
+
+/// Values for a template's placeholders.
+///
+/// Placeholders without a value are left empty.
+#[derive(Default)]
+pub struct Vars {
+	pub user_id: Option<UserId>,
+	pub user_name: Option<String>,
+	pub server_id: Option<GuildId>,
+	pub server_name: Option<String>,
+	/// The ticket's thread.
+	pub ticket_id: Option<ChannelId>,
+	pub staff_id: Option<UserId>,
+	pub staff_name: Option<String>,
+	pub message: Option<String>,
+	pub link: Option<String>,
+	pub attachments: Option<String>,
+	/// Why staff took a moderation action or closed a ticket.
+	pub reason: Option<String>,
+	/// When a timeout ends, as a unix timestamp.
+	pub expires: Option<i64>,
+	/// How the user rated their ticket, from 1 to 5.
+	pub rating: Option<i32>,
+	/// How long a ticket went without a reply.
+	pub hours: Option<u32>,
+}
+
+/// A template with its placeholders filled in, ready to send.
+pub struct Rendered {
+	/// This is empty if the template only has an embed.
+	pub content: String,
+	pub embeds: Vec<Embed>,
+}
+
+impl Template {
+	pub fn render(&self, vars: &Vars) -> Rendered {
+		let content = match &self.content {
+			Some(text) => text.render(vars, MESSAGE_CONTENT_LENGTH_MAX),
+			None => String::new(),
+		};
+
+		let mut embeds = Vec::new();
+		if let Some(template) = &self.embed {
+			let mut embed = EmbedBuilder::new();
+			if let Some(title) = &template.title {
+				embed = embed.title(title.render(vars, embed::TITLE_LENGTH));
+			}
+			if let Some(description) = &template.description {
+				embed = embed.description(description.render(vars, embed::DESCRIPTION_LENGTH));
+			}
+			if let Some(color) = template.color {
+				embed = embed.color(color);
+			}
+			if let Some(footer) = &template.footer {
+				embed = embed.footer(
+					EmbedFooterBuilder::new(footer.render(vars, embed::FOOTER_TEXT_LENGTH)).build(),
+				);
+			}
+			// a placeholder might not be a valid url, so skip it instead of failing to send
+			if let Some(image) = &template.image {
+				if let Ok(source) = ImageSource::url(image.render(vars, usize::MAX)) {
+					embed = embed.image(source);
+				}
+			}
+			if let Some(thumbnail) = &template.thumbnail {
+				if let Ok(source) = ImageSource::url(thumbnail.render(vars, usize::MAX)) {
+					embed = embed.thumbnail(source);
+				}
+			}
+			embeds.push(embed.build());
+		}
+
+		Rendered { content, embeds }
+	}
+
+	/// Renders just the text, for places that can't have an embed, like button labels.
+	///
+	/// Templates that only have an embed use its title, or its description.
+	pub fn render_text(&self, vars: &Vars, max_chars: usize) -> String {
+		let text = self
+			.content
+			.as_ref()
+			.filter(|text| !text.0.is_empty())
+			.or_else(|| {
+				let embed = self.embed.as_ref()?;
+				embed.title.as_ref().or(embed.description.as_ref())
+			});
+		match text {
+			Some(text) => text.render(vars, max_chars),
+			None => String::new(),
+		}
+	}
+
+	/// Makes sure that the template can't produce an empty message.
+	fn validate(self) -> Result<Self, String> {
+		let has_content = self.content.as_ref().is_some_and(|text| !text.0.is_empty());
+		if !has_content && self.embed.is_none() {
+			return Err("messages can't be empty".to_owned());
+		}
+		if let Some(text) = &self.content {
+			let literal_len: usize = text
+				.0
+				.iter()
+				.map(|segment| match segment {
+					Segment::Literal(s) => s.chars().count(),
+					Segment::Var(_) => 0,
+				})
+				.sum();
+			if literal_len > MESSAGE_CONTENT_LENGTH_MAX {
+				return Err(format!(
+					"message content must be at most {MESSAGE_CONTENT_LENGTH_MAX} characters long"
+				));
+			}
+		}
+		if let Some(EmbedTemplate {
+			color: Some(color), ..
+		}) = &self.embed
+		{
+			if *color > embed::COLOR_MAXIMUM {
+				return Err(format!(
+					"embed color must be at most {:#x}",
+					embed::COLOR_MAXIMUM
+				));
+			}
+		}
+		Ok(self)
+	}
+}
+
+impl FromStr for Template {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self {
+			content: Some(s.parse()?),
+			embed: None,
+		}
+		.validate()
+	}
+}
+
+impl<'de> serde::Deserialize<'de> for Template {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct TemplateVisitor;
+
+		impl<'de> Visitor<'de> for TemplateVisitor {
+			type Value = Template;
+
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("message text, or a map with `content` and/or `embed`")
+			}
+
+			fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+				s.parse().map_err(E::custom)
+			}
+
+			fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+				#[derive(serde::Deserialize)]
+				#[serde(deny_unknown_fields)]
+				struct Full {
+					#[serde(default)]
+					content: Option<Text>,
+					#[serde(default)]
+					embed: Option<EmbedTemplate>,
+				}
+
+				let full: Full = serde::Deserialize::deserialize(MapAccessDeserializer::new(map))?;
+				Template {
+					content: full.content,
+					embed: full.embed,
+				}
+				.validate()
+				.map_err(de::Error::custom)
+			}
+		}
+
+		deserializer.deserialize_any(TemplateVisitor)
+	}
+}
+
+impl Text {
+	fn render(&self, vars: &Vars, max_chars: usize) -> String {
+		fn opt<T: ToString>(value: &Option<T>) -> String {
+			value.as_ref().map(T::to_string).unwrap_or_default()
+		}
+
+		let mut rendered = String::new();
+		for segment in &self.0 {
+			match segment {
+				Segment::Literal(s) => rendered.push_str(s),
+				Segment::Var(var) => rendered.push_str(&match var {
+					Var::UserName => opt(&vars.user_name),
+					Var::UserId => opt(&vars.user_id),
+					Var::UserMention => vars
+						.user_id
+						.map(|id| format!("<@{id}>"))
+						.unwrap_or_default(),
+					Var::ServerName => opt(&vars.server_name),
+					Var::ServerId => opt(&vars.server_id),
+					Var::TicketId => opt(&vars.ticket_id),
+					Var::StaffName => opt(&vars.staff_name),
+					Var::StaffId => opt(&vars.staff_id),
+					Var::StaffMention => vars
+						.staff_id
+						.map(|id| format!("<@{id}>"))
+						.unwrap_or_default(),
+					Var::Message => opt(&vars.message),
+					Var::Link => opt(&vars.link),
+					Var::Attachments => opt(&vars.attachments),
+					Var::Reason => opt(&vars.reason),
+					Var::Expires => vars
+						.expires
+						.map(|timestamp| format!("<t:{timestamp}:f>"))
+						.unwrap_or_default(),
+					Var::Rating => opt(&vars.rating),
+					Var::Hours => opt(&vars.hours),
+				}),
+			}
+		}
+		formatting::truncate(&rendered, max_chars)
+	}
+}
+
+impl FromStr for Text {
+	type Err = String;
+
+	/// Parses `{variable}` placeholders. `{{` and `}}` are literal braces.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut segments = Vec::new();
+		let mut literal = String::new();
+		let mut chars = s.chars().peekable();
+
+		while let Some(c) = chars.next() {
+			match c {
+				'{' if chars.peek() == Some(&'{') => {
+					chars.next();
+					literal.push('{');
+				},
+				'}' if chars.peek() == Some(&'}') => {
+					chars.next();
+					literal.push('}');
+				},
+				'{' => {
+					let mut name = String::new();
+					loop {
+						match chars.next() {
+							Some('}') => break,
+							Some(c) => name.push(c),
+							None => return Err(format!("unclosed `{{{name}`")),
+						}
+					}
+					let name = name.trim();
+					let Some(&(_, var)) = VARS.iter().find(|(var_name, _)| *var_name == name)
+					else {
+						let names: Vec<_> = VARS.iter().map(|(name, _)| *name).collect();
+						return Err(format!(
+							"unknown variable `{{{name}}}`, expected one of `{{{}}}`",
+							names.join("}`, `{")
+						));
+					};
+					if !literal.is_empty() {
+						segments.push(Segment::Literal(std::mem::take(&mut literal)));
+					}
+					segments.push(Segment::Var(var));
+				},
+				'}' => return Err("unmatched `}`, use `}}` for a literal brace".to_owned()),
+				c => literal.push(c),
+			}
+		}
+		if !literal.is_empty() {
+			segments.push(Segment::Literal(literal));
+		}
+
+		Ok(Self(segments))
+	}
+}
+
+impl<'de> serde::Deserialize<'de> for Text {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+		s.parse().map_err(de::Error::custom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use twilight::id::Id;
+
+	fn template(yaml: &str) -> Template {
+		serde_yaml::from_str(yaml).unwrap()
+	}
+
+	#[test]
+	fn double_braces_are_literal() {
+		let rendered = template("'{{user.id}} is {user.id}}}'").render(&Vars {
+			user_id: Some(Id::new(1)),
+			..Vars::default()
+		});
+		assert_eq!(rendered.content, "{user.id} is 1}");
+	}
+
+	#[test]
+	fn unknown_variables_are_rejected() {
+		let err = "hi {user.nick}".parse::<Template>().err().unwrap();
+		assert!(err.starts_with("unknown variable `{user.nick}`"), "{err}");
+		assert!(err.contains("`{user.name}`"), "{err}");
+
+		assert!("hi {user.name".parse::<Template>().is_err());
+		assert!("hi }".parse::<Template>().is_err());
+	}
+
+	#[test]
+	fn missing_variables_are_left_empty() {
+		let rendered = template("'{staff.mention} closed it: {reason}'").render(&Vars::default());
+		assert_eq!(rendered.content, " closed it: ");
+	}
+
+	#[test]
+	fn embeds_are_rendered() {
+		let rendered = template(indoc! {"
+			embed:
+			  title: Ticket {ticket.id}
+			  description: From {user.mention}
+			  footer: '{server.name}'
+			  color: 0xff0000
+			  image: '{link}'
+		"})
+		.render(&Vars {
+			user_id: Some(Id::new(1)),
+			server_name: Some("Server".to_owned()),
+			ticket_id: Some(Id::new(2)),
+			link: Some("not a url".to_owned()),
+			..Vars::default()
+		});
+
+		assert!(rendered.content.is_empty());
+		let [embed] = rendered.embeds.as_slice() else {
+			panic!("expected one embed");
+		};
+		assert_eq!(embed.title.as_deref(), Some("Ticket 2"));
+		assert_eq!(embed.description.as_deref(), Some("From <@1>"));
+		assert_eq!(embed.footer.as_ref().unwrap().text, "Server");
+		assert_eq!(embed.color, Some(0xff0000));
+		// placeholders that aren't urls are skipped instead of failing to send
+		assert!(embed.image.is_none());
+	}
+
+	#[test]
+	fn empty_templates_and_invalid_colors_are_rejected() {
+		assert!(serde_yaml::from_str::<Template>("''").is_err());
+		assert!(serde_yaml::from_str::<Template>("embed:\n  color: 0x1000000").is_err());
+	}
+}
@@ -0,0 +1,87 @@
+use std::str::FromStr;
+
+/// An HTTP endpoint that's told about ticket events.
+#[derive(serde::Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+	pub url: String,
+	/// Signs each payload, so the endpoint can tell that it came from the bot.
+	pub secret: String,
+	/// The events to send. Every event is sent if this isn't set.
+	#[serde(default)]
+	pub events: Option<Vec<WebhookEvent>>,
+}
+
+impl WebhookConfig {
+	pub fn wants(&self, event: WebhookEvent) -> bool {
+		self.events
+			.as_ref()
+			.is_none_or(|events| events.contains(&event))
+	}
+}
+
+/// Something that happened to a ticket, which webhooks can be sent for.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+	Open,
+	Close,
+	Block,
+	Unblock,
+	/// A message from the user, or a reply from staff.
+	Message,
+}
+
+impl WebhookEvent {
+	const ALL: &[Self] = &[
+		Self::Open,
+		Self::Close,
+		Self::Block,
+		Self::Unblock,
+		Self::Message,
+	];
+
+	/// How this is stored in the database, and sent in payloads.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Open => "open",
+			Self::Close => "close",
+			Self::Block => "block",
+			Self::Unblock => "unblock",
+			Self::Message => "message",
+		}
+	}
+}
+
+impl FromStr for WebhookEvent {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::ALL
+			.iter()
+			.copied()
+			.find(|event| event.name() == s)
+			.ok_or_else(|| format!("unknown webhook event `{s}`"))
+	}
+}
+
+/// Makes sure that every webhook can be sent.
+pub fn validate(webhooks: &[WebhookConfig]) -> anyhow::Result<()> {
+	for (i, webhook) in webhooks.iter().enumerate() {
+		let uri: hyper::Uri = webhook
+			.url
+			.parse()
+			.map_err(|err| anyhow!("webhooks[{i}].url is invalid: {err}"))?;
+		if !matches!(uri.scheme_str(), Some("http" | "https")) || uri.host().is_none() {
+			bail!("webhooks[{i}].url must be an http or https url");
+		}
+		if webhook.secret.is_empty() {
+			bail!("webhooks[{i}].secret can't be empty");
+		}
+		// deliveries are matched back to their webhook by url
+		if webhooks[..i].iter().any(|other| other.url == webhook.url) {
+			bail!("webhooks[{i}].url is used by more than one webhook");
+		}
+	}
+	Ok(())
+}
@@ -0,0 +1,247 @@
+use super::Ticket;
+use dashmap::DashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use twilight::id::{ChannelId, UserId};
+
+/// Hit/miss counts for the [`TicketCache`].
+#[derive(Clone, Copy, Default)]
+pub struct CacheStats {
+	pub hits: u64,
+	pub misses: u64,
+}
+
+impl CacheStats {
+	pub fn hit_rate(&self) -> f64 {
+		match self.hits + self.misses {
+			0 => 0.0,
+			total => self.hits as f64 / total as f64,
+		}
+	}
+}
+
+/// An in-memory cache of tickets, kept in front of the database.
+///
+/// Lookups that miss the cache are filled from the database. Every write to the database must call
+/// [`TicketCache::invalidate`] afterwards.
+///
+/// Each invalidation bumps a generation counter. A miss only fills the cache if no invalidation
+/// happened while it was querying the database, so a slow read can't overwrite a newer write
+/// with stale data.
+///
+/// Channels that are known to have no ticket are cached too, since every DM, typing event, and
+/// reaction looks one up. Any write forgets all of them, because a new or moved ticket could be
+/// using one.
+#[derive(Default)]
+pub struct TicketCache {
+	/// `None` means the user is known to have no ticket.
+	tickets: DashMap<UserId, Option<Ticket>, ahash::RandomState>,
+	/// `None` means no ticket uses the channel.
+	dm_channels: DashMap<ChannelId, Option<UserId>, ahash::RandomState>,
+	/// `None` means no ticket uses the thread.
+	threads: DashMap<ChannelId, Option<UserId>, ahash::RandomState>,
+	generation: AtomicU64,
+	hits: AtomicU64,
+	misses: AtomicU64,
+}
+
+impl TicketCache {
+	pub fn stats(&self) -> CacheStats {
+		CacheStats {
+			hits: self.hits.load(Ordering::Relaxed),
+			misses: self.misses.load(Ordering::Relaxed),
+		}
+	}
+
+	/// Call this before querying the database on a miss, and pass the result to
+	/// [`TicketCache::fill`].
+	pub fn generation(&self) -> u64 {
+		self.generation.load(Ordering::Acquire)
+	}
+
+	pub fn by_user(&self, user_id: UserId) -> Option<Option<Ticket>> {
+		let cached = self.tickets.get(&user_id).map(|entry| entry.clone());
+		self.record(cached.is_some());
+		cached
+	}
+
+	pub fn by_dm_channel(&self, dm_channel_id: ChannelId) -> Option<Option<Ticket>> {
+		let cached = Self::by_channel(&self.dm_channels, &self.tickets, dm_channel_id, |ticket| {
+			ticket.dm_channel_id
+		});
+		self.record(cached.is_some());
+		cached
+	}
+
+	pub fn by_thread(&self, thread_id: ChannelId) -> Option<Option<Ticket>> {
+		let cached = Self::by_channel(&self.threads, &self.tickets, thread_id, |ticket| {
+			ticket.thread_id
+		});
+		self.record(cached.is_some());
+		cached
+	}
+
+	fn by_channel(
+		channels: &DashMap<ChannelId, Option<UserId>, ahash::RandomState>,
+		tickets: &DashMap<UserId, Option<Ticket>, ahash::RandomState>,
+		channel_id: ChannelId,
+		ticket_channel: fn(&Ticket) -> ChannelId,
+	) -> Option<Option<Ticket>> {
+		// copy the id out so we don't hold locks on both maps at once
+		match channels.get(&channel_id).map(|user_id| *user_id)? {
+			Some(user_id) => tickets
+				.get(&user_id)?
+				.clone()
+				.filter(|ticket| ticket_channel(ticket) == channel_id)
+				.map(Some),
+			None => Some(None),
+		}
+	}
+
+	/// Stores the result of a database query for a user's ticket.
+	pub fn fill(&self, generation: u64, user_id: UserId, ticket: Option<&Ticket>) {
+		// holding the entry locks out `invalidate` for this user until we're done
+		let entry = self.tickets.entry(user_id);
+		if self.generation.load(Ordering::Acquire) != generation {
+			return;
+		}
+		if let Some(ticket) = ticket {
+			self.dm_channels.insert(ticket.dm_channel_id, Some(user_id));
+			self.threads.insert(ticket.thread_id, Some(user_id));
+		}
+		*entry.or_default() = ticket.cloned();
+	}
+
+	/// Remembers that a database query found no ticket for a DM channel.
+	pub fn fill_missing_dm_channel(&self, generation: u64, dm_channel_id: ChannelId) {
+		self.fill_missing(&self.dm_channels, generation, dm_channel_id);
+	}
+
+	/// Remembers that a database query found no ticket for a thread.
+	pub fn fill_missing_thread(&self, generation: u64, thread_id: ChannelId) {
+		self.fill_missing(&self.threads, generation, thread_id);
+	}
+
+	fn fill_missing(
+		&self,
+		channels: &DashMap<ChannelId, Option<UserId>, ahash::RandomState>,
+		generation: u64,
+		channel_id: ChannelId,
+	) {
+		// holding the entry locks out `invalidate` from forgetting it until we're done
+		let entry = channels.entry(channel_id);
+		if self.generation.load(Ordering::Acquire) != generation {
+			return;
+		}
+		entry.or_insert(None);
+	}
+
+	pub fn invalidate(&self, user_id: UserId) {
+		self.generation.fetch_add(1, Ordering::AcqRel);
+		if let Some((_, Some(ticket))) = self.tickets.remove(&user_id) {
+			self.dm_channels.remove(&ticket.dm_channel_id);
+			self.threads.remove(&ticket.thread_id);
+		}
+		self.dm_channels.retain(|_, user_id| user_id.is_some());
+		self.threads.retain(|_, user_id| user_id.is_some());
+	}
+
+	fn record(&self, hit: bool) {
+		if hit {
+			self.hits.fetch_add(1, Ordering::Relaxed);
+		} else {
+			self.misses.fetch_add(1, Ordering::Relaxed);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use twilight::id::Id;
+
+	fn ticket(user: u64, dm_channel: u64, thread: u64) -> Ticket {
+		Ticket {
+			user_id: Id::new(user),
+			guild_id: None,
+			dm_channel_id: Id::new(dm_channel),
+			thread_id: Id::new(thread),
+			is_open: true,
+			blocked: false,
+		}
+	}
+
+	#[test]
+	fn fill_after_invalidate_is_ignored() {
+		let cache = TicketCache::default();
+		let ticket = ticket(1, 2, 3);
+
+		let generation = cache.generation();
+		cache.invalidate(ticket.user_id);
+		cache.fill(generation, ticket.user_id, Some(&ticket));
+
+		assert!(cache.by_user(ticket.user_id).is_none());
+		assert!(cache.by_dm_channel(ticket.dm_channel_id).is_none());
+		assert!(cache.by_thread(ticket.thread_id).is_none());
+	}
+
+	#[test]
+	fn fill_serves_every_lookup() {
+		let cache = TicketCache::default();
+		let ticket = ticket(1, 2, 3);
+
+		cache.fill(cache.generation(), ticket.user_id, Some(&ticket));
+
+		let cached = cache.by_thread(ticket.thread_id).unwrap().unwrap();
+		assert_eq!(cached.user_id, ticket.user_id);
+		let cached = cache.by_dm_channel(ticket.dm_channel_id).unwrap().unwrap();
+		assert_eq!(cached.user_id, ticket.user_id);
+		assert_eq!(cache.stats().hits, 2);
+	}
+
+	#[test]
+	fn missing_channels_are_cached_until_a_write() {
+		let cache = TicketCache::default();
+		let channel_id = Id::new(2);
+
+		assert!(cache.by_dm_channel(channel_id).is_none());
+		cache.fill_missing_dm_channel(cache.generation(), channel_id);
+		cache.fill_missing_thread(cache.generation(), channel_id);
+		assert!(matches!(cache.by_dm_channel(channel_id), Some(None)));
+		assert!(matches!(cache.by_thread(channel_id), Some(None)));
+
+		// the write could have been a ticket that uses the channel
+		cache.invalidate(Id::new(1));
+		assert!(cache.by_dm_channel(channel_id).is_none());
+		assert!(cache.by_thread(channel_id).is_none());
+	}
+
+	#[test]
+	fn missing_fill_after_invalidate_is_ignored() {
+		let cache = TicketCache::default();
+		let channel_id = Id::new(2);
+
+		let generation = cache.generation();
+		cache.invalidate(Id::new(1));
+		cache.fill_missing_dm_channel(generation, channel_id);
+		cache.fill_missing_thread(generation, channel_id);
+
+		assert!(cache.by_dm_channel(channel_id).is_none());
+		assert!(cache.by_thread(channel_id).is_none());
+	}
+
+	#[test]
+	fn stale_tickets_are_forgotten() {
+		let cache = TicketCache::default();
+		let old = ticket(1, 2, 3);
+		cache.fill(cache.generation(), old.user_id, Some(&old));
+
+		// the ticket moved to a new thread
+		cache.invalidate(old.user_id);
+		let new = ticket(1, 2, 4);
+		cache.fill(cache.generation(), new.user_id, Some(&new));
+
+		assert!(cache.by_thread(old.thread_id).is_none());
+		let cached = cache.by_thread(new.thread_id).unwrap().unwrap();
+		assert_eq!(cached.thread_id, new.thread_id);
+	}
+}
@@ -0,0 +1,685 @@
+use crate::config::{DatabaseConfig, FilterAction, WebhookEvent};
+use std::str::FromStr;
+use twilight::id::{ChannelId, GuildId, MessageId, UserId};
+
+mod cache;
+pub use cache::CacheStats;
+use cache::TicketCache;
+
+mod storage;
+pub use storage::Storage;
+
+mod postgres;
+mod sqlite;
+
+#[cfg(test)]
+pub mod testing;
+
+pub struct Database {
+	storage: Box<dyn Storage>,
+	cache: TicketCache,
+}
+
+#[derive(Clone)]
+pub struct Ticket {
+	pub user_id: UserId,
+	/// The server the ticket was opened from.
+	///
+	/// This is [`None`] for tickets opened before there could be more than one server.
+	pub guild_id: Option<GuildId>,
+	pub dm_channel_id: ChannelId,
+	pub thread_id: ChannelId,
+	pub is_open: bool,
+	pub blocked: bool,
+}
+
+/// A message relayed between a user's DMs and their ticket thread.
+#[derive(Clone)]
+pub struct RelayedMessage {
+	pub user_id: UserId,
+	pub dm_msg_id: MessageId,
+	pub thread_msg_id: MessageId,
+	pub thread_update_msg_id: Option<MessageId>,
+	/// The user for messages from the user, or the staff member for replies.
+	///
+	/// This is [`None`] for messages relayed before content was stored.
+	pub author_id: Option<UserId>,
+	/// The current content, as shown in the thread.
+	///
+	/// This is [`None`] for messages relayed before content was stored.
+	pub content: Option<String>,
+	pub created_at: Option<i64>,
+	pub edited_at: Option<i64>,
+	pub deleted_at: Option<i64>,
+}
+
+impl RelayedMessage {
+	/// The thread message that shows the latest version of this message.
+	pub fn latest_thread_msg_id(&self) -> MessageId {
+		self.thread_update_msg_id.unwrap_or(self.thread_msg_id)
+	}
+
+	pub fn is_from_staff(&self) -> bool {
+		self.author_id
+			.is_some_and(|author_id| author_id != self.user_id)
+	}
+}
+
+/// A previous version of a [`RelayedMessage`].
+#[derive(Clone)]
+pub struct MessageRevision {
+	/// The thread message that showed this version.
+	pub thread_msg_id: MessageId,
+	pub content: String,
+	pub created_at: i64,
+}
+
+/// A moderation action taken on a user, kept even after their ticket is gone.
+#[derive(Clone)]
+pub struct Moderation {
+	pub user_id: UserId,
+	pub guild_id: GuildId,
+	pub staff_id: UserId,
+	pub kind: ModerationKind,
+	pub reason: Option<String>,
+	/// When a timeout ends.
+	pub expires_at: Option<i64>,
+	pub created_at: i64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ModerationKind {
+	Warn,
+	Timeout,
+	Kick,
+	Ban,
+}
+
+impl ModerationKind {
+	const ALL: &[Self] = &[Self::Warn, Self::Timeout, Self::Kick, Self::Ban];
+
+	/// How this is stored in the database.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Warn => "warn",
+			Self::Timeout => "timeout",
+			Self::Kick => "kick",
+			Self::Ban => "ban",
+		}
+	}
+
+	pub fn emoji(self) -> &'static str {
+		match self {
+			Self::Warn => "⚠️",
+			Self::Timeout => "🔇",
+			Self::Kick => "👢",
+			Self::Ban => "🔨",
+		}
+	}
+
+	/// Fits in "User was ___".
+	pub fn past_tense(self) -> &'static str {
+		match self {
+			Self::Warn => "warned",
+			Self::Timeout => "timed out",
+			Self::Kick => "kicked",
+			Self::Ban => "banned",
+		}
+	}
+}
+
+impl FromStr for ModerationKind {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::ALL
+			.iter()
+			.copied()
+			.find(|kind| kind.name() == s)
+			.ok_or_else(|| format!("unknown moderation kind `{s}`"))
+	}
+}
+
+/// Something that happened to a user's ticket, kept even after the ticket is gone.
+#[derive(Clone)]
+pub struct TicketEvent {
+	pub user_id: UserId,
+	pub kind: TicketEventKind,
+	/// Who did it, if it was done by someone in particular.
+	pub staff_id: Option<UserId>,
+	pub reason: Option<String>,
+	pub created_at: i64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TicketEventKind {
+	Open,
+	Close,
+	Block,
+	Unblock,
+}
+
+impl TicketEventKind {
+	const ALL: &[Self] = &[Self::Open, Self::Close, Self::Block, Self::Unblock];
+
+	/// How this is stored in the database.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Open => "open",
+			Self::Close => "close",
+			Self::Block => "block",
+			Self::Unblock => "unblock",
+		}
+	}
+}
+
+impl FromStr for TicketEventKind {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::ALL
+			.iter()
+			.copied()
+			.find(|kind| kind.name() == s)
+			.ok_or_else(|| format!("unknown ticket event kind `{s}`"))
+	}
+}
+
+/// Something staff wrote down about a user, which the user never sees.
+#[derive(Clone)]
+pub struct StaffNote {
+	pub user_id: UserId,
+	pub staff_id: UserId,
+	pub content: String,
+	pub created_at: i64,
+}
+
+/// How long an open ticket has gone without a message, for closing it automatically.
+#[derive(Clone)]
+pub struct InactivityTimer {
+	pub user_id: UserId,
+	pub last_message_at: i64,
+	/// Whether the ticket is waiting on the user rather than on staff.
+	pub staff_spoke_last: bool,
+	/// When the user was told that the ticket would be closed, if they have been since the last
+	/// message.
+	pub warned_at: Option<i64>,
+}
+
+/// A DM that matched a content filter.
+#[derive(Clone)]
+pub struct FilterHit {
+	pub user_id: UserId,
+	pub dm_msg_id: MessageId,
+	/// The name of the filter.
+	pub filter: String,
+	pub action: FilterAction,
+	/// The part of the message that matched.
+	pub matched: String,
+	pub content: String,
+	pub created_at: i64,
+}
+
+/// A webhook payload waiting to be delivered.
+#[derive(Clone)]
+pub struct WebhookDelivery {
+	pub id: i64,
+	pub url: String,
+	pub event: WebhookEvent,
+	/// The JSON that's sent.
+	pub payload: String,
+	/// How many times delivering it has failed.
+	pub attempts: i32,
+}
+
+/// How a user rated one closing of their ticket.
+#[derive(Clone)]
+pub struct SurveyResponse {
+	pub thread_id: ChannelId,
+	/// When the ticket was closed, since a ticket can be closed more than once.
+	pub closed_at: i64,
+	pub user_id: UserId,
+	/// The member server the ticket was for.
+	pub guild_id: GuildId,
+	/// Who closed the ticket, if staff did.
+	pub staff_id: Option<UserId>,
+	/// From 1 to 5.
+	pub rating: i32,
+	pub comment: Option<String>,
+	pub created_at: i64,
+}
+
+impl Database {
+	pub async fn connect(config: &DatabaseConfig) -> anyhow::Result<Self> {
+		let storage: Box<dyn Storage> = match config {
+			DatabaseConfig::Sqlite { path } => {
+				Box::new(sqlite::SqliteStorage::connect(path).await?)
+			},
+			DatabaseConfig::Postgres { url } => {
+				Box::new(postgres::PostgresStorage::connect(url).await?)
+			},
+		};
+
+		Ok(Self {
+			storage,
+			cache: TicketCache::default(),
+		})
+	}
+
+	pub async fn close(&self) {
+		self.storage.close().await;
+	}
+
+	pub fn cache_stats(&self) -> CacheStats {
+		self.cache.stats()
+	}
+
+	pub async fn ticket_by_user(&self, user_id: UserId) -> anyhow::Result<Option<Ticket>> {
+		if let Some(ticket) = self.cache.by_user(user_id) {
+			return Ok(ticket);
+		}
+
+		let generation = self.cache.generation();
+		let ticket = self.storage.ticket_by_user(user_id).await?;
+		self.cache.fill(generation, user_id, ticket.as_ref());
+
+		Ok(ticket)
+	}
+
+	pub async fn ticket_by_dm_channel(
+		&self,
+		dm_channel_id: ChannelId,
+	) -> anyhow::Result<Option<Ticket>> {
+		if let Some(ticket) = self.cache.by_dm_channel(dm_channel_id) {
+			return Ok(ticket);
+		}
+
+		let generation = self.cache.generation();
+		let ticket = self.storage.ticket_by_dm_channel(dm_channel_id).await?;
+		match &ticket {
+			Some(ticket) => self.cache.fill(generation, ticket.user_id, Some(ticket)),
+			None => self
+				.cache
+				.fill_missing_dm_channel(generation, dm_channel_id),
+		}
+
+		Ok(ticket)
+	}
+
+	pub async fn ticket_by_thread(&self, thread_id: ChannelId) -> anyhow::Result<Option<Ticket>> {
+		if let Some(ticket) = self.cache.by_thread(thread_id) {
+			return Ok(ticket);
+		}
+
+		let generation = self.cache.generation();
+		let ticket = self.storage.ticket_by_thread(thread_id).await?;
+		match &ticket {
+			Some(ticket) => self.cache.fill(generation, ticket.user_id, Some(ticket)),
+			None => self.cache.fill_missing_thread(generation, thread_id),
+		}
+
+		Ok(ticket)
+	}
+
+	pub async fn insert_ticket(&self, ticket: &Ticket) -> anyhow::Result<()> {
+		self.storage.insert_ticket(ticket).await?;
+		self.cache.invalidate(ticket.user_id);
+		Ok(())
+	}
+
+	pub async fn set_ticket_open(&self, user_id: UserId, is_open: bool) -> anyhow::Result<()> {
+		self.storage.set_ticket_open(user_id, is_open).await?;
+		self.cache.invalidate(user_id);
+		Ok(())
+	}
+
+	/// Moves a ticket to a new thread for a different server, keeping its messages.
+	pub async fn move_ticket(
+		&self,
+		user_id: UserId,
+		guild_id: GuildId,
+		thread_id: ChannelId,
+	) -> anyhow::Result<()> {
+		self.storage
+			.move_ticket(user_id, guild_id, thread_id)
+			.await?;
+		self.cache.invalidate(user_id);
+		Ok(())
+	}
+
+	pub async fn set_ticket_blocked(&self, user_id: UserId, blocked: bool) -> anyhow::Result<()> {
+		self.storage.set_ticket_blocked(user_id, blocked).await?;
+		self.cache.invalidate(user_id);
+		Ok(())
+	}
+
+	pub async fn delete_ticket(&self, thread_id: ChannelId) -> anyhow::Result<()> {
+		if let Some(user_id) = self.storage.delete_ticket(thread_id).await? {
+			self.cache.invalidate(user_id);
+		}
+		Ok(())
+	}
+
+	pub async fn message_by_dm_msg(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+	) -> anyhow::Result<Option<RelayedMessage>> {
+		self.storage.message_by_dm_msg(user_id, dm_msg_id).await
+	}
+
+	/// Finds a message by any thread message that shows one of its versions.
+	pub async fn message_by_thread_msg(
+		&self,
+		user_id: UserId,
+		thread_msg_id: MessageId,
+	) -> anyhow::Result<Option<RelayedMessage>> {
+		self.storage
+			.message_by_thread_msg(user_id, thread_msg_id)
+			.await
+	}
+
+	/// Previous versions of a message, oldest first.
+	pub async fn message_revisions(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+	) -> anyhow::Result<Vec<MessageRevision>> {
+		self.storage.message_revisions(user_id, dm_msg_id).await
+	}
+
+	pub async fn insert_message(&self, msg: &RelayedMessage) -> anyhow::Result<()> {
+		self.storage.insert_message(msg).await
+	}
+
+	/// Saves the current content of a message as a revision, then replaces it.
+	///
+	/// `thread_update_msg_id` is the thread message showing the new content, if it isn't the
+	/// original thread message.
+	pub async fn edit_message(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+		thread_update_msg_id: Option<MessageId>,
+		content: &str,
+		edited_at: i64,
+	) -> anyhow::Result<()> {
+		self.storage
+			.edit_message(user_id, dm_msg_id, thread_update_msg_id, content, edited_at)
+			.await
+	}
+
+	/// Marks a message as deleted, returning it if it was found.
+	pub async fn delete_message(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+		deleted_at: i64,
+	) -> anyhow::Result<Option<RelayedMessage>> {
+		self.storage
+			.delete_message(user_id, dm_msg_id, deleted_at)
+			.await
+	}
+
+	pub async fn insert_moderation(&self, moderation: &Moderation) -> anyhow::Result<()> {
+		self.storage.insert_moderation(moderation).await
+	}
+
+	/// Every moderation action taken on a user, newest first.
+	pub async fn moderations(&self, user_id: UserId) -> anyhow::Result<Vec<Moderation>> {
+		self.storage.moderations(user_id).await
+	}
+
+	pub async fn insert_ticket_event(&self, event: &TicketEvent) -> anyhow::Result<()> {
+		self.storage.insert_ticket_event(event).await
+	}
+
+	/// Everything that happened to a user's ticket, newest first.
+	pub async fn ticket_events(&self, user_id: UserId) -> anyhow::Result<Vec<TicketEvent>> {
+		self.storage.ticket_events(user_id).await
+	}
+
+	/// When the newest message between a user and staff was sent.
+	pub async fn last_message_at(&self, user_id: UserId) -> anyhow::Result<Option<i64>> {
+		self.storage.last_message_at(user_id).await
+	}
+
+	pub async fn insert_staff_note(&self, note: &StaffNote) -> anyhow::Result<()> {
+		self.storage.insert_staff_note(note).await
+	}
+
+	/// Every note about a user, newest first.
+	pub async fn staff_notes(&self, user_id: UserId) -> anyhow::Result<Vec<StaffNote>> {
+		self.storage.staff_notes(user_id).await
+	}
+
+	/// Starts a ticket's inactivity timer over after a message.
+	pub async fn reset_inactivity_timer(
+		&self,
+		user_id: UserId,
+		last_message_at: i64,
+		staff_spoke_last: bool,
+	) -> anyhow::Result<()> {
+		self.storage
+			.reset_inactivity_timer(user_id, last_message_at, staff_spoke_last)
+			.await
+	}
+
+	pub async fn set_inactivity_warned(
+		&self,
+		user_id: UserId,
+		warned_at: i64,
+	) -> anyhow::Result<()> {
+		self.storage.set_inactivity_warned(user_id, warned_at).await
+	}
+
+	pub async fn delete_inactivity_timer(&self, user_id: UserId) -> anyhow::Result<()> {
+		self.storage.delete_inactivity_timer(user_id).await
+	}
+
+	pub async fn inactivity_timers(&self) -> anyhow::Result<Vec<InactivityTimer>> {
+		self.storage.inactivity_timers().await
+	}
+
+	/// Saves a user's rating, replacing it if they already rated that closing.
+	///
+	/// Any comment they left is kept.
+	pub async fn insert_survey_response(&self, response: &SurveyResponse) -> anyhow::Result<()> {
+		self.storage.insert_survey_response(response).await
+	}
+
+	pub async fn set_survey_comment(
+		&self,
+		thread_id: ChannelId,
+		closed_at: i64,
+		comment: &str,
+	) -> anyhow::Result<()> {
+		self.storage
+			.set_survey_comment(thread_id, closed_at, comment)
+			.await
+	}
+
+	pub async fn insert_filter_hit(&self, hit: &FilterHit) -> anyhow::Result<()> {
+		self.storage.insert_filter_hit(hit).await
+	}
+
+	/// Every filter hit since a unix timestamp, newest first.
+	pub async fn filter_hits(&self, since: i64) -> anyhow::Result<Vec<FilterHit>> {
+		self.storage.filter_hits(since).await
+	}
+
+	/// Every survey response, oldest first.
+	pub async fn survey_responses(&self) -> anyhow::Result<Vec<SurveyResponse>> {
+		self.storage.survey_responses().await
+	}
+
+	/// Saves a webhook payload to be delivered as soon as possible.
+	pub async fn insert_webhook_delivery(
+		&self,
+		url: &str,
+		event: WebhookEvent,
+		payload: &str,
+		created_at: i64,
+	) -> anyhow::Result<()> {
+		self.storage
+			.insert_webhook_delivery(url, event, payload, created_at)
+			.await
+	}
+
+	/// Webhook deliveries that should be attempted by a unix timestamp, oldest first.
+	pub async fn due_webhook_deliveries(&self, now: i64) -> anyhow::Result<Vec<WebhookDelivery>> {
+		self.storage.due_webhook_deliveries(now).await
+	}
+
+	pub async fn retry_webhook_delivery(
+		&self,
+		id: i64,
+		attempts: i32,
+		next_attempt_at: i64,
+	) -> anyhow::Result<()> {
+		self.storage
+			.retry_webhook_delivery(id, attempts, next_attempt_at)
+			.await
+	}
+
+	pub async fn delete_webhook_delivery(&self, id: i64) -> anyhow::Result<()> {
+		self.storage.delete_webhook_delivery(id).await
+	}
+
+	/// The locale that the user's discord client last reported.
+	pub async fn user_locale(&self, user_id: UserId) -> anyhow::Result<Option<String>> {
+		self.storage.user_locale(user_id).await
+	}
+
+	pub async fn set_user_locale(&self, user_id: UserId, locale: &str) -> anyhow::Result<()> {
+		self.storage.set_user_locale(user_id, locale).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	};
+	use twilight::id::Id;
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+	async fn writes_are_never_followed_by_stale_reads() {
+		for test_db in testing::databases().await {
+			println!("testing {}", test_db.backend);
+			stale_reads(&test_db.db).await;
+			test_db.finish().await;
+		}
+	}
+
+	async fn stale_reads(db: &Arc<Database>) {
+		let user_id = Id::new(1);
+		let dm_channel_id = Id::new(2);
+		let threads = [Id::new(3), Id::new(4)];
+		let guild_id = Id::new(5);
+		db.insert_ticket(&Ticket {
+			user_id,
+			guild_id: Some(guild_id),
+			dm_channel_id,
+			thread_id: threads[0],
+			is_open: true,
+			blocked: false,
+		})
+		.await
+		.unwrap();
+
+		// keep filling the cache from every direction while the ticket changes
+		let done = Arc::new(AtomicBool::new(false));
+		let readers: Vec<_> = (0..4)
+			.map(|_| {
+				let db = Arc::clone(db);
+				let done = Arc::clone(&done);
+				tokio::spawn(async move {
+					while !done.load(Ordering::Relaxed) {
+						db.ticket_by_user(user_id).await.unwrap();
+						db.ticket_by_dm_channel(dm_channel_id).await.unwrap();
+						for thread_id in threads {
+							db.ticket_by_thread(thread_id).await.unwrap();
+						}
+						// cache hits never wait, so let the database's own tasks run
+						tokio::task::yield_now().await;
+					}
+				})
+			})
+			.collect();
+
+		for i in 0..200 {
+			let blocked = i % 2 == 0;
+			let is_open = i % 3 != 0;
+			let thread_id = threads[i % 2];
+			let old_thread_id = threads[(i + 1) % 2];
+			db.set_ticket_blocked(user_id, blocked).await.unwrap();
+			db.set_ticket_open(user_id, is_open).await.unwrap();
+			db.move_ticket(user_id, guild_id, thread_id).await.unwrap();
+
+			for ticket in [
+				db.ticket_by_user(user_id).await.unwrap(),
+				db.ticket_by_dm_channel(dm_channel_id).await.unwrap(),
+				db.ticket_by_thread(thread_id).await.unwrap(),
+			] {
+				let ticket = ticket.expect("the ticket should be found");
+				assert_eq!(ticket.blocked, blocked);
+				assert_eq!(ticket.is_open, is_open);
+				assert_eq!(ticket.thread_id, thread_id);
+			}
+			assert!(db.ticket_by_thread(old_thread_id).await.unwrap().is_none());
+		}
+
+		// a deleted ticket isn't served either
+		db.delete_ticket(threads[1]).await.unwrap();
+		assert!(db.ticket_by_user(user_id).await.unwrap().is_none());
+		assert!(db
+			.ticket_by_dm_channel(dm_channel_id)
+			.await
+			.unwrap()
+			.is_none());
+		assert!(db.ticket_by_thread(threads[1]).await.unwrap().is_none());
+
+		done.store(true, Ordering::Relaxed);
+		for reader in readers {
+			reader.await.unwrap();
+		}
+	}
+
+	#[tokio::test]
+	async fn survey_responses_are_in_the_order_they_were_given() {
+		for test_db in testing::databases().await {
+			println!("testing {}", test_db.backend);
+			// the same time, so the tie breaks decide
+			for (thread_id, closed_at) in [(3, 20), (2, 20), (3, 10)] {
+				test_db
+					.db
+					.insert_survey_response(&SurveyResponse {
+						thread_id: Id::new(thread_id),
+						closed_at,
+						user_id: Id::new(1),
+						guild_id: Id::new(4),
+						staff_id: None,
+						rating: 5,
+						comment: None,
+						created_at: 100,
+					})
+					.await
+					.unwrap();
+			}
+
+			let order: Vec<_> = test_db
+				.db
+				.survey_responses()
+				.await
+				.unwrap()
+				.iter()
+				.map(|response| (response.thread_id.get(), response.closed_at))
+				.collect();
+			assert_eq!(order, [(2, 20), (3, 10), (3, 20)]);
+			test_db.finish().await;
+		}
+	}
+}
@@ -0,0 +1,742 @@
+use super::{
+	FilterHit, InactivityTimer, MessageRevision, Moderation, RelayedMessage, StaffNote, Storage,
+	SurveyResponse, Ticket, TicketEvent, WebhookDelivery,
+};
+use crate::{config::WebhookEvent, util::PgId};
+use sqlx::{
+	postgres::{PgConnectOptions, PgPoolOptions, PgRow},
+	ConnectOptions, FromRow, PgPool, Row,
+};
+use std::str::FromStr;
+use twilight::id::{ChannelId, GuildId, MessageId, UserId};
+
+pub struct PostgresStorage {
+	connection: PgPool,
+}
+
+impl<'r> FromRow<'r, PgRow> for Ticket {
+	fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+		let user_id: PgId<UserId> = row.try_get("user_id")?;
+		let dm_channel_id: PgId<ChannelId> = row.try_get("dm_channel_id")?;
+		let thread_id: PgId<ChannelId> = row.try_get("thread_id")?;
+		let guild_id: Option<PgId<GuildId>> = row.try_get("guild_id")?;
+		Ok(Self {
+			user_id: *user_id,
+			guild_id: guild_id.map(|id| *id),
+			dm_channel_id: *dm_channel_id,
+			thread_id: *thread_id,
+			is_open: row.try_get("is_open")?,
+			blocked: row.try_get("blocked")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, PgRow> for RelayedMessage {
+	fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+		let user_id: PgId<UserId> = row.try_get("user_id")?;
+		let dm_msg_id: PgId<MessageId> = row.try_get("dm_msg_id")?;
+		let thread_msg_id: PgId<MessageId> = row.try_get("thread_msg_id")?;
+		let thread_update_msg_id: Option<PgId<MessageId>> = row.try_get("thread_update_msg_id")?;
+		let author_id: Option<PgId<UserId>> = row.try_get("author_id")?;
+		Ok(Self {
+			user_id: *user_id,
+			dm_msg_id: *dm_msg_id,
+			thread_msg_id: *thread_msg_id,
+			thread_update_msg_id: thread_update_msg_id.map(|id| *id),
+			author_id: author_id.map(|id| *id),
+			content: row.try_get("content")?,
+			created_at: row.try_get("created_at")?,
+			edited_at: row.try_get("edited_at")?,
+			deleted_at: row.try_get("deleted_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, PgRow> for Moderation {
+	fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+		let user_id: PgId<UserId> = row.try_get("user_id")?;
+		let guild_id: PgId<GuildId> = row.try_get("guild_id")?;
+		let staff_id: PgId<UserId> = row.try_get("staff_id")?;
+		let kind: String = row.try_get("kind")?;
+		Ok(Self {
+			user_id: *user_id,
+			guild_id: *guild_id,
+			staff_id: *staff_id,
+			kind: kind
+				.parse()
+				.map_err(|err: String| sqlx::Error::Decode(err.into()))?,
+			reason: row.try_get("reason")?,
+			expires_at: row.try_get("expires_at")?,
+			created_at: row.try_get("created_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, PgRow> for TicketEvent {
+	fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+		let user_id: PgId<UserId> = row.try_get("user_id")?;
+		let staff_id: Option<PgId<UserId>> = row.try_get("staff_id")?;
+		let kind: String = row.try_get("kind")?;
+		Ok(Self {
+			user_id: *user_id,
+			kind: kind
+				.parse()
+				.map_err(|err: String| sqlx::Error::Decode(err.into()))?,
+			staff_id: staff_id.map(|id| *id),
+			reason: row.try_get("reason")?,
+			created_at: row.try_get("created_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, PgRow> for StaffNote {
+	fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+		let user_id: PgId<UserId> = row.try_get("user_id")?;
+		let staff_id: PgId<UserId> = row.try_get("staff_id")?;
+		Ok(Self {
+			user_id: *user_id,
+			staff_id: *staff_id,
+			content: row.try_get("content")?,
+			created_at: row.try_get("created_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, PgRow> for InactivityTimer {
+	fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+		let user_id: PgId<UserId> = row.try_get("user_id")?;
+		Ok(Self {
+			user_id: *user_id,
+			last_message_at: row.try_get("last_message_at")?,
+			staff_spoke_last: row.try_get("staff_spoke_last")?,
+			warned_at: row.try_get("warned_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, PgRow> for FilterHit {
+	fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+		let user_id: PgId<UserId> = row.try_get("user_id")?;
+		let dm_msg_id: PgId<MessageId> = row.try_get("dm_msg_id")?;
+		let action: String = row.try_get("action")?;
+		Ok(Self {
+			user_id: *user_id,
+			dm_msg_id: *dm_msg_id,
+			filter: row.try_get("filter")?,
+			action: action
+				.parse()
+				.map_err(|err: String| sqlx::Error::Decode(err.into()))?,
+			matched: row.try_get("matched")?,
+			content: row.try_get("content")?,
+			created_at: row.try_get("created_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, PgRow> for SurveyResponse {
+	fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+		let thread_id: PgId<ChannelId> = row.try_get("thread_id")?;
+		let user_id: PgId<UserId> = row.try_get("user_id")?;
+		let guild_id: PgId<GuildId> = row.try_get("guild_id")?;
+		let staff_id: Option<PgId<UserId>> = row.try_get("staff_id")?;
+		Ok(Self {
+			thread_id: *thread_id,
+			closed_at: row.try_get("closed_at")?,
+			user_id: *user_id,
+			guild_id: *guild_id,
+			staff_id: staff_id.map(|id| *id),
+			rating: row.try_get("rating")?,
+			comment: row.try_get("comment")?,
+			created_at: row.try_get("created_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, PgRow> for MessageRevision {
+	fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+		let thread_msg_id: PgId<MessageId> = row.try_get("thread_msg_id")?;
+		Ok(Self {
+			thread_msg_id: *thread_msg_id,
+			content: row.try_get("content")?,
+			created_at: row.try_get("created_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, PgRow> for WebhookDelivery {
+	fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+		let event: String = row.try_get("event")?;
+		Ok(Self {
+			id: row.try_get("id")?,
+			url: row.try_get("url")?,
+			event: event
+				.parse()
+				.map_err(|err: String| sqlx::Error::Decode(err.into()))?,
+			payload: row.try_get("payload")?,
+			attempts: row.try_get("attempts")?,
+		})
+	}
+}
+
+impl PostgresStorage {
+	pub async fn connect(url: &str) -> anyhow::Result<Self> {
+		let mut db_options = PgConnectOptions::from_str(url)?;
+
+		db_options.disable_statement_logging();
+		let connection = PgPoolOptions::new().connect_with(db_options).await?;
+		sqlx::migrate!("./migrations/postgres")
+			.run(&connection)
+			.await?;
+
+		Ok(Self { connection })
+	}
+}
+
+#[async_trait::async_trait]
+impl Storage for PostgresStorage {
+	async fn close(&self) {
+		self.connection.close().await;
+	}
+
+	async fn ticket_by_user(&self, user_id: UserId) -> anyhow::Result<Option<Ticket>> {
+		Ok(sqlx::query_as("SELECT * FROM tickets WHERE user_id = $1")
+			.bind(PgId(user_id))
+			.fetch_optional(&self.connection)
+			.await?)
+	}
+
+	async fn ticket_by_dm_channel(
+		&self,
+		dm_channel_id: ChannelId,
+	) -> anyhow::Result<Option<Ticket>> {
+		Ok(
+			sqlx::query_as("SELECT * FROM tickets WHERE dm_channel_id = $1")
+				.bind(PgId(dm_channel_id))
+				.fetch_optional(&self.connection)
+				.await?,
+		)
+	}
+
+	async fn ticket_by_thread(&self, thread_id: ChannelId) -> anyhow::Result<Option<Ticket>> {
+		Ok(sqlx::query_as("SELECT * FROM tickets WHERE thread_id = $1")
+			.bind(PgId(thread_id))
+			.fetch_optional(&self.connection)
+			.await?)
+	}
+
+	async fn insert_ticket(&self, ticket: &Ticket) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO tickets (user_id, thread_id, dm_channel_id, guild_id)
+			VALUES ($1, $2, $3, $4)
+		"})
+		.bind(PgId(ticket.user_id))
+		.bind(PgId(ticket.thread_id))
+		.bind(PgId(ticket.dm_channel_id))
+		.bind(ticket.guild_id.map(PgId))
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn set_ticket_open(&self, user_id: UserId, is_open: bool) -> anyhow::Result<()> {
+		sqlx::query("UPDATE tickets SET is_open = $1 WHERE user_id = $2")
+			.bind(is_open)
+			.bind(PgId(user_id))
+			.execute(&self.connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn move_ticket(
+		&self,
+		user_id: UserId,
+		guild_id: GuildId,
+		thread_id: ChannelId,
+	) -> anyhow::Result<()> {
+		sqlx::query("UPDATE tickets SET guild_id = $1, thread_id = $2 WHERE user_id = $3")
+			.bind(PgId(guild_id))
+			.bind(PgId(thread_id))
+			.bind(PgId(user_id))
+			.execute(&self.connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn set_ticket_blocked(&self, user_id: UserId, blocked: bool) -> anyhow::Result<()> {
+		sqlx::query("UPDATE tickets SET blocked = $1 WHERE user_id = $2")
+			.bind(blocked)
+			.bind(PgId(user_id))
+			.execute(&self.connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn delete_ticket(&self, thread_id: ChannelId) -> anyhow::Result<Option<UserId>> {
+		Ok(
+			sqlx::query("DELETE FROM tickets WHERE thread_id = $1 RETURNING user_id")
+				.bind(PgId(thread_id))
+				.try_map(|row: PgRow| {
+					let id: PgId<UserId> = row.try_get(0)?;
+					Ok(*id)
+				})
+				.fetch_optional(&self.connection)
+				.await?,
+		)
+	}
+
+	async fn message_by_dm_msg(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+	) -> anyhow::Result<Option<RelayedMessage>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM messages
+			WHERE user_id = $1 AND dm_msg_id = $2
+			ORDER BY id DESC
+		"})
+		.bind(PgId(user_id))
+		.bind(PgId(dm_msg_id))
+		.fetch_optional(&self.connection)
+		.await?)
+	}
+
+	async fn message_by_thread_msg(
+		&self,
+		user_id: UserId,
+		thread_msg_id: MessageId,
+	) -> anyhow::Result<Option<RelayedMessage>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM messages
+			WHERE user_id = $1 AND (
+				thread_msg_id = $2
+				OR thread_update_msg_id = $2
+				OR dm_msg_id IN (
+					SELECT dm_msg_id FROM message_revisions
+					WHERE user_id = $1 AND thread_msg_id = $2
+				)
+			)
+			ORDER BY id DESC
+		"})
+		.bind(PgId(user_id))
+		.bind(PgId(thread_msg_id))
+		.fetch_optional(&self.connection)
+		.await?)
+	}
+
+	async fn message_revisions(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+	) -> anyhow::Result<Vec<MessageRevision>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM message_revisions
+			WHERE user_id = $1 AND dm_msg_id = $2
+			ORDER BY id
+		"})
+		.bind(PgId(user_id))
+		.bind(PgId(dm_msg_id))
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn insert_message(&self, msg: &RelayedMessage) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO messages (
+				user_id, dm_msg_id, thread_msg_id, thread_update_msg_id,
+				author_id, content, created_at, edited_at, deleted_at
+			)
+			VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+		"})
+		.bind(PgId(msg.user_id))
+		.bind(PgId(msg.dm_msg_id))
+		.bind(PgId(msg.thread_msg_id))
+		.bind(msg.thread_update_msg_id.map(PgId))
+		.bind(msg.author_id.map(PgId))
+		.bind(&msg.content)
+		.bind(msg.created_at)
+		.bind(msg.edited_at)
+		.bind(msg.deleted_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn edit_message(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+		thread_update_msg_id: Option<MessageId>,
+		content: &str,
+		edited_at: i64,
+	) -> anyhow::Result<()> {
+		let mut transaction = self.connection.begin().await?;
+
+		sqlx::query(indoc! {"
+			INSERT INTO message_revisions (user_id, dm_msg_id, thread_msg_id, content, created_at)
+			SELECT
+				user_id,
+				dm_msg_id,
+				COALESCE(thread_update_msg_id, thread_msg_id),
+				content,
+				COALESCE(edited_at, created_at)
+			FROM messages
+			WHERE user_id = $1 AND dm_msg_id = $2
+				AND content IS NOT NULL AND created_at IS NOT NULL
+		"})
+		.bind(PgId(user_id))
+		.bind(PgId(dm_msg_id))
+		.execute(&mut transaction)
+		.await?;
+
+		sqlx::query(indoc! {"
+			UPDATE messages
+			SET
+				thread_update_msg_id = COALESCE($1, thread_update_msg_id),
+				content = $2,
+				edited_at = $3
+			WHERE user_id = $4 AND dm_msg_id = $5
+		"})
+		.bind(thread_update_msg_id.map(PgId))
+		.bind(content)
+		.bind(edited_at)
+		.bind(PgId(user_id))
+		.bind(PgId(dm_msg_id))
+		.execute(&mut transaction)
+		.await?;
+
+		transaction.commit().await?;
+		Ok(())
+	}
+
+	async fn delete_message(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+		deleted_at: i64,
+	) -> anyhow::Result<Option<RelayedMessage>> {
+		Ok(sqlx::query_as(indoc! {"
+			UPDATE messages
+			SET deleted_at = $1
+			WHERE user_id = $2 AND dm_msg_id = $3
+			RETURNING *
+		"})
+		.bind(deleted_at)
+		.bind(PgId(user_id))
+		.bind(PgId(dm_msg_id))
+		.fetch_optional(&self.connection)
+		.await?)
+	}
+
+	async fn insert_moderation(&self, moderation: &Moderation) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO moderations (
+				user_id, guild_id, staff_id, kind, reason, expires_at, created_at
+			)
+			VALUES ($1, $2, $3, $4, $5, $6, $7)
+		"})
+		.bind(PgId(moderation.user_id))
+		.bind(PgId(moderation.guild_id))
+		.bind(PgId(moderation.staff_id))
+		.bind(moderation.kind.name())
+		.bind(&moderation.reason)
+		.bind(moderation.expires_at)
+		.bind(moderation.created_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn moderations(&self, user_id: UserId) -> anyhow::Result<Vec<Moderation>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM moderations
+			WHERE user_id = $1
+			ORDER BY created_at DESC, id DESC
+		"})
+		.bind(PgId(user_id))
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn insert_ticket_event(&self, event: &TicketEvent) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO ticket_events (user_id, kind, staff_id, reason, created_at)
+			VALUES ($1, $2, $3, $4, $5)
+		"})
+		.bind(PgId(event.user_id))
+		.bind(event.kind.name())
+		.bind(event.staff_id.map(PgId))
+		.bind(&event.reason)
+		.bind(event.created_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn ticket_events(&self, user_id: UserId) -> anyhow::Result<Vec<TicketEvent>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM ticket_events
+			WHERE user_id = $1
+			ORDER BY created_at DESC, id DESC
+		"})
+		.bind(PgId(user_id))
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn last_message_at(&self, user_id: UserId) -> anyhow::Result<Option<i64>> {
+		Ok(
+			sqlx::query_scalar("SELECT MAX(created_at) FROM messages WHERE user_id = $1")
+				.bind(PgId(user_id))
+				.fetch_one(&self.connection)
+				.await?,
+		)
+	}
+
+	async fn insert_staff_note(&self, note: &StaffNote) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO staff_notes (user_id, staff_id, content, created_at)
+			VALUES ($1, $2, $3, $4)
+		"})
+		.bind(PgId(note.user_id))
+		.bind(PgId(note.staff_id))
+		.bind(&note.content)
+		.bind(note.created_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn staff_notes(&self, user_id: UserId) -> anyhow::Result<Vec<StaffNote>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM staff_notes
+			WHERE user_id = $1
+			ORDER BY created_at DESC, id DESC
+		"})
+		.bind(PgId(user_id))
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn reset_inactivity_timer(
+		&self,
+		user_id: UserId,
+		last_message_at: i64,
+		staff_spoke_last: bool,
+	) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO inactivity_timers (user_id, last_message_at, staff_spoke_last, warned_at)
+			VALUES ($1, $2, $3, NULL)
+			ON CONFLICT (user_id) DO UPDATE SET
+				last_message_at = excluded.last_message_at,
+				staff_spoke_last = excluded.staff_spoke_last,
+				warned_at = NULL
+		"})
+		.bind(PgId(user_id))
+		.bind(last_message_at)
+		.bind(staff_spoke_last)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn set_inactivity_warned(&self, user_id: UserId, warned_at: i64) -> anyhow::Result<()> {
+		sqlx::query("UPDATE inactivity_timers SET warned_at = $1 WHERE user_id = $2")
+			.bind(warned_at)
+			.bind(PgId(user_id))
+			.execute(&self.connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn delete_inactivity_timer(&self, user_id: UserId) -> anyhow::Result<()> {
+		sqlx::query("DELETE FROM inactivity_timers WHERE user_id = $1")
+			.bind(PgId(user_id))
+			.execute(&self.connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn inactivity_timers(&self) -> anyhow::Result<Vec<InactivityTimer>> {
+		Ok(sqlx::query_as("SELECT * FROM inactivity_timers")
+			.fetch_all(&self.connection)
+			.await?)
+	}
+
+	async fn insert_filter_hit(&self, hit: &FilterHit) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO filter_hits (
+				user_id, dm_msg_id, filter, action, matched, content, created_at
+			)
+			VALUES ($1, $2, $3, $4, $5, $6, $7)
+		"})
+		.bind(PgId(hit.user_id))
+		.bind(PgId(hit.dm_msg_id))
+		.bind(&hit.filter)
+		.bind(hit.action.name())
+		.bind(&hit.matched)
+		.bind(&hit.content)
+		.bind(hit.created_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn filter_hits(&self, since: i64) -> anyhow::Result<Vec<FilterHit>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM filter_hits
+			WHERE created_at >= $1
+			ORDER BY created_at DESC, id DESC
+		"})
+		.bind(since)
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn insert_survey_response(&self, response: &SurveyResponse) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO survey_responses (
+				thread_id, closed_at, user_id, guild_id, staff_id, rating, comment, created_at
+			)
+			VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+			ON CONFLICT (thread_id, closed_at) DO UPDATE SET
+				rating = excluded.rating,
+				created_at = excluded.created_at
+		"})
+		.bind(PgId(response.thread_id))
+		.bind(response.closed_at)
+		.bind(PgId(response.user_id))
+		.bind(PgId(response.guild_id))
+		.bind(response.staff_id.map(PgId))
+		.bind(response.rating)
+		.bind(&response.comment)
+		.bind(response.created_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn set_survey_comment(
+		&self,
+		thread_id: ChannelId,
+		closed_at: i64,
+		comment: &str,
+	) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			UPDATE survey_responses SET comment = $1
+			WHERE thread_id = $2 AND closed_at = $3
+		"})
+		.bind(comment)
+		.bind(PgId(thread_id))
+		.bind(closed_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn survey_responses(&self) -> anyhow::Result<Vec<SurveyResponse>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM survey_responses
+			ORDER BY created_at, thread_id, closed_at
+		"})
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn insert_webhook_delivery(
+		&self,
+		url: &str,
+		event: WebhookEvent,
+		payload: &str,
+		created_at: i64,
+	) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO webhook_deliveries (url, event, payload, next_attempt_at, created_at)
+			VALUES ($1, $2, $3, $4, $4)
+		"})
+		.bind(url)
+		.bind(event.name())
+		.bind(payload)
+		.bind(created_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn due_webhook_deliveries(&self, now: i64) -> anyhow::Result<Vec<WebhookDelivery>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM webhook_deliveries
+			WHERE next_attempt_at <= $1
+			ORDER BY id
+		"})
+		.bind(now)
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn retry_webhook_delivery(
+		&self,
+		id: i64,
+		attempts: i32,
+		next_attempt_at: i64,
+	) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			UPDATE webhook_deliveries
+			SET attempts = $1, next_attempt_at = $2
+			WHERE id = $3
+		"})
+		.bind(attempts)
+		.bind(next_attempt_at)
+		.bind(id)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn delete_webhook_delivery(&self, id: i64) -> anyhow::Result<()> {
+		sqlx::query("DELETE FROM webhook_deliveries WHERE id = $1")
+			.bind(id)
+			.execute(&self.connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn user_locale(&self, user_id: UserId) -> anyhow::Result<Option<String>> {
+		Ok(
+			sqlx::query_scalar("SELECT locale FROM user_locales WHERE user_id = $1")
+				.bind(PgId(user_id))
+				.fetch_optional(&self.connection)
+				.await?,
+		)
+	}
+
+	async fn set_user_locale(&self, user_id: UserId, locale: &str) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO user_locales (user_id, locale)
+			VALUES ($1, $2)
+			ON CONFLICT (user_id) DO UPDATE SET locale = excluded.locale
+		"})
+		.bind(PgId(user_id))
+		.bind(locale)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+}
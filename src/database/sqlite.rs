@@ -0,0 +1,750 @@
+use super::{
+	FilterHit, InactivityTimer, MessageRevision, Moderation, RelayedMessage, StaffNote, Storage,
+	SurveyResponse, Ticket, TicketEvent, WebhookDelivery,
+};
+use crate::{config::WebhookEvent, util::SqliteId};
+use sqlx::{
+	sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteRow},
+	ConnectOptions, FromRow, Row, SqlitePool,
+};
+use std::path::Path;
+use twilight::id::{ChannelId, GuildId, MessageId, UserId};
+
+pub struct SqliteStorage {
+	connection: SqlitePool,
+}
+
+impl<'r> FromRow<'r, SqliteRow> for Ticket {
+	fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+		let user_id: SqliteId<UserId> = row.try_get("user_id")?;
+		let dm_channel_id: SqliteId<ChannelId> = row.try_get("dm_channel_id")?;
+		let thread_id: SqliteId<ChannelId> = row.try_get("thread_id")?;
+		let guild_id: Option<SqliteId<GuildId>> = row.try_get("guild_id")?;
+		Ok(Self {
+			user_id: *user_id,
+			guild_id: guild_id.map(|id| *id),
+			dm_channel_id: *dm_channel_id,
+			thread_id: *thread_id,
+			is_open: row.try_get("is_open")?,
+			blocked: row.try_get("blocked")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, SqliteRow> for RelayedMessage {
+	fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+		let user_id: SqliteId<UserId> = row.try_get("user_id")?;
+		let dm_msg_id: SqliteId<MessageId> = row.try_get("dm_msg_id")?;
+		let thread_msg_id: SqliteId<MessageId> = row.try_get("thread_msg_id")?;
+		let thread_update_msg_id: Option<SqliteId<MessageId>> =
+			row.try_get("thread_update_msg_id")?;
+		let author_id: Option<SqliteId<UserId>> = row.try_get("author_id")?;
+		Ok(Self {
+			user_id: *user_id,
+			dm_msg_id: *dm_msg_id,
+			thread_msg_id: *thread_msg_id,
+			thread_update_msg_id: thread_update_msg_id.map(|id| *id),
+			author_id: author_id.map(|id| *id),
+			content: row.try_get("content")?,
+			created_at: row.try_get("created_at")?,
+			edited_at: row.try_get("edited_at")?,
+			deleted_at: row.try_get("deleted_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, SqliteRow> for Moderation {
+	fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+		let user_id: SqliteId<UserId> = row.try_get("user_id")?;
+		let guild_id: SqliteId<GuildId> = row.try_get("guild_id")?;
+		let staff_id: SqliteId<UserId> = row.try_get("staff_id")?;
+		let kind: String = row.try_get("kind")?;
+		Ok(Self {
+			user_id: *user_id,
+			guild_id: *guild_id,
+			staff_id: *staff_id,
+			kind: kind
+				.parse()
+				.map_err(|err: String| sqlx::Error::Decode(err.into()))?,
+			reason: row.try_get("reason")?,
+			expires_at: row.try_get("expires_at")?,
+			created_at: row.try_get("created_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, SqliteRow> for TicketEvent {
+	fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+		let user_id: SqliteId<UserId> = row.try_get("user_id")?;
+		let staff_id: Option<SqliteId<UserId>> = row.try_get("staff_id")?;
+		let kind: String = row.try_get("kind")?;
+		Ok(Self {
+			user_id: *user_id,
+			kind: kind
+				.parse()
+				.map_err(|err: String| sqlx::Error::Decode(err.into()))?,
+			staff_id: staff_id.map(|id| *id),
+			reason: row.try_get("reason")?,
+			created_at: row.try_get("created_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, SqliteRow> for StaffNote {
+	fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+		let user_id: SqliteId<UserId> = row.try_get("user_id")?;
+		let staff_id: SqliteId<UserId> = row.try_get("staff_id")?;
+		Ok(Self {
+			user_id: *user_id,
+			staff_id: *staff_id,
+			content: row.try_get("content")?,
+			created_at: row.try_get("created_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, SqliteRow> for InactivityTimer {
+	fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+		let user_id: SqliteId<UserId> = row.try_get("user_id")?;
+		Ok(Self {
+			user_id: *user_id,
+			last_message_at: row.try_get("last_message_at")?,
+			staff_spoke_last: row.try_get("staff_spoke_last")?,
+			warned_at: row.try_get("warned_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, SqliteRow> for FilterHit {
+	fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+		let user_id: SqliteId<UserId> = row.try_get("user_id")?;
+		let dm_msg_id: SqliteId<MessageId> = row.try_get("dm_msg_id")?;
+		let action: String = row.try_get("action")?;
+		Ok(Self {
+			user_id: *user_id,
+			dm_msg_id: *dm_msg_id,
+			filter: row.try_get("filter")?,
+			action: action
+				.parse()
+				.map_err(|err: String| sqlx::Error::Decode(err.into()))?,
+			matched: row.try_get("matched")?,
+			content: row.try_get("content")?,
+			created_at: row.try_get("created_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, SqliteRow> for SurveyResponse {
+	fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+		let thread_id: SqliteId<ChannelId> = row.try_get("thread_id")?;
+		let user_id: SqliteId<UserId> = row.try_get("user_id")?;
+		let guild_id: SqliteId<GuildId> = row.try_get("guild_id")?;
+		let staff_id: Option<SqliteId<UserId>> = row.try_get("staff_id")?;
+		Ok(Self {
+			thread_id: *thread_id,
+			closed_at: row.try_get("closed_at")?,
+			user_id: *user_id,
+			guild_id: *guild_id,
+			staff_id: staff_id.map(|id| *id),
+			rating: row.try_get("rating")?,
+			comment: row.try_get("comment")?,
+			created_at: row.try_get("created_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, SqliteRow> for MessageRevision {
+	fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+		let thread_msg_id: SqliteId<MessageId> = row.try_get("thread_msg_id")?;
+		Ok(Self {
+			thread_msg_id: *thread_msg_id,
+			content: row.try_get("content")?,
+			created_at: row.try_get("created_at")?,
+		})
+	}
+}
+
+impl<'r> FromRow<'r, SqliteRow> for WebhookDelivery {
+	fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+		let event: String = row.try_get("event")?;
+		Ok(Self {
+			id: row.try_get("id")?,
+			url: row.try_get("url")?,
+			event: event
+				.parse()
+				.map_err(|err: String| sqlx::Error::Decode(err.into()))?,
+			payload: row.try_get("payload")?,
+			attempts: row.try_get("attempts")?,
+		})
+	}
+}
+
+impl SqliteStorage {
+	pub async fn connect(path: &Path) -> anyhow::Result<Self> {
+		let mut db_options = SqliteConnectOptions::new()
+			.filename(path)
+			.create_if_missing(true)
+			.journal_mode(SqliteJournalMode::Wal);
+
+		db_options.disable_statement_logging();
+		let connection = SqlitePoolOptions::new().connect_with(db_options).await?;
+		sqlx::migrate!("./migrations/sqlite")
+			.run(&connection)
+			.await?;
+
+		Ok(Self { connection })
+	}
+}
+
+#[async_trait::async_trait]
+impl Storage for SqliteStorage {
+	async fn close(&self) {
+		self.connection.close().await;
+	}
+
+	async fn ticket_by_user(&self, user_id: UserId) -> anyhow::Result<Option<Ticket>> {
+		Ok(sqlx::query_as("SELECT * FROM tickets WHERE user_id = ?")
+			.bind(SqliteId(user_id))
+			.fetch_optional(&self.connection)
+			.await?)
+	}
+
+	async fn ticket_by_dm_channel(
+		&self,
+		dm_channel_id: ChannelId,
+	) -> anyhow::Result<Option<Ticket>> {
+		Ok(
+			sqlx::query_as("SELECT * FROM tickets WHERE dm_channel_id = ?")
+				.bind(SqliteId(dm_channel_id))
+				.fetch_optional(&self.connection)
+				.await?,
+		)
+	}
+
+	async fn ticket_by_thread(&self, thread_id: ChannelId) -> anyhow::Result<Option<Ticket>> {
+		Ok(sqlx::query_as("SELECT * FROM tickets WHERE thread_id = ?")
+			.bind(SqliteId(thread_id))
+			.fetch_optional(&self.connection)
+			.await?)
+	}
+
+	async fn insert_ticket(&self, ticket: &Ticket) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO tickets (user_id, thread_id, dm_channel_id, guild_id)
+			VALUES (?1, ?2, ?3, ?4)
+		"})
+		.bind(SqliteId(ticket.user_id))
+		.bind(SqliteId(ticket.thread_id))
+		.bind(SqliteId(ticket.dm_channel_id))
+		.bind(ticket.guild_id.map(SqliteId))
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn set_ticket_open(&self, user_id: UserId, is_open: bool) -> anyhow::Result<()> {
+		sqlx::query("UPDATE tickets SET is_open = ? WHERE user_id = ?")
+			.bind(is_open)
+			.bind(SqliteId(user_id))
+			.execute(&self.connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn move_ticket(
+		&self,
+		user_id: UserId,
+		guild_id: GuildId,
+		thread_id: ChannelId,
+	) -> anyhow::Result<()> {
+		sqlx::query("UPDATE tickets SET guild_id = ?, thread_id = ? WHERE user_id = ?")
+			.bind(SqliteId(guild_id))
+			.bind(SqliteId(thread_id))
+			.bind(SqliteId(user_id))
+			.execute(&self.connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn set_ticket_blocked(&self, user_id: UserId, blocked: bool) -> anyhow::Result<()> {
+		sqlx::query("UPDATE tickets SET blocked = ? WHERE user_id = ?")
+			.bind(blocked)
+			.bind(SqliteId(user_id))
+			.execute(&self.connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn delete_ticket(&self, thread_id: ChannelId) -> anyhow::Result<Option<UserId>> {
+		// `RETURNING` changes aren't committed until the statement finishes, so fetch every row
+		let user_ids = sqlx::query("DELETE FROM tickets WHERE thread_id = ? RETURNING user_id")
+			.bind(SqliteId(thread_id))
+			.try_map(|row: SqliteRow| {
+				let id: SqliteId<UserId> = row.try_get(0)?;
+				Ok(*id)
+			})
+			.fetch_all(&self.connection)
+			.await?;
+
+		Ok(user_ids.into_iter().next())
+	}
+
+	async fn message_by_dm_msg(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+	) -> anyhow::Result<Option<RelayedMessage>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM messages
+			WHERE user_id = ? AND dm_msg_id = ?
+			ORDER BY rowid DESC
+		"})
+		.bind(SqliteId(user_id))
+		.bind(SqliteId(dm_msg_id))
+		.fetch_optional(&self.connection)
+		.await?)
+	}
+
+	async fn message_by_thread_msg(
+		&self,
+		user_id: UserId,
+		thread_msg_id: MessageId,
+	) -> anyhow::Result<Option<RelayedMessage>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM messages
+			WHERE user_id = ?1 AND (
+				thread_msg_id = ?2
+				OR thread_update_msg_id = ?2
+				OR dm_msg_id IN (
+					SELECT dm_msg_id FROM message_revisions
+					WHERE user_id = ?1 AND thread_msg_id = ?2
+				)
+			)
+			ORDER BY rowid DESC
+		"})
+		.bind(SqliteId(user_id))
+		.bind(SqliteId(thread_msg_id))
+		.fetch_optional(&self.connection)
+		.await?)
+	}
+
+	async fn message_revisions(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+	) -> anyhow::Result<Vec<MessageRevision>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM message_revisions
+			WHERE user_id = ? AND dm_msg_id = ?
+			ORDER BY rowid
+		"})
+		.bind(SqliteId(user_id))
+		.bind(SqliteId(dm_msg_id))
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn insert_message(&self, msg: &RelayedMessage) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO messages (
+				user_id, dm_msg_id, thread_msg_id, thread_update_msg_id,
+				author_id, content, created_at, edited_at, deleted_at
+			)
+			VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+		"})
+		.bind(SqliteId(msg.user_id))
+		.bind(SqliteId(msg.dm_msg_id))
+		.bind(SqliteId(msg.thread_msg_id))
+		.bind(msg.thread_update_msg_id.map(SqliteId))
+		.bind(msg.author_id.map(SqliteId))
+		.bind(&msg.content)
+		.bind(msg.created_at)
+		.bind(msg.edited_at)
+		.bind(msg.deleted_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn edit_message(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+		thread_update_msg_id: Option<MessageId>,
+		content: &str,
+		edited_at: i64,
+	) -> anyhow::Result<()> {
+		let mut transaction = self.connection.begin().await?;
+
+		sqlx::query(indoc! {"
+			INSERT INTO message_revisions (user_id, dm_msg_id, thread_msg_id, content, created_at)
+			SELECT
+				user_id,
+				dm_msg_id,
+				IFNULL(thread_update_msg_id, thread_msg_id),
+				content,
+				IFNULL(edited_at, created_at)
+			FROM messages
+			WHERE user_id = ?1 AND dm_msg_id = ?2
+				AND content IS NOT NULL AND created_at IS NOT NULL
+		"})
+		.bind(SqliteId(user_id))
+		.bind(SqliteId(dm_msg_id))
+		.execute(&mut transaction)
+		.await?;
+
+		sqlx::query(indoc! {"
+			UPDATE messages
+			SET
+				thread_update_msg_id = IFNULL(?1, thread_update_msg_id),
+				content = ?2,
+				edited_at = ?3
+			WHERE user_id = ?4 AND dm_msg_id = ?5
+		"})
+		.bind(thread_update_msg_id.map(SqliteId))
+		.bind(content)
+		.bind(edited_at)
+		.bind(SqliteId(user_id))
+		.bind(SqliteId(dm_msg_id))
+		.execute(&mut transaction)
+		.await?;
+
+		transaction.commit().await?;
+		Ok(())
+	}
+
+	async fn delete_message(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+		deleted_at: i64,
+	) -> anyhow::Result<Option<RelayedMessage>> {
+		// see `delete_ticket`
+		let msgs: Vec<RelayedMessage> = sqlx::query_as(indoc! {"
+			UPDATE messages
+			SET deleted_at = ?1
+			WHERE user_id = ?2 AND dm_msg_id = ?3
+			RETURNING *
+		"})
+		.bind(deleted_at)
+		.bind(SqliteId(user_id))
+		.bind(SqliteId(dm_msg_id))
+		.fetch_all(&self.connection)
+		.await?;
+
+		Ok(msgs.into_iter().next())
+	}
+
+	async fn insert_moderation(&self, moderation: &Moderation) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO moderations (
+				user_id, guild_id, staff_id, kind, reason, expires_at, created_at
+			)
+			VALUES (?, ?, ?, ?, ?, ?, ?)
+		"})
+		.bind(SqliteId(moderation.user_id))
+		.bind(SqliteId(moderation.guild_id))
+		.bind(SqliteId(moderation.staff_id))
+		.bind(moderation.kind.name())
+		.bind(&moderation.reason)
+		.bind(moderation.expires_at)
+		.bind(moderation.created_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn moderations(&self, user_id: UserId) -> anyhow::Result<Vec<Moderation>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM moderations
+			WHERE user_id = ?
+			ORDER BY created_at DESC, rowid DESC
+		"})
+		.bind(SqliteId(user_id))
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn insert_ticket_event(&self, event: &TicketEvent) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO ticket_events (user_id, kind, staff_id, reason, created_at)
+			VALUES (?, ?, ?, ?, ?)
+		"})
+		.bind(SqliteId(event.user_id))
+		.bind(event.kind.name())
+		.bind(event.staff_id.map(SqliteId))
+		.bind(&event.reason)
+		.bind(event.created_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn ticket_events(&self, user_id: UserId) -> anyhow::Result<Vec<TicketEvent>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM ticket_events
+			WHERE user_id = ?
+			ORDER BY created_at DESC, rowid DESC
+		"})
+		.bind(SqliteId(user_id))
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn last_message_at(&self, user_id: UserId) -> anyhow::Result<Option<i64>> {
+		Ok(
+			sqlx::query_scalar("SELECT MAX(created_at) FROM messages WHERE user_id = ?")
+				.bind(SqliteId(user_id))
+				.fetch_one(&self.connection)
+				.await?,
+		)
+	}
+
+	async fn insert_staff_note(&self, note: &StaffNote) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO staff_notes (user_id, staff_id, content, created_at)
+			VALUES (?, ?, ?, ?)
+		"})
+		.bind(SqliteId(note.user_id))
+		.bind(SqliteId(note.staff_id))
+		.bind(&note.content)
+		.bind(note.created_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn staff_notes(&self, user_id: UserId) -> anyhow::Result<Vec<StaffNote>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM staff_notes
+			WHERE user_id = ?
+			ORDER BY created_at DESC, rowid DESC
+		"})
+		.bind(SqliteId(user_id))
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn reset_inactivity_timer(
+		&self,
+		user_id: UserId,
+		last_message_at: i64,
+		staff_spoke_last: bool,
+	) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO inactivity_timers (user_id, last_message_at, staff_spoke_last, warned_at)
+			VALUES (?1, ?2, ?3, NULL)
+			ON CONFLICT (user_id) DO UPDATE SET
+				last_message_at = excluded.last_message_at,
+				staff_spoke_last = excluded.staff_spoke_last,
+				warned_at = NULL
+		"})
+		.bind(SqliteId(user_id))
+		.bind(last_message_at)
+		.bind(staff_spoke_last)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn set_inactivity_warned(&self, user_id: UserId, warned_at: i64) -> anyhow::Result<()> {
+		sqlx::query("UPDATE inactivity_timers SET warned_at = ?1 WHERE user_id = ?2")
+			.bind(warned_at)
+			.bind(SqliteId(user_id))
+			.execute(&self.connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn delete_inactivity_timer(&self, user_id: UserId) -> anyhow::Result<()> {
+		sqlx::query("DELETE FROM inactivity_timers WHERE user_id = ?1")
+			.bind(SqliteId(user_id))
+			.execute(&self.connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn inactivity_timers(&self) -> anyhow::Result<Vec<InactivityTimer>> {
+		Ok(sqlx::query_as("SELECT * FROM inactivity_timers")
+			.fetch_all(&self.connection)
+			.await?)
+	}
+
+	async fn insert_filter_hit(&self, hit: &FilterHit) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO filter_hits (
+				user_id, dm_msg_id, filter, action, matched, content, created_at
+			)
+			VALUES (?, ?, ?, ?, ?, ?, ?)
+		"})
+		.bind(SqliteId(hit.user_id))
+		.bind(SqliteId(hit.dm_msg_id))
+		.bind(&hit.filter)
+		.bind(hit.action.name())
+		.bind(&hit.matched)
+		.bind(&hit.content)
+		.bind(hit.created_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn filter_hits(&self, since: i64) -> anyhow::Result<Vec<FilterHit>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM filter_hits
+			WHERE created_at >= ?
+			ORDER BY created_at DESC, rowid DESC
+		"})
+		.bind(since)
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn insert_survey_response(&self, response: &SurveyResponse) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO survey_responses (
+				thread_id, closed_at, user_id, guild_id, staff_id, rating, comment, created_at
+			)
+			VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+			ON CONFLICT (thread_id, closed_at) DO UPDATE SET
+				rating = excluded.rating,
+				created_at = excluded.created_at
+		"})
+		.bind(SqliteId(response.thread_id))
+		.bind(response.closed_at)
+		.bind(SqliteId(response.user_id))
+		.bind(SqliteId(response.guild_id))
+		.bind(response.staff_id.map(SqliteId))
+		.bind(response.rating)
+		.bind(&response.comment)
+		.bind(response.created_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn set_survey_comment(
+		&self,
+		thread_id: ChannelId,
+		closed_at: i64,
+		comment: &str,
+	) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			UPDATE survey_responses SET comment = ?
+			WHERE thread_id = ? AND closed_at = ?
+		"})
+		.bind(comment)
+		.bind(SqliteId(thread_id))
+		.bind(closed_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn survey_responses(&self) -> anyhow::Result<Vec<SurveyResponse>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM survey_responses
+			ORDER BY created_at, thread_id, closed_at
+		"})
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn insert_webhook_delivery(
+		&self,
+		url: &str,
+		event: WebhookEvent,
+		payload: &str,
+		created_at: i64,
+	) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO webhook_deliveries (url, event, payload, next_attempt_at, created_at)
+			VALUES (?1, ?2, ?3, ?4, ?4)
+		"})
+		.bind(url)
+		.bind(event.name())
+		.bind(payload)
+		.bind(created_at)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn due_webhook_deliveries(&self, now: i64) -> anyhow::Result<Vec<WebhookDelivery>> {
+		Ok(sqlx::query_as(indoc! {"
+			SELECT * FROM webhook_deliveries
+			WHERE next_attempt_at <= ?1
+			ORDER BY id
+		"})
+		.bind(now)
+		.fetch_all(&self.connection)
+		.await?)
+	}
+
+	async fn retry_webhook_delivery(
+		&self,
+		id: i64,
+		attempts: i32,
+		next_attempt_at: i64,
+	) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			UPDATE webhook_deliveries
+			SET attempts = ?1, next_attempt_at = ?2
+			WHERE id = ?3
+		"})
+		.bind(attempts)
+		.bind(next_attempt_at)
+		.bind(id)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+
+	async fn delete_webhook_delivery(&self, id: i64) -> anyhow::Result<()> {
+		sqlx::query("DELETE FROM webhook_deliveries WHERE id = ?1")
+			.bind(id)
+			.execute(&self.connection)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn user_locale(&self, user_id: UserId) -> anyhow::Result<Option<String>> {
+		Ok(
+			sqlx::query_scalar("SELECT locale FROM user_locales WHERE user_id = ?")
+				.bind(SqliteId(user_id))
+				.fetch_optional(&self.connection)
+				.await?,
+		)
+	}
+
+	async fn set_user_locale(&self, user_id: UserId, locale: &str) -> anyhow::Result<()> {
+		sqlx::query(indoc! {"
+			INSERT INTO user_locales (user_id, locale)
+			VALUES (?1, ?2)
+			ON CONFLICT (user_id) DO UPDATE SET locale = excluded.locale
+		"})
+		.bind(SqliteId(user_id))
+		.bind(locale)
+		.execute(&self.connection)
+		.await?;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,115 @@
+use super::{
+	FilterHit, InactivityTimer, MessageRevision, Moderation, RelayedMessage, StaffNote,
+	SurveyResponse, Ticket, TicketEvent, WebhookDelivery,
+};
+use crate::config::WebhookEvent;
+use twilight::id::{ChannelId, GuildId, MessageId, UserId};
+
+/// A place to store tickets and messages.
+///
+/// Implementations only run queries. Caching is handled by [`Database`](super::Database), so
+/// nothing should call these methods directly.
+#[async_trait::async_trait]
+pub trait Storage: Send + Sync {
+	async fn close(&self);
+
+	async fn ticket_by_user(&self, user_id: UserId) -> anyhow::Result<Option<Ticket>>;
+	async fn ticket_by_dm_channel(
+		&self,
+		dm_channel_id: ChannelId,
+	) -> anyhow::Result<Option<Ticket>>;
+	async fn ticket_by_thread(&self, thread_id: ChannelId) -> anyhow::Result<Option<Ticket>>;
+	async fn insert_ticket(&self, ticket: &Ticket) -> anyhow::Result<()>;
+	async fn set_ticket_open(&self, user_id: UserId, is_open: bool) -> anyhow::Result<()>;
+	async fn move_ticket(
+		&self,
+		user_id: UserId,
+		guild_id: GuildId,
+		thread_id: ChannelId,
+	) -> anyhow::Result<()>;
+	async fn set_ticket_blocked(&self, user_id: UserId, blocked: bool) -> anyhow::Result<()>;
+	/// Returns the user whose ticket was deleted, if there was one.
+	async fn delete_ticket(&self, thread_id: ChannelId) -> anyhow::Result<Option<UserId>>;
+
+	async fn message_by_dm_msg(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+	) -> anyhow::Result<Option<RelayedMessage>>;
+	async fn message_by_thread_msg(
+		&self,
+		user_id: UserId,
+		thread_msg_id: MessageId,
+	) -> anyhow::Result<Option<RelayedMessage>>;
+	async fn message_revisions(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+	) -> anyhow::Result<Vec<MessageRevision>>;
+	async fn insert_message(&self, msg: &RelayedMessage) -> anyhow::Result<()>;
+	async fn edit_message(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+		thread_update_msg_id: Option<MessageId>,
+		content: &str,
+		edited_at: i64,
+	) -> anyhow::Result<()>;
+	async fn delete_message(
+		&self,
+		user_id: UserId,
+		dm_msg_id: MessageId,
+		deleted_at: i64,
+	) -> anyhow::Result<Option<RelayedMessage>>;
+
+	async fn insert_moderation(&self, moderation: &Moderation) -> anyhow::Result<()>;
+	async fn moderations(&self, user_id: UserId) -> anyhow::Result<Vec<Moderation>>;
+
+	async fn insert_ticket_event(&self, event: &TicketEvent) -> anyhow::Result<()>;
+	async fn ticket_events(&self, user_id: UserId) -> anyhow::Result<Vec<TicketEvent>>;
+	async fn last_message_at(&self, user_id: UserId) -> anyhow::Result<Option<i64>>;
+
+	async fn insert_staff_note(&self, note: &StaffNote) -> anyhow::Result<()>;
+	async fn staff_notes(&self, user_id: UserId) -> anyhow::Result<Vec<StaffNote>>;
+
+	async fn reset_inactivity_timer(
+		&self,
+		user_id: UserId,
+		last_message_at: i64,
+		staff_spoke_last: bool,
+	) -> anyhow::Result<()>;
+	async fn set_inactivity_warned(&self, user_id: UserId, warned_at: i64) -> anyhow::Result<()>;
+	async fn delete_inactivity_timer(&self, user_id: UserId) -> anyhow::Result<()>;
+	async fn inactivity_timers(&self) -> anyhow::Result<Vec<InactivityTimer>>;
+
+	async fn insert_filter_hit(&self, hit: &FilterHit) -> anyhow::Result<()>;
+	async fn filter_hits(&self, since: i64) -> anyhow::Result<Vec<FilterHit>>;
+
+	async fn insert_survey_response(&self, response: &SurveyResponse) -> anyhow::Result<()>;
+	async fn set_survey_comment(
+		&self,
+		thread_id: ChannelId,
+		closed_at: i64,
+		comment: &str,
+	) -> anyhow::Result<()>;
+	async fn survey_responses(&self) -> anyhow::Result<Vec<SurveyResponse>>;
+
+	async fn insert_webhook_delivery(
+		&self,
+		url: &str,
+		event: WebhookEvent,
+		payload: &str,
+		created_at: i64,
+	) -> anyhow::Result<()>;
+	async fn due_webhook_deliveries(&self, now: i64) -> anyhow::Result<Vec<WebhookDelivery>>;
+	async fn retry_webhook_delivery(
+		&self,
+		id: i64,
+		attempts: i32,
+		next_attempt_at: i64,
+	) -> anyhow::Result<()>;
+	async fn delete_webhook_delivery(&self, id: i64) -> anyhow::Result<()>;
+
+	async fn user_locale(&self, user_id: UserId) -> anyhow::Result<Option<String>>;
+	async fn set_user_locale(&self, user_id: UserId, locale: &str) -> anyhow::Result<()>;
+}
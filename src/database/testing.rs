@@ -0,0 +1,105 @@
+use super::Database;
+use crate::config::DatabaseConfig;
+use sqlx::{Connection, PgConnection};
+use std::{
+	env,
+	sync::{
+		atomic::{AtomicU32, Ordering},
+		Arc,
+	},
+	time::{SystemTime, UNIX_EPOCH},
+};
+use tempfile::TempDir;
+
+/// Set this to a postgres url to run the storage tests against postgres too.
+const POSTGRES_URL_VAR: &str = "MODMAIL_TEST_POSTGRES_URL";
+
+/// A new, empty database for one test.
+pub struct TestDatabase {
+	pub backend: &'static str,
+	pub db: Arc<Database>,
+	cleanup: Cleanup,
+}
+
+enum Cleanup {
+	/// The directory is deleted when this is dropped.
+	Sqlite {
+		_dir: TempDir,
+	},
+	Postgres {
+		url: String,
+		schema: String,
+	},
+}
+
+/// A database for every backend that can be tested.
+///
+/// Postgres is skipped unless [`POSTGRES_URL_VAR`] is set. Each test gets its own schema there,
+/// so tests can run at the same time.
+pub async fn databases() -> Vec<TestDatabase> {
+	let dir = tempfile::tempdir().unwrap();
+	let sqlite = Database::connect(&DatabaseConfig::Sqlite {
+		path: dir.path().join("modmail.db"),
+	})
+	.await
+	.unwrap();
+	let mut databases = vec![TestDatabase {
+		backend: "sqlite",
+		db: Arc::new(sqlite),
+		cleanup: Cleanup::Sqlite { _dir: dir },
+	}];
+
+	if let Ok(url) = env::var(POSTGRES_URL_VAR) {
+		databases.push(postgres(url).await);
+	}
+
+	databases
+}
+
+async fn postgres(url: String) -> TestDatabase {
+	static COUNT: AtomicU32 = AtomicU32::new(0);
+	let nanos = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.unwrap()
+		.subsec_nanos();
+	let schema = format!(
+		"modmail_test_{}_{nanos}_{}",
+		std::process::id(),
+		COUNT.fetch_add(1, Ordering::Relaxed)
+	);
+
+	let mut connection = PgConnection::connect(&url).await.unwrap();
+	sqlx::query(&format!("CREATE SCHEMA {schema}"))
+		.execute(&mut connection)
+		.await
+		.unwrap();
+	connection.close().await.unwrap();
+
+	let separator = if url.contains('?') { '&' } else { '?' };
+	let postgres = Database::connect(&DatabaseConfig::Postgres {
+		url: format!("{url}{separator}options=-c%20search_path%3D{schema}"),
+	})
+	.await
+	.unwrap();
+
+	TestDatabase {
+		backend: "postgres",
+		db: Arc::new(postgres),
+		cleanup: Cleanup::Postgres { url, schema },
+	}
+}
+
+impl TestDatabase {
+	/// Closes the database and deletes everything the test put in it.
+	pub async fn finish(self) {
+		self.db.close().await;
+		if let Cleanup::Postgres { url, schema } = self.cleanup {
+			let mut connection = PgConnection::connect(&url).await.unwrap();
+			sqlx::query(&format!("DROP SCHEMA {schema} CASCADE"))
+				.execute(&mut connection)
+				.await
+				.unwrap();
+			connection.close().await.unwrap();
+		}
+	}
+}
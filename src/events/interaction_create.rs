@@ -1,16 +1,66 @@
-use crate::{bot::Bot, commands};
+use crate::{
+	bot::{
+		Bot, GUILD_PICKER_ID, PROFILE_ID_PREFIX, REOPEN_ID, SURVEY_COMMENT_ID_PREFIX,
+		SURVEY_ID_PREFIX,
+	},
+	commands,
+};
 use std::sync::Arc;
 use twilight::model::{
-	application::interaction::InteractionData, gateway::payload::incoming::InteractionCreate,
+	application::interaction::{Interaction, InteractionData},
+	gateway::payload::incoming::InteractionCreate,
 };
 
 pub async fn handle(bot: Arc<Bot>, mut interaction: InteractionCreate) {
-	let Some(InteractionData::ApplicationCommand(data)) = interaction.data.take() else {
-		return;
-	};
+	if let Err(err) = remember_locale(&bot, &interaction).await {
+		error!(?err);
+	}
 
 	// TODO: add better information to this
-	if let Err(err) = commands::handle_command(&bot, interaction.0, *data).await {
+	let result = match interaction.data.take() {
+		Some(InteractionData::ApplicationCommand(data)) => {
+			commands::handle_command(&bot, interaction.0, *data).await
+		},
+		Some(InteractionData::MessageComponent(data)) if data.custom_id == GUILD_PICKER_ID => {
+			bot.pick_server(interaction.0, data).await
+		},
+		Some(InteractionData::MessageComponent(data)) if data.custom_id == REOPEN_ID => {
+			bot.reopen_from_dm(interaction.0, data).await
+		},
+		Some(InteractionData::MessageComponent(data))
+			if data.custom_id.starts_with(PROFILE_ID_PREFIX) =>
+		{
+			bot.switch_profile_page(interaction.0, data).await
+		},
+		Some(InteractionData::MessageComponent(data))
+			if data.custom_id.starts_with(SURVEY_ID_PREFIX) =>
+		{
+			bot.rate_ticket(interaction.0, data).await
+		},
+		Some(InteractionData::MessageComponent(data))
+			if data.custom_id.starts_with(SURVEY_COMMENT_ID_PREFIX) =>
+		{
+			bot.ask_survey_comment(interaction.0, data).await
+		},
+		Some(InteractionData::ModalSubmit(data))
+			if data.custom_id.starts_with(SURVEY_COMMENT_ID_PREFIX) =>
+		{
+			bot.save_survey_comment(interaction.0, data).await
+		},
+		_ => return,
+	};
+	if let Err(err) = result {
 		error!(?err);
 	}
 }
+
+/// Saves the user's locale, so that messages sent to them later can be translated.
+async fn remember_locale(bot: &Bot, interaction: &Interaction) -> anyhow::Result<()> {
+	let (Some(user_id), Some(locale)) = (interaction.author_id(), &interaction.locale) else {
+		return Ok(());
+	};
+	if bot.db.user_locale(user_id).await?.as_ref() != Some(locale) {
+		bot.db.set_user_locale(user_id, locale).await?;
+	}
+	Ok(())
+}
@@ -1,13 +1,11 @@
-use crate::{
-	bot::Bot,
-	util::{SqliteId, RED},
-};
+use crate::{bot::Bot, util::RED};
 use anyhow::Context;
-use sqlx::Row;
-use std::sync::Arc;
+use std::{
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
 use twilight::{
-	id::MessageId, model::gateway::payload::incoming::MessageDelete,
-	util::builder::embed::EmbedBuilder,
+	model::gateway::payload::incoming::MessageDelete, util::builder::embed::EmbedBuilder,
 };
 
 pub async fn handle(bot: Arc<Bot>, dm_msg_deleted: MessageDelete) -> anyhow::Result<()> {
@@ -25,49 +23,28 @@ pub async fn handle(bot: Arc<Bot>, dm_msg_deleted: MessageDelete) -> anyhow::Res
 		None => return Ok(()),
 	};
 
-	let thread_msg_id = sqlx::query(indoc! {"
-		DELETE FROM messages
-		WHERE user_id = ? AND dm_msg_id = ?
-		RETURNING IFNULL(thread_update_msg_id, thread_msg_id)
-	"})
-	.bind(SqliteId(ticket.user_id))
-	.bind(SqliteId(dm_msg_deleted.id))
-	.try_map(|row| {
-		let id: SqliteId<MessageId> = row.try_get(0)?;
-		Ok(id.0)
-	})
-	.fetch_optional(&bot.db.connection)
-	.await?
-	.context("missing thread message id")?;
-
-	let thread_msg = bot
-		.http
-		.message(ticket.thread_id, thread_msg_id)
+	let deleted_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+	let msg = bot
+		.db
+		.delete_message(ticket.user_id, dm_msg_deleted.id, deleted_at)
 		.await?
-		.model()
-		.await?;
+		.context("missing deleted message")?;
 
 	// the bot's messages can only be deleted with /delete, so staff already know about it.
 	// don't send a duplicate "hey this message was deleted" notification
-	if thread_msg.interaction.is_some() {
+	if msg.is_from_staff() {
 		return Ok(());
 	}
 
 	// include the deleted content instead of forcing staff to click the reply
 	let mut embed = EmbedBuilder::new().color(RED).title("🗑️ Message deleted");
-	if let Some(content) = thread_msg
-		.embeds
-		.into_iter()
-		.next()
-		.context("missing embed")?
-		.description
-	{
+	if let Some(content) = msg.content.as_ref().filter(|content| !content.is_empty()) {
 		embed = embed.description(content);
 	}
 
 	bot.http
 		.create_message(ticket.thread_id)
-		.reply(thread_msg_id)
+		.reply(msg.latest_thread_msg_id())
 		.embeds(&[embed.build()])?
 		.await?;
 
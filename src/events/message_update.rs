@@ -1,22 +1,25 @@
 use crate::{
-	bot::Bot,
-	util::{formatting, SqliteId, YELLOW},
+	bot::{filter_action, filter_field, spoiler, Bot},
+	config::FilterAction,
+	util::{formatting, YELLOW},
 };
 use anyhow::Context;
-use sqlx::Row;
-use std::{sync::Arc, time::Duration};
+use std::{
+	sync::Arc,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
 use twilight::{
 	http::request::channel::reaction::RequestReactionType,
-	id::MessageId,
 	model::{channel::message::MessageType, gateway::payload::incoming::MessageUpdate},
-	util::builder::embed::{EmbedBuilder, EmbedFooterBuilder},
+	util::builder::embed::{EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder},
+	validate::embed::{DESCRIPTION_LENGTH, FIELD_VALUE_LENGTH},
 };
 
 pub async fn handle(bot: Arc<Bot>, updated_msg: MessageUpdate) -> anyhow::Result<()> {
 	let ignore = updated_msg.guild_id.is_some()
-		|| updated_msg.kind.map_or(false, |kind| {
-			!matches!(kind, MessageType::Regular | MessageType::Reply)
-		});
+		|| updated_msg
+			.kind
+			.is_some_and(|kind| !matches!(kind, MessageType::Regular | MessageType::Reply));
 	if ignore {
 		return Ok(());
 	}
@@ -39,47 +42,98 @@ pub async fn handle(bot: Arc<Bot>, updated_msg: MessageUpdate) -> anyhow::Result
 		None => return Ok(()),
 	};
 
-	let reply_to_thread_msg_id = sqlx::query(indoc! {"
-		SELECT IFNULL(thread_update_msg_id, thread_msg_id) FROM messages
-		WHERE user_id = ? AND dm_msg_id = ?
-		ORDER BY rowid DESC
-	"})
-	.bind(SqliteId(user.id))
-	.bind(SqliteId(updated_msg.id))
-	.try_map(|row| {
-		let id: SqliteId<MessageId> = row.try_get(0)?;
-		Ok(id.0)
-	})
-	.fetch_optional(&bot.db.connection)
-	.await?
-	.context("missing data for edited message")?;
+	let msg = bot
+		.db
+		.message_by_dm_msg(user.id, updated_msg.id)
+		.await?
+		.context("missing data for edited message")?;
+
+	// link previews and other embed changes also trigger updates
+	if msg.content.as_ref() == Some(&content) {
+		// previews usually show up just after the message is sent
+		let previews = match &updated_msg.embeds {
+			Some(embeds) if updated_msg.edited_timestamp.is_none() => {
+				formatting::embed_previews(embeds, &[])
+			},
+			_ => Vec::new(),
+		};
+		if !previews.is_empty() {
+			bot.http
+				.create_message(ticket.thread_id)
+				.reply(msg.latest_thread_msg_id())
+				.embeds(&previews)?
+				.await?;
+		}
+		return Ok(());
+	}
+
+	let edited_at = match updated_msg.edited_timestamp {
+		Some(timestamp) => timestamp.as_secs(),
+		None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+	};
+
+	// editing a message shouldn't get it past the filters
+	let filter_matches = bot.check_filters(user.id, updated_msg.id, &content).await?;
+	let filter_action = filter_action(&filter_matches);
+	if filter_action == Some(FilterAction::Block) {
+		return bot
+			.report_filtered(
+				&ticket,
+				updated_msg.channel_id,
+				&content,
+				&filter_matches,
+				true,
+			)
+			.await;
+	}
+	let is_spoiler = filter_action == Some(FilterAction::Spoiler);
 
-	let embed = EmbedBuilder::new()
+	let mut embed = EmbedBuilder::new()
 		.color(YELLOW)
 		.author(formatting::embed_author(user.id, &user.name, user.avatar))
-		.description(content)
-		.footer(EmbedFooterBuilder::new("✏️ Edited"))
-		.build();
+		.description(if is_spoiler {
+			spoiler(&content, DESCRIPTION_LENGTH)
+		} else {
+			content.clone()
+		})
+		.footer(EmbedFooterBuilder::new("✏️ Edited"));
+
+	// show what changed since the previous version
+	if let Some(old_content) = &msg.content {
+		let diff = formatting::text_diff(old_content, &content);
+		let diff = if is_spoiler {
+			"Hidden by a filter, use `/history` to see every version.".to_owned()
+		} else if diff.chars().count() > FIELD_VALUE_LENGTH {
+			"Too long to show here, use `/history` to see every version.".to_owned()
+		} else {
+			diff
+		};
+		embed = embed.field(EmbedFieldBuilder::new("Changes", diff));
+	}
+	if !filter_matches.is_empty() {
+		embed = embed.field(filter_field(&filter_matches));
+	}
+
+	let embed = embed.build();
 
 	let thread_update_msg = bot
 		.http
 		.create_message(ticket.thread_id)
-		.reply(reply_to_thread_msg_id)
+		.reply(msg.latest_thread_msg_id())
 		.embeds(&[embed])?
 		.await?
 		.model()
 		.await?;
 
-	sqlx::query(indoc! {"
-		UPDATE messages
-		SET thread_update_msg_id = ?
-		WHERE user_id = ? AND dm_msg_id = ?
-	"})
-	.bind(SqliteId(thread_update_msg.id))
-	.bind(SqliteId(user.id))
-	.bind(SqliteId(updated_msg.id))
-	.execute(&bot.db.connection)
-	.await?;
+	bot.db
+		.edit_message(
+			user.id,
+			updated_msg.id,
+			Some(thread_update_msg.id),
+			&content,
+			edited_at,
+		)
+		.await?;
 
 	// let the user know that their edit was sent
 	static EDITED_REACTION: RequestReactionType = RequestReactionType::Unicode { name: "✏️" };
@@ -23,9 +23,15 @@ pub async fn handle_event(bot: Arc<Bot>, event: Event) -> anyhow::Result<()> {
 		Event::Ready(_) => ready::handle(bot)?,
 		Event::GuildCreate(guild) => guild_create::handle(bot, *guild)?,
 		Event::GuildAuditLogEntryCreate(entry) => new_audit_log_entry::handle(bot, *entry).await?,
+		Event::ReactionAdd(reaction) => bot.relay_reaction(&reaction, true).await?,
+		Event::ReactionRemove(reaction) => bot.relay_reaction(&reaction, false).await?,
+		Event::TypingStart(typing) => bot.relay_typing(&typing).await?,
 
 		Event::ThreadUpdate(channel) => {
-			if channel.parent_id != Some(bot.config.forum_channel_id) {
+			let in_forum = channel
+				.parent_id
+				.is_some_and(|parent_id| bot.config.server_by_forum(parent_id).is_some());
+			if !in_forum {
 				return Ok(());
 			}
 			let Some(metadata) = &channel.thread_metadata else {
@@ -46,11 +52,12 @@ pub async fn handle_event(bot: Arc<Bot>, event: Event) -> anyhow::Result<()> {
 		},
 
 		Event::ThreadDelete(thread) => {
-			if thread.parent_id != bot.config.forum_channel_id {
+			if bot.config.server_by_forum(thread.parent_id).is_none() {
 				return Ok(());
 			}
 			warn!("thread {} deleted", thread.id);
 			bot.db.delete_ticket(thread.id).await?;
+			bot.typing_since.remove(&thread.id);
 		},
 
 		Event::MemberRemove(info) => {
@@ -59,9 +66,21 @@ pub async fn handle_event(bot: Arc<Bot>, event: Event) -> anyhow::Result<()> {
 				Some(ticket) if !ticket.is_open || ticket.blocked => return Ok(()),
 				Some(ticket) => ticket,
 			};
+			let server = bot.config.ticket_server(ticket.guild_id);
+			if info.guild_id != server.guild_id {
+				return Ok(());
+			}
+			let locale = bot.guild_locale(server.forum_guild_id);
+			let notif_msg = bot
+				.config
+				.live()
+				.messages(locale.as_deref())
+				.user_left
+				.render(&bot.ticket_vars(&ticket));
 			bot.http
 				.create_message(ticket.thread_id)
-				.content("👋 User left the server.")?
+				.content(&notif_msg.content)?
+				.embeds(&notif_msg.embeds)?
 				.await?;
 		},
 
@@ -71,9 +90,21 @@ pub async fn handle_event(bot: Arc<Bot>, event: Event) -> anyhow::Result<()> {
 				Some(ticket) if !ticket.is_open || ticket.blocked => return Ok(()),
 				Some(ticket) => ticket,
 			};
+			let server = bot.config.ticket_server(ticket.guild_id);
+			if member.guild_id != server.guild_id {
+				return Ok(());
+			}
+			let locale = bot.guild_locale(server.forum_guild_id);
+			let notif_msg = bot
+				.config
+				.live()
+				.messages(locale.as_deref())
+				.user_rejoined
+				.render(&bot.ticket_vars(&ticket));
 			bot.http
 				.create_message(ticket.thread_id)
-				.content("👋 User rejoined the server.")?
+				.content(&notif_msg.content)?
+				.embeds(&notif_msg.embeds)?
 				.await?;
 		},
 
@@ -1,85 +1,248 @@
-use crate::bot::Bot;
+use crate::{
+	bot::Bot,
+	config::ModAction,
+	database::{Moderation, ModerationKind},
+	util::{BLURPLE, GREEN, PINK, RED, YELLOW},
+};
 use std::{fmt::Write as _, sync::Arc};
-use twilight::model::{
-	gateway::payload::incoming::GuildAuditLogEntryCreate,
-	guild::audit_log::{AuditLogChange, AuditLogEventType},
+use twilight::{
+	model::{
+		gateway::payload::incoming::GuildAuditLogEntryCreate,
+		guild::audit_log::{AffectedRole, AuditLogChange, AuditLogEventType},
+	},
+	util::{
+		builder::embed::{EmbedBuilder, EmbedFieldBuilder},
+		snowflake::Snowflake,
+	},
 };
 
 pub async fn handle(bot: Arc<Bot>, entry: GuildAuditLogEntryCreate) -> anyhow::Result<()> {
-	if entry.guild_id != Some(bot.config.guild_id) {
+	let Some(guild_id) = entry.guild_id.filter(|&id| bot.config.server(id).is_some()) else {
+		return Ok(());
+	};
+	let (Some(staff_id), Some(target_id)) = (entry.user_id, entry.target_id) else {
+		return Ok(());
+	};
+	let user_id = target_id.cast();
+
+	let Some(action) = action_of(&entry) else {
 		return Ok(());
+	};
+
+	// actions taken through the bot's commands were already logged with who used them
+	if staff_id == bot.user_id && bot.logged_mod_actions.remove(&(user_id, action)).is_some() {
+		return Ok(());
+	}
+
+	// keep a history of moderation actions, even for users without tickets
+	if let Some((kind, expires_at)) = moderation_of(&entry) {
+		bot.db
+			.insert_moderation(&Moderation {
+				user_id,
+				guild_id,
+				staff_id,
+				kind,
+				reason: entry.reason.clone(),
+				expires_at,
+				created_at: entry.id.timestamp() / 1_000,
+			})
+			.await?;
 	}
-	let (Some(user_id), Some(target_id)) = (entry.user_id, entry.target_id) else {
+
+	if !bot.config.live().mod_actions.contains(&action) {
 		return Ok(());
+	}
+
+	// only tell a ticket about what happens in the server it's for
+	let ticket = match bot.db.ticket_by_user(user_id).await? {
+		Some(ticket) if !ticket.is_open => return Ok(()),
+		Some(ticket) if bot.config.ticket_server(ticket.guild_id).guild_id != guild_id => {
+			return Ok(());
+		},
+		Some(ticket) => ticket,
+		None => return Ok(()),
 	};
-	if user_id == bot.user_id {
+
+	let Some(mut embed) = action_embed(action, &entry, &format!("<@{staff_id}>"))? else {
 		return Ok(());
+	};
+	if let Some(reason) = &entry.reason {
+		embed = embed.field(EmbedFieldBuilder::new("Reason", reason).build());
 	}
 
+	bot.http
+		.create_message(ticket.thread_id)
+		.embeds(&[embed.build()])?
+		.await?;
+
+	Ok(())
+}
+
+fn action_of(entry: &GuildAuditLogEntryCreate) -> Option<ModAction> {
 	match entry.action_type {
-		AuditLogEventType::MemberBanAdd | AuditLogEventType::MemberKick => {
-			let ticket = match bot.db.ticket_by_user(target_id.cast()).await? {
-				Some(ticket) if !ticket.is_open => return Ok(()),
-				Some(ticket) => ticket,
-				None => return Ok(()),
-			};
+		AuditLogEventType::MemberBanAdd => Some(ModAction::Ban),
+		AuditLogEventType::MemberBanRemove => Some(ModAction::Unban),
+		AuditLogEventType::MemberKick => Some(ModAction::Kick),
+		AuditLogEventType::MemberRoleUpdate => Some(ModAction::Roles),
+		AuditLogEventType::MessageDelete => Some(ModAction::MessageDelete),
+		// one kind of member update per entry
+		AuditLogEventType::MemberUpdate => entry.changes.iter().find_map(|change| match change {
+			AuditLogChange::CommunicationDisabledUntil { .. } => Some(ModAction::Timeout),
+			AuditLogChange::Nick { .. } => Some(ModAction::Nickname),
+			_ => None,
+		}),
+		_ => None,
+	}
+}
+
+/// The moderation history entry for an action, and when it expires.
+fn moderation_of(entry: &GuildAuditLogEntryCreate) -> Option<(ModerationKind, Option<i64>)> {
+	match entry.action_type {
+		AuditLogEventType::MemberBanAdd => Some((ModerationKind::Ban, None)),
+		AuditLogEventType::MemberKick => Some((ModerationKind::Kick, None)),
+		// only new timeouts, not changes to them
+		AuditLogEventType::MemberUpdate => entry.changes.iter().find_map(|change| match change {
+			AuditLogChange::CommunicationDisabledUntil {
+				old: None,
+				new: Some(expires_at),
+			} => Some((ModerationKind::Timeout, Some(expires_at.as_secs()))),
+			_ => None,
+		}),
+		_ => None,
+	}
+}
 
-			let mut notif_text = if entry.action_type == AuditLogEventType::MemberBanAdd {
-				format!("🔨 User was banned by <@{user_id}>")
-			} else {
-				format!("👢 User was kicked by <@{user_id}>")
+/// Describes an action for the ticket thread, or returns [`None`] if nothing changed.
+fn action_embed(
+	action: ModAction,
+	entry: &GuildAuditLogEntryCreate,
+	staff: &str,
+) -> anyhow::Result<Option<EmbedBuilder>> {
+	let embed = match action {
+		ModAction::Ban => EmbedBuilder::new()
+			.color(RED)
+			.description(format!("🔨 User was banned by {staff}.")),
+		ModAction::Unban => EmbedBuilder::new()
+			.color(GREEN)
+			.description(format!("🕊️ User was unbanned by {staff}.")),
+		ModAction::Kick => EmbedBuilder::new()
+			.color(YELLOW)
+			.description(format!("👢 User was kicked by {staff}.")),
+
+		ModAction::Timeout => {
+			let Some((old, new)) = entry.changes.iter().find_map(|change| match change {
+				AuditLogChange::CommunicationDisabledUntil { old, new } => Some((old, new)),
+				_ => None,
+			}) else {
+				return Ok(None);
 			};
-			match &entry.reason {
-				Some(reason) => write!(notif_text, " for `{reason}`.")?,
-				None => notif_text.push('.'),
+			match (old, new) {
+				(_, None) => EmbedBuilder::new()
+					.color(GREEN)
+					.description(format!("🔊 User's timeout was removed by {staff}.")),
+				(None, Some(expires_at)) => EmbedBuilder::new()
+					.color(YELLOW)
+					.description(format!("🔇 User was timed out by {staff}."))
+					.field(
+						EmbedFieldBuilder::new(
+							"Expires",
+							format!("<t:{}:R>", expires_at.as_secs()),
+						)
+						.inline()
+						.build(),
+					),
+				(Some(old_expires_at), Some(expires_at)) => EmbedBuilder::new()
+					.color(YELLOW)
+					.description(format!("🔇 User's timeout was changed by {staff}."))
+					.field(
+						EmbedFieldBuilder::new(
+							"Before",
+							format!("Expires <t:{}:R>", old_expires_at.as_secs()),
+						)
+						.inline()
+						.build(),
+					)
+					.field(
+						EmbedFieldBuilder::new(
+							"After",
+							format!("Expires <t:{}:R>", expires_at.as_secs()),
+						)
+						.inline()
+						.build(),
+					),
 			}
-
-			bot.http
-				.create_message(ticket.thread_id)
-				.content(&notif_text)?
-				.await?;
 		},
 
-		// mutes
-		AuditLogEventType::MemberUpdate => {
-			let Some(mute_expires_at) = entry.changes.iter().find_map(|change| {
-				if let AuditLogChange::CommunicationDisabledUntil {
-					old: None,
-					new: Some(timestamp),
-				} = change {
-					Some(timestamp)
-				} else {
-					None
+		ModAction::Roles => {
+			fn role_list(roles: &[AffectedRole]) -> String {
+				let mentions: Vec<_> = roles
+					.iter()
+					.map(|role| format!("<@&{}>", role.id))
+					.collect();
+				mentions.join(", ")
+			}
+
+			let mut embed = EmbedBuilder::new()
+				.color(BLURPLE)
+				.description(format!("🏷️ User's roles were changed by {staff}."));
+			let mut changed = false;
+			for change in &entry.changes {
+				let (name, roles) = match change {
+					AuditLogChange::RoleAdded { new, .. } => ("Added", new),
+					AuditLogChange::RoleRemoved { new, .. } => ("Removed", new),
+					_ => continue,
+				};
+				if !roles.is_empty() {
+					changed = true;
+					embed = embed.field(EmbedFieldBuilder::new(name, role_list(roles)).build());
 				}
+			}
+			if !changed {
+				return Ok(None);
+			}
+			embed
+		},
+
+		ModAction::Nickname => {
+			let Some((old, new)) = entry.changes.iter().find_map(|change| match change {
+				AuditLogChange::Nick { old, new } => Some((old, new)),
+				_ => None,
 			}) else {
-				return Ok(());
+				return Ok(None);
 			};
-
-			let ticket = match bot.db.ticket_by_user(target_id.cast()).await? {
-				Some(data) if !data.is_open => return Ok(()),
-				None => return Ok(()),
-				Some(data) => data,
+			let nickname = |nick: &Option<String>| match nick {
+				Some(nick) => format!("`{nick}`"),
+				None => "*None*".to_owned(),
 			};
+			EmbedBuilder::new()
+				.color(BLURPLE)
+				.description(format!("📝 User's nickname was changed by {staff}."))
+				.field(
+					EmbedFieldBuilder::new("Before", nickname(old))
+						.inline()
+						.build(),
+				)
+				.field(
+					EmbedFieldBuilder::new("After", nickname(new))
+						.inline()
+						.build(),
+				)
+		},
 
-			let mut notif_text = format!("🔇 User was muted by <@{user_id}>");
-			match &entry.reason {
-				Some(reason) => write!(notif_text, " for `{reason}`.")?,
-				None => notif_text.push('.'),
+		ModAction::MessageDelete => {
+			let options = entry.options.as_ref();
+			let count = options.and_then(|options| options.count.as_deref());
+			let mut description = format!(
+				"🗑️ {} message(s) from the user were deleted by {staff}",
+				count.unwrap_or("1"),
+			);
+			match options.and_then(|options| options.channel_id) {
+				Some(channel_id) => write!(description, " in <#{channel_id}>.")?,
+				None => description.push('.'),
 			}
-			write!(
-				notif_text,
-				"\nThe mute expires <t:{}:R>",
-				mute_expires_at.as_secs()
-			)?;
-
-			bot.http
-				.create_message(ticket.thread_id)
-				.content(&notif_text)?
-				.await?;
+			EmbedBuilder::new().color(PINK).description(description)
 		},
+	};
 
-		_ => (),
-	}
-
-	Ok(())
+	Ok(Some(embed))
 }
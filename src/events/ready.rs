@@ -15,12 +15,19 @@ pub fn handle(bot: Arc<Bot>) -> anyhow::Result<()> {
 	}
 	STARTUP_RAN.store(true, Ordering::Release);
 
-	let result = bot
-		.discord_websocket
-		.command(&RequestGuildMembers::builder(bot.config.guild_id).query("", None));
-	if let Err(err) = result {
-		error!(?err, "error requesting guild members");
-		bot.stop();
+	bot.start_inactivity_checks();
+	bot.start_webhook_deliveries();
+	bot.start_cleanups();
+
+	for server in &bot.config.servers {
+		let result = bot
+			.discord_websocket
+			.command(&RequestGuildMembers::builder(server.guild_id).query("", None));
+		if let Err(err) = result {
+			error!(?err, "error requesting guild members");
+			bot.stop();
+			break;
+		}
 	}
 
 	Ok(())
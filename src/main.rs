@@ -8,6 +8,7 @@ extern crate indoc;
 #[macro_use]
 extern crate tracing;
 
+use clap::Parser;
 use std::{
 	process::ExitCode,
 	sync::{atomic::Ordering, Arc},
@@ -15,23 +16,33 @@ use std::{
 use twilight::gateway::{error::ReceiveMessageErrorType, Event};
 
 mod bot;
+mod cli;
 mod commands;
 mod config;
 mod database;
 mod events;
 mod logging;
+mod scanner;
 mod util;
 
-use crate::bot::Bot;
+use crate::{
+	bot::Bot,
+	cli::{Args, Command},
+};
 
 fn main() -> ExitCode {
+	let args = Args::parse();
+	if let Some(Command::CheckConfig { online }) = args.command {
+		return config::check(&args, online);
+	}
+
 	logging::init();
 
 	let result = tokio::runtime::Builder::new_multi_thread()
 		.enable_all()
 		.build()
 		.expect("failed to build tokio runtime")
-		.block_on(run());
+		.block_on(run(args));
 
 	match result {
 		Ok(_) => ExitCode::SUCCESS,
@@ -42,8 +53,8 @@ fn main() -> ExitCode {
 	}
 }
 
-async fn run() -> anyhow::Result<()> {
-	let (bot, mut shard) = Bot::init().await?;
+async fn run(args: Args) -> anyhow::Result<()> {
+	let (bot, mut shard) = Bot::init(&args).await?;
 	let bot = Arc::new(bot);
 
 	// handle shutdown gracefully
@@ -58,6 +69,28 @@ async fn run() -> anyhow::Result<()> {
 		}
 	});
 
+	// reload the config on SIGHUP
+	let bot_handle = Arc::clone(&bot);
+	let sighup_handler = tokio::spawn(async move {
+		#[cfg(unix)]
+		{
+			use tokio::signal::unix::{signal, SignalKind};
+			let mut hangups = match signal(SignalKind::hangup()) {
+				Ok(hangups) => hangups,
+				Err(err) => {
+					error!(?err, "unable to listen for SIGHUP");
+					return;
+				},
+			};
+			while hangups.recv().await.is_some() {
+				info!("SIGHUP received");
+				if let Err(err) = bot_handle.reload_config() {
+					error!(?err, "unable to reload config");
+				}
+			}
+		}
+	});
+
 	// event loop
 	loop {
 		match shard.next_event().await {
@@ -91,6 +124,7 @@ async fn run() -> anyhow::Result<()> {
 
 	bot.tasks.finished().await;
 	ctrl_c_handler.abort();
+	sighup_handler.abort();
 	bot.finish_shutdown().await;
 
 	Ok(())
@@ -0,0 +1,75 @@
+use super::{ScanResult, Scanner};
+use std::path::PathBuf;
+use tokio::{
+	io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+	net::TcpStream,
+};
+
+#[cfg(unix)]
+use tokio::net::UnixStream;
+
+/// How much of the file is sent at once.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Scans files with a ClamAV daemon.
+///
+/// See <https://docs.clamav.net/manual/Usage/Scanning.html#clamd> for the protocol.
+pub struct Clamd {
+	pub socket: Option<PathBuf>,
+	pub address: Option<String>,
+}
+
+#[async_trait::async_trait]
+impl Scanner for Clamd {
+	async fn scan(&self, data: &[u8]) -> anyhow::Result<ScanResult> {
+		let reply = match (&self.socket, &self.address) {
+			#[cfg(unix)]
+			(Some(socket), _) => instream(UnixStream::connect(socket).await?, data).await?,
+			// the config doesn't allow this
+			#[cfg(not(unix))]
+			(Some(_), _) => bail!("clamd sockets only work on unix"),
+			(None, Some(address)) => instream(TcpStream::connect(address).await?, data).await?,
+			(None, None) => bail!("clamd has no socket or address"),
+		};
+
+		// like `stream: OK` or `stream: Eicar-Signature FOUND`
+		let reply = reply.split('\0').next().unwrap_or_default().trim();
+		let result = reply.strip_prefix("stream:").unwrap_or(reply).trim();
+		if result == "OK" {
+			Ok(ScanResult::Clean)
+		} else if let Some(signature) = result.strip_suffix("FOUND") {
+			Ok(ScanResult::Infected(signature.trim().to_owned()))
+		} else {
+			bail!("clamd replied: {reply}")
+		}
+	}
+}
+
+/// Sends a file to clamd and returns its reply.
+async fn instream<S>(mut stream: S, data: &[u8]) -> anyhow::Result<String>
+where
+	S: AsyncRead + AsyncWrite + Unpin,
+{
+	stream.write_all(b"zINSTREAM\0").await?;
+	for chunk in data.chunks(CHUNK_SIZE) {
+		stream
+			.write_all(&(chunk.len() as u32).to_be_bytes())
+			.await?;
+		stream.write_all(chunk).await?;
+	}
+	// an empty chunk ends the file
+	stream.write_all(&0u32.to_be_bytes()).await?;
+	stream.flush().await?;
+
+	// replies to commands starting with `z` end with a null byte
+	let mut reply = Vec::new();
+	let mut buf = [0; 256];
+	loop {
+		let read = stream.read(&mut buf).await?;
+		reply.extend_from_slice(&buf[..read]);
+		if read == 0 || reply.contains(&0) {
+			break;
+		}
+	}
+	Ok(String::from_utf8_lossy(&reply).into_owned())
+}
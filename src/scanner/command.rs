@@ -0,0 +1,45 @@
+use super::{ScanResult, Scanner};
+use std::process::Stdio;
+use tokio::{io::AsyncWriteExt, process};
+
+/// Scans files with a program, which is given the file on stdin.
+///
+/// It should exit with 0 if the file is clean, or 1 if it isn't, with anything it prints being
+/// the reason. Any other exit code is an error.
+pub struct Command {
+	pub command: Vec<String>,
+}
+
+#[async_trait::async_trait]
+impl Scanner for Command {
+	async fn scan(&self, data: &[u8]) -> anyhow::Result<ScanResult> {
+		let Some((program, args)) = self.command.split_first() else {
+			bail!("the scanner command is empty");
+		};
+		let mut child = process::Command::new(program)
+			.args(args)
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::null())
+			.kill_on_drop(true)
+			.spawn()?;
+
+		let mut stdin = child.stdin.take().expect("stdin should be piped");
+		// the program might not read all of it before deciding
+		if let Err(err) = stdin.write_all(data).await {
+			debug!(?err, "scanner didn't read the whole file");
+		}
+		drop(stdin);
+
+		let output = child.wait_with_output().await?;
+		let reason = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+		match output.status.code() {
+			Some(0) => Ok(ScanResult::Clean),
+			Some(1) if reason.is_empty() => {
+				Ok(ScanResult::Infected("flagged by scanner".to_owned()))
+			},
+			Some(1) => Ok(ScanResult::Infected(reason)),
+			_ => bail!("scanner exited with {}", output.status),
+		}
+	}
+}
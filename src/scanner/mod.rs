@@ -0,0 +1,36 @@
+use crate::config::ScannerConfig;
+use std::time::Duration;
+
+mod clamd;
+mod command;
+
+/// How long a scan can take before the file is treated as unsafe.
+pub const SCAN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Files are downloaded into memory to be scanned, so bigger ones are treated as unsafe too.
+pub const MAX_SCAN_SIZE: usize = 100 * 1024 * 1024;
+
+/// Something that checks attachments before staff can open them.
+#[async_trait::async_trait]
+pub trait Scanner: Send + Sync {
+	async fn scan(&self, data: &[u8]) -> anyhow::Result<ScanResult>;
+}
+
+pub enum ScanResult {
+	Clean,
+	/// The file shouldn't be opened, with what the scanner found.
+	Infected(String),
+}
+
+/// Creates the scanner that the config asks for.
+pub fn connect(config: &ScannerConfig) -> Box<dyn Scanner> {
+	match config {
+		ScannerConfig::Clamd { socket, address } => Box::new(clamd::Clamd {
+			socket: socket.clone(),
+			address: address.clone(),
+		}),
+		ScannerConfig::Command { command } => Box::new(command::Command {
+			command: command.clone(),
+		}),
+	}
+}
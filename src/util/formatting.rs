@@ -1,11 +1,13 @@
 use super::BLURPLE;
-use crate::bot::Bot;
-use anyhow::Context;
+use crate::{bot::Bot, database::TicketEventKind};
+use similar::{ChangeTag, TextDiff};
+use std::fmt::Write as _;
 use twilight::{
+	http::error::ErrorType,
 	id::{GuildId, UserId},
 	model::{
 		channel::message::{
-			embed::EmbedAuthor,
+			embed::{EmbedAuthor, EmbedFooter},
 			sticker::{MessageSticker, StickerFormatType},
 			Embed,
 		},
@@ -15,65 +17,114 @@ use twilight::{
 		builder::embed::{EmbedAuthorBuilder, EmbedBuilder, EmbedFieldBuilder, ImageSource},
 		snowflake::Snowflake,
 	},
+	validate::{
+		embed::{chars as embed_chars, EMBED_TOTAL_LENGTH, TITLE_LENGTH},
+		message::EMBED_COUNT_LIMIT,
+	},
 };
 
-pub async fn user_info_embed(bot: &Bot, user_id: UserId) -> anyhow::Result<Embed> {
-	let user = bot.discord_cache.user(user_id).context("user not found")?;
-	let member = bot
+/// Shows who a user is, and what they look like in a server.
+pub async fn user_info_embed(
+	bot: &Bot,
+	user_id: UserId,
+	guild_id: GuildId,
+) -> anyhow::Result<Embed> {
+	// copy what's needed, so cache references aren't held over await points
+	let cached_user = bot
 		.discord_cache
-		.member(bot.config.guild_id, user_id)
-		.context("member not found")?;
-
-	let mut embed = EmbedBuilder::new()
-		.color(BLURPLE)
-		.description(format!(
-			"**Joined server <t:{0}:R>** on <t:{0}>\n**Registered <t:{1}:R>** on <t:{1}>",
-			member.joined_at().as_secs(),
-			user_id.timestamp() / 1_000,
-		))
-		.field(
-			EmbedFieldBuilder::new("User ID", format!("{user_id}"))
-				.inline()
-				.build(),
-		);
-
-	if let Some(hash) = user.avatar {
-		embed = embed.thumbnail(ImageSource::url(user_avatar_url(user_id, hash))?);
-	}
+		.user(user_id)
+		.map(|user| (user.name.clone(), user.avatar));
+	let (name, avatar) = match cached_user {
+		Some(user) => user,
+		None => {
+			let user = bot.http.user(user_id).await?.model().await?;
+			(user.name, user.avatar)
+		},
+	};
 
-	let mut author = EmbedAuthorBuilder::new(&user.name);
-	if let Some(hash) = member.avatar() {
-		author = author.icon_url(ImageSource::url(member_avatar_url(
-			bot.config.guild_id,
-			user_id,
-			hash,
-		))?);
-	}
-	embed = embed.author(author.build());
+	// members might not be cached yet, or might have left
+	let cached_member = bot
+		.discord_cache
+		.member(guild_id, user_id)
+		.map(|member| (member.joined_at(), member.avatar(), member.roles().to_vec()));
+	let member = match cached_member {
+		Some(member) => Some(member),
+		None => match bot.http.guild_member(guild_id, user_id).await {
+			Ok(response) => {
+				let member = response.model().await?;
+				Some((member.joined_at, member.avatar, member.roles))
+			},
+			Err(err) if is_not_found(&err) => None,
+			Err(err) => return Err(err.into()),
+		},
+	};
 
+	let registered = format!(
+		"**Registered <t:{0}:R>** on <t:{0}>",
+		user_id.timestamp() / 1_000
+	);
+	let mut embed = EmbedBuilder::new().color(BLURPLE);
+	let mut author = EmbedAuthorBuilder::new(&name);
 	let mut roles_list = String::new();
-	let mut is_first = true;
-	for &role_id in member.roles() {
-		// skip @everyone
-		if role_id.cast() == bot.config.guild_id {
-			continue;
-		}
-		if let Some(role) = bot.discord_cache.role(role_id) {
-			if is_first {
-				is_first = false;
-			} else {
-				roles_list.push_str(", ");
+	match &member {
+		Some((joined_at, member_avatar, roles)) => {
+			embed = embed.description(format!(
+				"**Joined server <t:{0}:R>** on <t:{0}>\n{registered}",
+				joined_at.as_secs(),
+			));
+
+			if let Some(hash) = *member_avatar {
+				author = author.icon_url(ImageSource::url(member_avatar_url(
+					guild_id, user_id, hash,
+				))?);
 			}
-			roles_list.push_str(&role.name);
-		}
+
+			let mut is_first = true;
+			for &role_id in roles {
+				// skip @everyone
+				if role_id.cast() == guild_id {
+					continue;
+				}
+				if let Some(role) = bot.discord_cache.role(role_id) {
+					if is_first {
+						is_first = false;
+					} else {
+						roles_list.push_str(", ");
+					}
+					roles_list.push_str(&role.name);
+				}
+			}
+		},
+		None => {
+			let status = match bot.http.ban(guild_id, user_id).await {
+				Ok(_) => "🔨 Banned",
+				Err(err) if is_not_found(&err) => "Not banned",
+				// probably missing permissions
+				Err(_) => "Unknown if banned",
+			};
+			embed = embed.description(format!("**Not in server** ({status})\n{registered}"));
+		},
 	}
+	embed = embed.field(
+		EmbedFieldBuilder::new("User ID", format!("{user_id}"))
+			.inline()
+			.build(),
+	);
 
-	// don't hold cache references over await point
-	drop(user);
-	drop(member);
+	if let Some(hash) = avatar {
+		embed = embed.thumbnail(ImageSource::url(user_avatar_url(user_id, hash))?);
+	}
+	embed = embed.author(author.build());
 
-	if let Ok(dm_channel) = bot.http.create_private_channel(user_id).await {
-		let dm_channel_id = dm_channel.model().await?.id;
+	// the ticket already knows it, which saves asking discord
+	let dm_channel_id = match bot.db.ticket_by_user(user_id).await? {
+		Some(ticket) => Some(ticket.dm_channel_id),
+		None => match bot.http.create_private_channel(user_id).await {
+			Ok(dm_channel) => Some(dm_channel.model().await?.id),
+			Err(_) => None,
+		},
+	};
+	if let Some(dm_channel_id) = dm_channel_id {
 		embed = embed.field(
 			EmbedFieldBuilder::new("DM channel ID", format!("{dm_channel_id}"))
 				.inline()
@@ -85,9 +136,34 @@ pub async fn user_info_embed(bot: &Bot, user_id: UserId) -> anyhow::Result<Embed
 		embed = embed.field(EmbedFieldBuilder::new("Roles", roles_list).build());
 	}
 
+	// everything else is on the other pages of the profile
+	let events = bot.db.ticket_events(user_id).await?;
+	let count_events = |kind| events.iter().filter(|event| event.kind == kind).count();
+	let moderations = bot
+		.db
+		.moderations(user_id)
+		.await?
+		.into_iter()
+		.filter(|moderation| moderation.guild_id == guild_id)
+		.count();
+	let mut history = format!(
+		"**Tickets:** {}\n**Blocks:** {}\n**Moderation actions:** {moderations}\n**Notes:** {}",
+		count_events(TicketEventKind::Open),
+		count_events(TicketEventKind::Block),
+		bot.db.staff_notes(user_id).await?.len(),
+	);
+	if let Some(timestamp) = bot.db.last_message_at(user_id).await? {
+		write!(history, "\n**Last contacted** <t:{timestamp}:R>")?;
+	}
+	embed = embed.field(EmbedFieldBuilder::new("History", history).build());
+
 	Ok(embed.build())
 }
 
+fn is_not_found(err: &twilight::http::Error) -> bool {
+	matches!(err.kind(), ErrorType::Response { status, .. } if status.get() == 404)
+}
+
 pub fn user_avatar_url(user_id: UserId, hash: ImageHash) -> String {
 	let mut url = format!("https://cdn.discordapp.com/avatars/{user_id}/{hash}");
 	if hash.is_animated() {
@@ -136,3 +212,138 @@ pub fn embed_author(user_id: UserId, name: &str, avatar: Option<ImageHash>) -> E
 	}
 	author.build()
 }
+
+/// Rebuilds an embed from a user's message, like a link preview, so it can be sent to a thread.
+///
+/// Returns [`None`] if there's nothing that can be shown.
+pub fn embed_preview(embed: &Embed) -> Option<Embed> {
+	let mut preview = EmbedBuilder::new();
+	if let Some(color) = embed.color {
+		preview = preview.color(color);
+	}
+	if let Some(author) = &embed.author {
+		preview = preview.author(EmbedAuthor {
+			proxy_icon_url: None,
+			..author.clone()
+		});
+	}
+	// images and videos often only have a url, which isn't shown without a title
+	let title = embed.title.clone().or_else(|| {
+		embed
+			.provider
+			.as_ref()
+			.and_then(|provider| provider.name.clone())
+			.or_else(|| embed.url.clone())
+	});
+	if let Some(title) = title {
+		preview = preview.title(truncate(&title, TITLE_LENGTH));
+	}
+	if let Some(url) = &embed.url {
+		preview = preview.url(url);
+	}
+	if let Some(description) = &embed.description {
+		preview = preview.description(description);
+	}
+	for field in &embed.fields {
+		preview = preview.field(field.clone());
+	}
+	let image_url = embed
+		.image
+		.as_ref()
+		.map(|image| &image.url)
+		.or(embed.thumbnail.as_ref().map(|thumbnail| &thumbnail.url));
+	if let Some(source) = image_url.and_then(|url| ImageSource::url(url).ok()) {
+		preview = preview.image(source);
+	}
+	if let Some(footer) = &embed.footer {
+		preview = preview.footer(EmbedFooter {
+			proxy_icon_url: None,
+			..footer.clone()
+		});
+	}
+
+	let preview = preview.validate().ok()?.build();
+	let is_empty = preview.title.is_none()
+		&& preview.description.is_none()
+		&& preview.fields.is_empty()
+		&& preview.image.is_none();
+	(!is_empty).then_some(preview)
+}
+
+/// Previews of as many embeds as fit in a message alongside the embeds it already has.
+pub fn embed_previews(embeds: &[Embed], existing: &[Embed]) -> Vec<Embed> {
+	let mut total_chars: usize = existing.iter().map(embed_chars).sum();
+	let mut previews = Vec::new();
+	for preview in embeds.iter().filter_map(embed_preview) {
+		total_chars += embed_chars(&preview);
+		if existing.len() + previews.len() == EMBED_COUNT_LIMIT || total_chars > EMBED_TOTAL_LENGTH
+		{
+			break;
+		}
+		previews.push(preview);
+	}
+	previews
+}
+
+/// Shortens text to at most `max_chars` characters, ending it with `…` if it was cut off.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+	if text.chars().count() <= max_chars {
+		return text.to_owned();
+	}
+	let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+	truncated.push('…');
+	truncated
+}
+
+/// Escapes text so that discord doesn't interpret it as markdown.
+pub fn escape_markdown(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|') {
+			escaped.push('\\');
+		}
+		escaped.push(c);
+	}
+	escaped
+}
+
+/// Shows word-level changes between two versions of a message, with removed words ~~crossed out~~
+/// and added words in **bold**.
+pub fn text_diff(old: &str, new: &str) -> String {
+	fn push_run(output: &mut String, tag: ChangeTag, text: &str) {
+		let marker = match tag {
+			ChangeTag::Equal => "",
+			ChangeTag::Delete => "~~",
+			ChangeTag::Insert => "**",
+		};
+		// keep surrounding whitespace outside of the markers, or discord won't render them
+		let trimmed = text.trim();
+		if marker.is_empty() || trimmed.is_empty() {
+			output.push_str(&escape_markdown(text));
+			return;
+		}
+		let start = text.len() - text.trim_start().len();
+		let end = start + trimmed.len();
+		output.push_str(&text[..start]);
+		output.push_str(marker);
+		output.push_str(&escape_markdown(trimmed));
+		output.push_str(marker);
+		output.push_str(&text[end..]);
+	}
+
+	let mut output = String::new();
+	let mut pending = String::new();
+	let mut pending_tag = ChangeTag::Equal;
+
+	for change in TextDiff::from_words(old, new).iter_all_changes() {
+		if change.tag() != pending_tag {
+			push_run(&mut output, pending_tag, &pending);
+			pending.clear();
+			pending_tag = change.tag();
+		}
+		pending.push_str(change.value());
+	}
+	push_run(&mut output, pending_tag, &pending);
+
+	output
+}
@@ -11,3 +11,6 @@ pub use tasks::*;
 
 mod sqlite_id;
 pub use sqlite_id::*;
+
+mod pg_id;
+pub use pg_id::*;
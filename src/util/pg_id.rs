@@ -0,0 +1,71 @@
+use anyhow::Context;
+use sqlx::{
+	postgres::{PgArgumentBuffer, PgRow, PgTypeInfo, PgValueRef},
+	Decode, Encode, FromRow, Postgres, Row, Type,
+};
+use std::ops::{Deref, DerefMut};
+use twilight::id::Id;
+
+/// Postgres doesn't support storing [`u64`] directly, so this wrapper stores it as a `BIGINT`.
+///
+/// This is the Postgres counterpart to [`SqliteId`](super::SqliteId).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PgId<T>(pub T);
+
+impl<T> From<T> for PgId<T> {
+	fn from(value: T) -> Self {
+		Self(value)
+	}
+}
+
+impl<T> Deref for PgId<Id<T>> {
+	type Target = Id<T>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for PgId<Id<T>> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl<T> Type<Postgres> for PgId<Id<T>> {
+	fn compatible(ty: &PgTypeInfo) -> bool {
+		<i64 as Type<Postgres>>::compatible(ty)
+	}
+
+	fn type_info() -> PgTypeInfo {
+		<i64 as Type<Postgres>>::type_info()
+	}
+}
+
+impl<'q, T> Encode<'q, Postgres> for PgId<Id<T>> {
+	fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> sqlx::encode::IsNull {
+		<i64 as Encode<Postgres>>::encode_by_ref(&(self.0.get() as i64), buf)
+	}
+
+	fn produces(&self) -> Option<PgTypeInfo> {
+		<i64 as Encode<Postgres>>::produces(&(self.0.get() as i64))
+	}
+
+	fn size_hint(&self) -> usize {
+		<i64 as Encode<Postgres>>::size_hint(&(self.0.get() as i64))
+	}
+}
+
+impl<'r, T> Decode<'r, Postgres> for PgId<Id<T>> {
+	fn decode(value: PgValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+		let value = <i64 as Decode<Postgres>>::decode(value)? as u64;
+		let id = Id::new_checked(value).context("value cannot be 0")?;
+		Ok(Self(id))
+	}
+}
+
+impl<'r, T> FromRow<'r, PgRow> for PgId<Id<T>> {
+	fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+		row.try_get(0)
+	}
+}
@@ -32,38 +32,38 @@ impl<T> DerefMut for SqliteId<Id<T>> {
 
 impl<T> Type<Sqlite> for SqliteId<Id<T>> {
 	fn compatible(ty: &SqliteTypeInfo) -> bool {
-		i64::compatible(ty)
+		<i64 as Type<Sqlite>>::compatible(ty)
 	}
 
 	fn type_info() -> SqliteTypeInfo {
-		i64::type_info()
+		<i64 as Type<Sqlite>>::type_info()
 	}
 }
 
 impl<'q, T> Encode<'q, Sqlite> for SqliteId<Id<T>> {
 	fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue>) -> sqlx::encode::IsNull {
-		(self.0.get() as i64).encode_by_ref(buf)
+		<i64 as Encode<Sqlite>>::encode_by_ref(&(self.0.get() as i64), buf)
 	}
 
 	fn encode(self, buf: &mut Vec<SqliteArgumentValue>) -> sqlx::encode::IsNull
 	where
 		Self: Sized,
 	{
-		(self.0.get() as i64).encode(buf)
+		<i64 as Encode<Sqlite>>::encode(self.0.get() as i64, buf)
 	}
 
 	fn produces(&self) -> Option<SqliteTypeInfo> {
-		(self.0.get() as i64).produces()
+		<i64 as Encode<Sqlite>>::produces(&(self.0.get() as i64))
 	}
 
 	fn size_hint(&self) -> usize {
-		(self.0.get() as i64).size_hint()
+		<i64 as Encode<Sqlite>>::size_hint(&(self.0.get() as i64))
 	}
 }
 
 impl<'r, T> Decode<'r, Sqlite> for SqliteId<Id<T>> {
 	fn decode(value: SqliteValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
-		let value = i64::decode(value)? as u64;
+		let value = <i64 as Decode<Sqlite>>::decode(value)? as u64;
 		let id = Id::new_checked(value).context("value cannot be 0")?;
 		Ok(Self(id))
 	}